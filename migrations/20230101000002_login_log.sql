@@ -1 +1,13 @@
--- Add migration script here
+-- 로그인 시도 기록. 없는 이메일로 시도한 경우 user_id 는 NULL
+CREATE TABLE login_log (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    email VARCHAR(100) NOT NULL,
+    success BOOLEAN NOT NULL,
+    reason VARCHAR(50),
+    ip_address VARCHAR(45),
+    user_agent VARCHAR(255),
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX login_log_user_id_idx ON login_log (user_id, attempted_at);
@@ -0,0 +1,6 @@
+-- create_user 가 저장하는 솔트와 로그인 실패 횟수, 잠금 시각
+ALTER TABLE users ADD COLUMN IF NOT EXISTS salt_key VARCHAR(100) NOT NULL DEFAULT '';
+ALTER TABLE users ADD COLUMN failed_logins INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX users_email_idx ON users (email);
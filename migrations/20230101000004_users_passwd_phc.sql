@@ -0,0 +1,2 @@
+-- Argon2id PHC 문자열은 매개변수와 솔트를 함께 담아 100 자를 넘을 수 있다
+ALTER TABLE users ALTER COLUMN passwd TYPE VARCHAR(255);
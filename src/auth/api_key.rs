@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+
+// 키 원문 대신 SHA-256 다이제스트로 보관하고 찾는다
+#[derive(Clone, Default)]
+pub struct ApiKeyStore {
+    keys: HashMap<[u8; 32], String>,
+}
+
+impl std::fmt::Debug for ApiKeyStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeyStore").field("keys", &self.keys.len()).finish()
+    }
+}
+
+fn digest(key: &str) -> [u8; 32] {
+    Sha256::digest(key.as_bytes()).into()
+}
+
+impl ApiKeyStore {
+    pub fn insert(&mut self, subject: &str, key: &str) {
+        self.keys.insert(digest(key), subject.to_string());
+    }
+
+    // AUTH_API_KEYS=이름:키,이름:키
+    pub fn parse(spec: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut store = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            match entry.split_once(':') {
+                Some((subject, key)) if !subject.trim().is_empty() && !key.trim().is_empty() => {
+                    store.insert(subject.trim(), key.trim())
+                }
+                // 키가 로그에 남지 않도록 항목 내용은 싣지 않는다
+                _ => return Err("Invalid AUTH_API_KEYS entry, expected name:key".into()),
+            }
+        }
+        Ok(store)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    // 키에 해당하는 주체 이름
+    pub fn verify(&self, key: &str) -> Option<&str> {
+        self.keys.get(&digest(key)).map(String::as_str)
+    }
+}
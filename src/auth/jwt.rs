@@ -0,0 +1,242 @@
+use chrono::Utc;
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
+// 외부에서 발급한 토큰에는 token_type 이 없으므로 액세스 토큰으로 본다
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<TokenType>,
+}
+
+impl Claims {
+    pub fn is_refresh(&self) -> bool {
+        self.token_type == Some(TokenType::Refresh)
+    }
+}
+
+// 알고리즘마다 키를 따로 두어 토큰 헤더의 alg 로 다른 키를 쓰게 만들 수 없게 한다
+#[derive(Clone, Default)]
+pub struct JwtVerifier {
+    hs256: Option<DecodingKey>,
+    rs256: Option<DecodingKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+    leeway: u64,
+}
+
+impl std::fmt::Debug for JwtVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtVerifier")
+            .field("hs256", &self.hs256.is_some())
+            .field("rs256", &self.rs256.is_some())
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+impl JwtVerifier {
+    pub fn with_hs256_secret(mut self, secret: &[u8]) -> Self {
+        self.hs256 = Some(DecodingKey::from_secret(secret));
+        self
+    }
+
+    pub fn with_rs256_public_key(mut self, pem: &[u8]) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        self.rs256 = Some(DecodingKey::from_rsa_pem(pem)?);
+        Ok(self)
+    }
+
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    pub fn with_audience(mut self, audience: Option<String>) -> Self {
+        self.audience = audience;
+        self
+    }
+
+    // JWT_HS256_SECRET, JWT_RS256_PUBLIC_KEY_FILE (PEM), JWT_ISSUER, JWT_AUDIENCE, JWT_LEEWAY_SECS
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut verifier = Self::default()
+            .with_issuer(env("JWT_ISSUER"))
+            .with_audience(env("JWT_AUDIENCE"));
+        if let Some(secret) = env("JWT_HS256_SECRET") {
+            verifier = verifier.with_hs256_secret(secret.as_bytes());
+        }
+        if let Some(path) = env("JWT_RS256_PUBLIC_KEY_FILE") {
+            verifier = verifier.with_rs256_public_key(&std::fs::read(&path)?)?;
+        }
+        if let Some(leeway) = env("JWT_LEEWAY_SECS") {
+            verifier.leeway = leeway.parse()?;
+        }
+        Ok(verifier)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.hs256.is_some() || self.rs256.is_some()
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Claims, Box<dyn std::error::Error + Send + Sync>> {
+        let header = decode_header(token)?;
+        let key = match header.alg {
+            Algorithm::HS256 => self.hs256.as_ref(),
+            Algorithm::RS256 => self.rs256.as_ref(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("Unsupported token algorithm: {:?}", header.alg))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = self.leeway;
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        Ok(decode::<Claims>(token, key, &validation)?.claims)
+    }
+}
+
+// iss, aud 는 검증 쪽 설정과 같게 붙인다
+#[derive(Serialize)]
+struct IssuedClaims<'a> {
+    #[serde(flatten)]
+    claims: &'a Claims,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aud: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: &'static str,
+    // 액세스 토큰 유효 시간(초)
+    pub expires_in: u64,
+}
+
+#[derive(Clone)]
+pub struct TokenIssuer {
+    algorithm: Algorithm,
+    key: EncodingKey,
+    issuer: Option<String>,
+    audience: Option<String>,
+    access_ttl: u64,
+    refresh_ttl: u64,
+}
+
+impl std::fmt::Debug for TokenIssuer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenIssuer")
+            .field("algorithm", &self.algorithm)
+            .field("access_ttl", &self.access_ttl)
+            .field("refresh_ttl", &self.refresh_ttl)
+            .finish()
+    }
+}
+
+impl TokenIssuer {
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            key: EncodingKey::from_secret(secret),
+            issuer: None,
+            audience: None,
+            access_ttl: 15 * 60,
+            refresh_ttl: 7 * 24 * 60 * 60,
+        }
+    }
+
+    pub fn rs256(private_pem: &[u8]) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            algorithm: Algorithm::RS256,
+            key: EncodingKey::from_rsa_pem(private_pem)?,
+            ..Self::hs256(b"")
+        })
+    }
+
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    pub fn with_audience(mut self, audience: Option<String>) -> Self {
+        self.audience = audience;
+        self
+    }
+
+    // JWT_RS256_PRIVATE_KEY_FILE 가 있으면 RS256, 없고 JWT_HS256_SECRET 이 있으면 HS256 으로 서명한다.
+    // JWT_ACCESS_TTL_SECS (기본 900), JWT_REFRESH_TTL_SECS (기본 7일)
+    pub fn from_env() -> Result<Option<Self>, Box<dyn std::error::Error + Send + Sync>> {
+        let issuer = if let Some(path) = env("JWT_RS256_PRIVATE_KEY_FILE") {
+            Self::rs256(&std::fs::read(&path)?)?
+        } else if let Some(secret) = env("JWT_HS256_SECRET") {
+            Self::hs256(secret.as_bytes())
+        } else {
+            return Ok(None);
+        };
+        let mut issuer = issuer.with_issuer(env("JWT_ISSUER")).with_audience(env("JWT_AUDIENCE"));
+        if let Some(ttl) = env("JWT_ACCESS_TTL_SECS") {
+            issuer.access_ttl = ttl.parse()?;
+        }
+        if let Some(ttl) = env("JWT_REFRESH_TTL_SECS") {
+            issuer.refresh_ttl = ttl.parse()?;
+        }
+        Ok(Some(issuer))
+    }
+
+    fn sign(
+        &self,
+        subject: &str,
+        roles: &[String],
+        token_type: TokenType,
+        ttl: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let now = Utc::now().timestamp().max(0) as u64;
+        let claims = Claims {
+            sub: subject.to_string(),
+            exp: now + ttl,
+            iat: Some(now),
+            jti: Some(Uuid::new_v4().to_string()),
+            roles: roles.to_vec(),
+            token_type: Some(token_type),
+        };
+        let issued = IssuedClaims { claims: &claims, iss: self.issuer.as_deref(), aud: self.audience.as_deref() };
+        Ok(encode(&Header::new(self.algorithm), &issued, &self.key)?)
+    }
+
+    pub fn issue(&self, subject: &str, roles: &[String]) -> Result<TokenPair, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(TokenPair {
+            access_token: self.sign(subject, roles, TokenType::Access, self.access_ttl)?,
+            refresh_token: self.sign(subject, roles, TokenType::Refresh, self.refresh_ttl)?,
+            token_type: "Bearer",
+            expires_in: self.access_ttl,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use serde::Deserialize;
+use std::sync::OnceLock;
+use sqlx::PgPool;
+use crate::utils::passwd::{passwd_hash, Passwd};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub passwd: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+// 연속 실패 max_failures 번이면 lockout_secs 동안 잠근다
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoginPolicy {
+    pub max_failures: i32,
+    pub lockout_secs: f64,
+}
+
+impl Default for LoginPolicy {
+    fn default() -> Self {
+        Self { max_failures: 5, lockout_secs: 900.0 }
+    }
+}
+
+impl LoginPolicy {
+    // LOGIN_MAX_FAILURES, LOGIN_LOCKOUT_SECS
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut policy = Self::default();
+        if let Ok(value) = std::env::var("LOGIN_MAX_FAILURES") {
+            policy.max_failures = value.parse()?;
+        }
+        if let Ok(value) = std::env::var("LOGIN_LOCKOUT_SECS") {
+            policy.lockout_secs = value.parse()?;
+        }
+        if policy.max_failures < 1 {
+            return Err("LOGIN_MAX_FAILURES must be at least 1".into());
+        }
+        Ok(policy)
+    }
+}
+
+// 없는 이메일로 로그인할 때도 이 해시로 비밀번호를 확인해 응답 시간으로 가입 여부가 드러나지 않게 한다.
+// 처음 쓸 때 만들므로 블로킹 스레드에서 부른다
+pub fn dummy_passwd() -> &'static Passwd {
+    static DUMMY: OnceLock<Passwd> = OnceLock::new();
+    DUMMY.get_or_init(|| passwd_hash("dummy passwd for unknown users").expect("Failed to hash the dummy password"))
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct UserCredentials {
+    pub id: i32,
+    pub email: String,
+    pub passwd: String,
+    pub salt_key: String,
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoginAttempt {
+    pub user_id: Option<i32>,
+    pub email: String,
+    pub success: bool,
+    pub reason: Option<&'static str>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+pub async fn find_user(pool: &PgPool, email: &str) -> Result<Option<UserCredentials>, sqlx::Error> {
+    sqlx::query_as::<_, UserCredentials>(
+        "SELECT id, email, passwd, salt_key, COALESCE(locked_until > NOW(), FALSE) AS locked FROM users WHERE email = $1",
+    )
+    .bind(email)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn record_attempt(pool: &PgPool, attempt: &LoginAttempt) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO login_log (user_id, email, success, reason, ip_address, user_agent) VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(attempt.user_id)
+    .bind(&attempt.email)
+    .bind(attempt.success)
+    .bind(attempt.reason)
+    .bind(&attempt.ip_address)
+    .bind(&attempt.user_agent)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// 실패 횟수를 올리고 한도에 닿으면 잠근다. 잠금이 풀린 뒤의 첫 실패는 1 부터 다시 센다
+pub async fn register_failure(pool: &PgPool, user_id: i32, policy: &LoginPolicy) -> Result<bool, sqlx::Error> {
+    let (locked,): (bool,) = sqlx::query_as(
+        "UPDATE users SET \
+         failed_logins = CASE WHEN locked_until <= NOW() THEN 1 ELSE failed_logins + 1 END, \
+         locked_until = CASE WHEN (CASE WHEN locked_until <= NOW() THEN 1 ELSE failed_logins + 1 END) >= $2 \
+         THEN NOW() + make_interval(secs => $3) ELSE NULL END \
+         WHERE id = $1 RETURNING locked_until IS NOT NULL",
+    )
+    .bind(user_id)
+    .bind(policy.max_failures)
+    .bind(policy.lockout_secs)
+    .fetch_one(pool)
+    .await?;
+    Ok(locked)
+}
+
+// 로그인 때 예전 형식 해시를 새 형식으로 바꿔 저장한다
+pub async fn store_passwd(pool: &PgPool, user_id: i32, passwd: &Passwd) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE users SET passwd = $2, salt_key = $3 WHERE id = $1")
+        .bind(user_id)
+        .bind(&passwd.wd)
+        .bind(&passwd.salt)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn reset_failures(pool: &PgPool, user_id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE users SET failed_logins = 0, locked_until = NULL WHERE id = $1")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
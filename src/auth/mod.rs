@@ -0,0 +1,209 @@
+pub mod api_key;
+pub mod jwt;
+pub mod login;
+pub mod password;
+pub mod rbac;
+
+use std::sync::Arc;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::{header, request::Parts};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::Serialize;
+use crate::error::AppError;
+
+pub use api_key::ApiKeyStore;
+pub use jwt::{Claims, JwtVerifier, TokenIssuer, TokenPair, TokenType};
+pub use login::LoginPolicy;
+pub use password::ResetPolicy;
+pub use rbac::{AccessControl, Permission, Role, Subject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+    Anonymous,
+}
+
+// 인증된 호출자. 핸들러에서 Principal 을 인자로 받아 쓴다
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Principal {
+    pub subject: String,
+    pub method: AuthMethod,
+    pub roles: Vec<String>,
+}
+
+impl Principal {
+    pub fn anonymous() -> Self {
+        Self { subject: "anonymous".into(), method: AuthMethod::Anonymous, roles: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct AuthInner {
+    api_keys: ApiKeyStore,
+    jwt: JwtVerifier,
+    issuer: Option<TokenIssuer>,
+    login_policy: LoginPolicy,
+    reset_policy: ResetPolicy,
+    allow_anonymous: bool,
+}
+
+// 기본값은 아무 자격 증명도 받지 않고 익명 접근도 막는다
+#[derive(Debug, Clone, Default)]
+pub struct Authenticator {
+    inner: Arc<AuthInner>,
+}
+
+impl Authenticator {
+    pub fn new(api_keys: ApiKeyStore, jwt: JwtVerifier, allow_anonymous: bool) -> Self {
+        Self { inner: Arc::new(AuthInner { api_keys, jwt, allow_anonymous, ..Default::default() }) }
+    }
+
+    // POST /login 에서 토큰을 발급할 때 쓴다
+    pub fn with_issuer(self, issuer: Option<TokenIssuer>) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.issuer = issuer;
+        Self { inner: Arc::new(inner) }
+    }
+
+    pub fn with_login_policy(self, login_policy: LoginPolicy) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.login_policy = login_policy;
+        Self { inner: Arc::new(inner) }
+    }
+
+    pub fn with_reset_policy(self, reset_policy: ResetPolicy) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.reset_policy = reset_policy;
+        Self { inner: Arc::new(inner) }
+    }
+
+    // AUTH_API_KEYS, AUTH_ALLOW_ANONYMOUS 와 JwtVerifier, TokenIssuer, LoginPolicy, ResetPolicy 의 from_env 설정
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let api_keys = match std::env::var("AUTH_API_KEYS") {
+            Ok(spec) => ApiKeyStore::parse(&spec)?,
+            Err(_) => ApiKeyStore::default(),
+        };
+        let allow_anonymous = std::env::var("AUTH_ALLOW_ANONYMOUS")
+            .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        Ok(Self::new(api_keys, JwtVerifier::from_env()?, allow_anonymous)
+            .with_issuer(TokenIssuer::from_env()?)
+            .with_login_policy(LoginPolicy::from_env()?)
+            .with_reset_policy(ResetPolicy::from_env()?))
+    }
+
+    pub fn jwt(&self) -> &JwtVerifier {
+        &self.inner.jwt
+    }
+
+    pub fn issuer(&self) -> Option<&TokenIssuer> {
+        self.inner.issuer.as_ref()
+    }
+
+    pub fn login_policy(&self) -> &LoginPolicy {
+        &self.inner.login_policy
+    }
+
+    pub fn reset_policy(&self) -> &ResetPolicy {
+        &self.inner.reset_policy
+    }
+
+    // 자격 증명이 있는데 틀렸으면 익명 허용 여부와 관계없이 거부한다
+    pub fn authenticate(&self, parts: &Parts) -> Result<Principal, AppError> {
+        if let Some(key) = parts.headers.get("x-api-key") {
+            let key = key.to_str().map_err(|_| AppError::unauthorized("Invalid API key".into()))?;
+            return self
+                .inner
+                .api_keys
+                .verify(key)
+                .map(|subject| Principal { subject: subject.to_string(), method: AuthMethod::ApiKey, roles: Vec::new() })
+                .ok_or_else(|| AppError::unauthorized("Invalid API key".into()));
+        }
+        if let Some(authorization) = parts.headers.get(header::AUTHORIZATION) {
+            let token = authorization
+                .to_str()
+                .ok()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .ok_or_else(|| AppError::unauthorized("Expected a Bearer token".into()))?;
+            if !self.inner.jwt.is_enabled() {
+                return Err(AppError::unauthorized("Bearer tokens are not enabled".into()));
+            }
+            let claims = self
+                .inner
+                .jwt
+                .verify(token.trim())
+                .map_err(|e| AppError::unauthorized(format!("Invalid token: {}", e)))?;
+            if claims.is_refresh() {
+                return Err(AppError::unauthorized("Refresh tokens cannot be used for API access".into()));
+            }
+            return Ok(Principal { subject: claims.sub, method: AuthMethod::Jwt, roles: claims.roles });
+        }
+        if self.inner.allow_anonymous {
+            return Ok(Principal::anonymous());
+        }
+        Err(AppError::unauthorized("Authentication required".into()))
+    }
+}
+
+// routes::create_routes() 앞에 붙이는 미들웨어. 인증된 Principal 을 요청 확장에 넣는다
+pub async fn require_auth(
+    State(authenticator): State<Authenticator>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let (mut parts, body) = request.into_parts();
+    let principal = authenticator.authenticate(&parts)?;
+    parts.extensions.insert(principal);
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Principal {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or_else(|| AppError::unauthorized("Authentication required".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    fn parts(name: &str, value: &str) -> Parts {
+        Request::builder().header(name, value).body(()).unwrap().into_parts().0
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let api_keys = ApiKeyStore::parse("reporting:s3cret").unwrap();
+        let jwt = JwtVerifier::default().with_hs256_secret(b"signing-key");
+        let authenticator = Authenticator::new(api_keys, jwt, false);
+
+        let principal = authenticator.authenticate(&parts("x-api-key", "s3cret")).unwrap();
+        assert_eq!(principal.subject, "reporting");
+        assert!(authenticator.authenticate(&parts("x-api-key", "wrong")).is_err());
+
+        let claims = Claims { sub: "alice".into(), exp: 4_102_444_800, roles: vec!["admin".into()], ..Default::default() };
+        let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(b"signing-key")).unwrap();
+        let principal = authenticator.authenticate(&parts("authorization", &format!("Bearer {}", token))).unwrap();
+        assert_eq!(principal, Principal { subject: "alice".into(), method: AuthMethod::Jwt, roles: vec!["admin".into()] });
+
+        let forged = encode(&Header::default(), &claims, &EncodingKey::from_secret(b"other-key")).unwrap();
+        assert!(authenticator.authenticate(&parts("authorization", &format!("Bearer {}", forged))).is_err());
+        assert!(authenticator.authenticate(&Request::new(()).into_parts().0).is_err());
+
+        let tokens = TokenIssuer::hs256(b"signing-key").issue("bob", &[]).unwrap();
+        let principal = authenticator.authenticate(&parts("authorization", &format!("Bearer {}", tokens.access_token))).unwrap();
+        assert_eq!(principal.subject, "bob");
+        assert!(authenticator.authenticate(&parts("authorization", &format!("Bearer {}", tokens.refresh_token))).is_err());
+    }
+}
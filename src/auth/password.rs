@@ -0,0 +1,131 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+const MIN_PASSWD_LEN: usize = 8;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub old_passwd: String,
+    pub new_passwd: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResetConfirmRequest {
+    pub token: String,
+    pub new_passwd: String,
+}
+
+// 재설정 토큰 유효 시간과 메일에 넣을 링크. url_template 의 {token} 을 토큰으로 바꾼다
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResetPolicy {
+    pub ttl_secs: f64,
+    pub url_template: Option<String>,
+}
+
+impl Default for ResetPolicy {
+    fn default() -> Self {
+        Self { ttl_secs: 3600.0, url_template: None }
+    }
+}
+
+impl ResetPolicy {
+    // PASSWORD_RESET_TTL_SECS, PASSWORD_RESET_URL
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut policy = Self::default();
+        if let Ok(value) = std::env::var("PASSWORD_RESET_TTL_SECS") {
+            policy.ttl_secs = value.parse()?;
+        }
+        policy.url_template = std::env::var("PASSWORD_RESET_URL").ok().filter(|url| !url.is_empty());
+        Ok(policy)
+    }
+
+    pub fn mail_body(&self, token: &str) -> String {
+        let minutes = (self.ttl_secs / 60.0).ceil() as u64;
+        let action = match &self.url_template {
+            Some(template) => format!("Open this link to choose a new password:\n\n{}", template.replace("{token}", token)),
+            None => format!("Use this token to choose a new password:\n\n{}", token),
+        };
+        format!(
+            "{}\n\nThe link expires in {} minutes and can be used once. \
+             If you did not ask to reset your password, ignore this message.",
+            action, minutes
+        )
+    }
+}
+
+pub fn validate_new_passwd(passwd: &str) -> Result<(), String> {
+    if passwd.chars().count() < MIN_PASSWD_LEN {
+        return Err(format!("Password must be at least {} characters", MIN_PASSWD_LEN));
+    }
+    Ok(())
+}
+
+// 32 바이트 난수의 16 진수. 원문은 메일로만 보내고 데이터베이스에는 해시만 둔다
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn token_hash(token: &str) -> String {
+    Sha256::digest(token.trim().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 새 토큰을 만들면 아직 쓰지 않은 이전 토큰은 쓸 수 없게 한다
+pub async fn create_reset_token(pool: &PgPool, user_id: i32, policy: &ResetPolicy) -> Result<String, sqlx::Error> {
+    let token = generate_token();
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) \
+         VALUES ($1, $2, NOW() + make_interval(secs => $3))",
+    )
+    .bind(user_id)
+    .bind(token_hash(&token))
+    .bind(policy.ttl_secs)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(token)
+}
+
+// 유효한 토큰을 사용 처리하고 사용자 ID 를 돌려준다. 한 문장으로 처리해 같은 토큰을 두 번 쓸 수 없다
+pub async fn consume_reset_token(pool: &PgPool, token: &str) -> Result<Option<i32>, sqlx::Error> {
+    let row: Option<(i32,)> = sqlx::query_as(
+        "UPDATE password_reset_tokens SET used_at = NOW() \
+         WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW() RETURNING user_id",
+    )
+    .bind(token_hash(token))
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(user_id,)| user_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_token_and_mail_body() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+        assert_eq!(token_hash(&token), token_hash(&format!(" {}\n", token)));
+
+        let policy = ResetPolicy { ttl_secs: 1800.0, url_template: Some("https://app/reset?token={token}".into()) };
+        let body = policy.mail_body("abc");
+        assert!(body.contains("https://app/reset?token=abc"));
+        assert!(body.contains("30 minutes"));
+        assert!(validate_new_passwd("short").is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use crate::auth::{AuthMethod, Principal};
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Analyst,
+    Admin,
+}
+
+impl Role {
+    // JWT roles 클레임의 이름. 모르는 이름은 무시한다
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "viewer" => Some(Self::Viewer),
+            "analyst" => Some(Self::Analyst),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        match self {
+            Self::Viewer => permission == Permission::Read,
+            Self::Analyst => matches!(permission, Permission::Read | Permission::Write | Permission::RegisterConnection),
+            Self::Admin => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    // 새 연결 등록 (전역)
+    RegisterConnection,
+    // 조회 쿼리
+    Read,
+    // DML, DDL
+    Write,
+    // 연결 해제와 그 연결에 대한 권한 부여
+    ManageConnection,
+    // 사용자, 팀, 전역 권한 관리 (전역)
+    ManageUsers,
+}
+
+impl Permission {
+    // 연결 단위 권한으로는 주어지지 않는 권한
+    fn is_global(&self) -> bool {
+        matches!(self, Self::RegisterConnection | Self::ManageUsers)
+    }
+}
+
+// 사용자(Principal 의 subject) 또는 팀
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Subject {
+    User(String),
+    Team(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateGrantRequest {
+    pub subject: Subject,
+    pub role: Role,
+    // 없으면 모든 연결에 적용되는 전역 권한
+    pub connection_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Grant {
+    pub id: String,
+    pub subject: Subject,
+    pub role: Role,
+    pub connection_id: Option<String>,
+    pub granted_by: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Team {
+    pub name: String,
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct AccessState {
+    grants: Vec<Grant>,
+    teams: HashMap<String, HashSet<String>>,
+    // 연결을 등록한 사용자나 팀. 소유자는 그 연결의 admin 이다
+    owners: HashMap<String, Subject>,
+}
+
+impl AccessState {
+    fn matches(&self, subject: &Subject, principal: &Principal) -> bool {
+        match subject {
+            Subject::User(name) => *name == principal.subject,
+            Subject::Team(team) => self.teams.get(team).is_some_and(|members| members.contains(&principal.subject)),
+        }
+    }
+
+    // 토큰의 역할, 전역 권한, (connection_id 가 있으면) 그 연결의 권한과 소유 관계를 합친다
+    fn roles(&self, principal: &Principal, connection_id: Option<&str>) -> HashSet<Role> {
+        let mut roles = principal.roles.iter().filter_map(|name| Role::parse(name)).collect::<HashSet<_>>();
+        roles.extend(
+            self.grants
+                .iter()
+                .filter(|grant| grant.connection_id.is_none() || grant.connection_id.as_deref() == connection_id)
+                .filter(|grant| self.matches(&grant.subject, principal))
+                .map(|grant| grant.role),
+        );
+        if let Some(owner) = connection_id.and_then(|id| self.owners.get(id)) {
+            if self.matches(owner, principal) {
+                roles.insert(Role::Admin);
+            }
+        }
+        roles
+    }
+
+    fn allows(&self, principal: &Principal, permission: Permission, connection_id: Option<&str>) -> bool {
+        let scope = if permission.is_global() { None } else { connection_id };
+        self.roles(principal, scope).iter().any(|role| role.allows(permission))
+    }
+}
+
+// 권한 정보는 연결 목록처럼 프로세스 메모리에 둔다. RBAC_ADMINS 의 주체는 전역 admin 으로 시작한다
+#[derive(Debug, Clone, Default)]
+pub struct AccessControl {
+    state: Arc<RwLock<AccessState>>,
+}
+
+impl AccessControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // RBAC_ADMINS=주체,주체
+    pub async fn from_env() -> Self {
+        let access = Self::new();
+        if let Ok(admins) = std::env::var("RBAC_ADMINS") {
+            for admin in admins.split(',').map(str::trim).filter(|admin| !admin.is_empty()) {
+                let request = CreateGrantRequest { subject: Subject::User(admin.to_string()), role: Role::Admin, connection_id: None };
+                access.grant(request, "RBAC_ADMINS").await;
+            }
+        }
+        access
+    }
+
+    pub async fn allows(&self, principal: &Principal, permission: Permission, connection_id: Option<&str>) -> bool {
+        self.state.read().await.allows(principal, permission, connection_id)
+    }
+
+    pub async fn authorize(
+        &self,
+        principal: &Principal,
+        permission: Permission,
+        connection_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        if self.allows(principal, permission, connection_id).await {
+            return Ok(());
+        }
+        let scope = connection_id.map(|id| format!(" on connection {}", id)).unwrap_or_default();
+        Err(AppError::forbidden(format!(
+            "{} is not allowed to {:?}{}",
+            principal.subject, permission, scope
+        )))
+    }
+
+    // 로그인 때 발급하는 토큰의 roles 클레임. 사용자와 그 팀의 전역 권한 (소문자 역할 이름)
+    pub async fn global_roles(&self, subject: &str) -> Vec<String> {
+        let principal = Principal { subject: subject.to_string(), method: AuthMethod::Jwt, roles: Vec::new() };
+        let mut roles = self
+            .state
+            .read()
+            .await
+            .roles(&principal, None)
+            .into_iter()
+            .filter_map(|role| serde_json::to_value(role).ok()?.as_str().map(str::to_string))
+            .collect::<Vec<_>>();
+        roles.sort();
+        roles
+    }
+
+    pub async fn is_team_member(&self, team: &str, subject: &str) -> bool {
+        self.state.read().await.teams.get(team).is_some_and(|members| members.contains(subject))
+    }
+
+    pub async fn set_owner(&self, connection_id: &str, owner: Subject) {
+        self.state.write().await.owners.insert(connection_id.to_string(), owner);
+    }
+
+    pub async fn owner(&self, connection_id: &str) -> Option<Subject> {
+        self.state.read().await.owners.get(connection_id).cloned()
+    }
+
+    // 연결을 해제하면 소유 관계와 그 연결에 대한 권한도 지운다
+    pub async fn forget_connection(&self, connection_id: &str) {
+        let mut state = self.state.write().await;
+        state.owners.remove(connection_id);
+        state.grants.retain(|grant| grant.connection_id.as_deref() != Some(connection_id));
+    }
+
+    pub async fn grant(&self, request: CreateGrantRequest, granted_by: &str) -> Grant {
+        let grant = Grant {
+            id: Uuid::new_v4().to_string(),
+            subject: request.subject,
+            role: request.role,
+            connection_id: request.connection_id,
+            granted_by: granted_by.to_string(),
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        };
+        self.state.write().await.grants.push(grant.clone());
+        grant
+    }
+
+    pub async fn get_grant(&self, id: &str) -> Option<Grant> {
+        self.state.read().await.grants.iter().find(|grant| grant.id == id).cloned()
+    }
+
+    pub async fn revoke(&self, id: &str) -> Option<Grant> {
+        let mut state = self.state.write().await;
+        let index = state.grants.iter().position(|grant| grant.id == id)?;
+        Some(state.grants.remove(index))
+    }
+
+    // connection_id 가 있으면 그 연결에 대한 권한만
+    pub async fn list_grants(&self, connection_id: Option<&str>) -> Vec<Grant> {
+        let state = self.state.read().await;
+        state
+            .grants
+            .iter()
+            .filter(|grant| connection_id.is_none() || grant.connection_id.as_deref() == connection_id)
+            .cloned()
+            .collect()
+    }
+
+    pub async fn add_team_member(&self, team: &str, subject: &str) {
+        let mut state = self.state.write().await;
+        state.teams.entry(team.to_string()).or_default().insert(subject.to_string());
+    }
+
+    pub async fn remove_team_member(&self, team: &str, subject: &str) -> bool {
+        let mut state = self.state.write().await;
+        let Some(members) = state.teams.get_mut(team) else {
+            return false;
+        };
+        let removed = members.remove(subject);
+        if members.is_empty() {
+            state.teams.remove(team);
+        }
+        removed
+    }
+
+    pub async fn list_teams(&self) -> Vec<Team> {
+        let state = self.state.read().await;
+        let mut teams = state
+            .teams
+            .iter()
+            .map(|(name, members)| {
+                let mut members = members.iter().cloned().collect::<Vec<_>>();
+                members.sort();
+                Team { name: name.clone(), members }
+            })
+            .collect::<Vec<_>>();
+        teams.sort_by(|a, b| a.name.cmp(&b.name));
+        teams
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(subject: &str) -> Principal {
+        Principal { subject: subject.into(), method: AuthMethod::Jwt, roles: Vec::new() }
+    }
+
+    #[tokio::test]
+    async fn test_grants_teams_and_owners() {
+        let access = AccessControl::new();
+        let (alice, bob, carol) = (user("alice"), user("bob"), user("carol"));
+
+        access
+            .grant(CreateGrantRequest { subject: Subject::User("alice".into()), role: Role::Analyst, connection_id: None }, "test")
+            .await;
+        access.add_team_member("data", "bob").await;
+        access
+            .grant(
+                CreateGrantRequest { subject: Subject::Team("data".into()), role: Role::Viewer, connection_id: Some("c1".into()) },
+                "test",
+            )
+            .await;
+        access.set_owner("c2", Subject::User("carol".into())).await;
+
+        assert!(access.allows(&alice, Permission::Write, Some("c1")).await);
+        assert!(access.allows(&alice, Permission::RegisterConnection, None).await);
+        assert!(!access.allows(&alice, Permission::ManageUsers, None).await);
+
+        assert!(access.allows(&bob, Permission::Read, Some("c1")).await);
+        assert!(!access.allows(&bob, Permission::Read, Some("c2")).await);
+        assert!(!access.allows(&bob, Permission::Write, Some("c1")).await);
+
+        // 소유자는 그 연결의 admin 이지만 전역 권한은 없다
+        assert!(access.allows(&carol, Permission::ManageConnection, Some("c2")).await);
+        assert!(!access.allows(&carol, Permission::ManageUsers, Some("c2")).await);
+
+        let token_admin = Principal { roles: vec!["Admin".into()], ..user("dave") };
+        assert!(access.allows(&token_admin, Permission::ManageUsers, None).await);
+
+        // 연결 단위 권한과 소유 관계는 토큰에 넣지 않는다
+        access.add_team_member("ops", "erin").await;
+        access
+            .grant(CreateGrantRequest { subject: Subject::Team("ops".into()), role: Role::Admin, connection_id: None }, "test")
+            .await;
+        assert_eq!(access.global_roles("alice").await, vec!["analyst".to_string()]);
+        assert_eq!(access.global_roles("erin").await, vec!["admin".to_string()]);
+        assert!(access.global_roles("bob").await.is_empty());
+        assert!(access.global_roles("carol").await.is_empty());
+
+        access.forget_connection("c1").await;
+        assert!(!access.allows(&bob, Permission::Read, Some("c1")).await);
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use crate::cache::CacheStore;
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    entries: Arc<RwLock<HashMap<String, (String, Instant)>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CacheStore for MemoryStore {
+    async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let entries = self.entries.read().await;
+        Ok(entries
+            .get(key)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(value, _)| value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut entries = self.entries.write().await;
+        // 쓰기 시점에 만료된 항목을 정리한다
+        let now = Instant::now();
+        entries.retain(|_, (_, expires_at)| *expires_at > now);
+        entries.insert(key.to_string(), (value, now + ttl));
+        Ok(())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut entries = self.entries.write().await;
+        let now = Instant::now();
+        entries.retain(|_, (_, expires_at)| *expires_at > now);
+        let before = entries.len();
+        entries.retain(|key, _| !key.starts_with(prefix));
+        Ok((before - entries.len()) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store_expiry_and_invalidation() {
+        let store = MemoryStore::new();
+        store.set("sqlcache:a:1", "one".into(), Duration::from_secs(60)).await.unwrap();
+        store.set("sqlcache:b:1", "two".into(), Duration::from_secs(60)).await.unwrap();
+        store.set("sqlcache:a:2", "expired".into(), Duration::ZERO).await.unwrap();
+
+        assert_eq!(store.get("sqlcache:a:1").await.unwrap().as_deref(), Some("one"));
+        assert_eq!(store.get("sqlcache:a:2").await.unwrap(), None);
+
+        assert_eq!(store.delete_prefix("sqlcache:a:").await.unwrap(), 1);
+        assert_eq!(store.get("sqlcache:a:1").await.unwrap(), None);
+        assert_eq!(store.get("sqlcache:b:1").await.unwrap().as_deref(), Some("two"));
+    }
+}
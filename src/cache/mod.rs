@@ -0,0 +1,18 @@
+pub mod memory;
+pub mod query_cache;
+pub mod redis_store;
+
+use std::time::Duration;
+use async_trait::async_trait;
+
+pub use memory::MemoryStore;
+pub use query_cache::QueryCache;
+pub use redis_store::RedisStore;
+
+// 쿼리 결과 캐시 저장소. 값은 직렬화된 JSON 문자열이다
+#[async_trait]
+pub trait CacheStore: Send + Sync + std::fmt::Debug + 'static {
+    async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn delete_prefix(&self, prefix: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>;
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sha2::{Digest, Sha256};
+use crate::cache::{CacheStore, MemoryStore, RedisStore};
+use crate::db::types::ResultSet;
+
+const KEY_PREFIX: &str = "sqlcache";
+
+#[derive(Debug, Clone)]
+pub struct QueryCache {
+    store: Arc<dyn CacheStore>,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryStore::new()))
+    }
+}
+
+impl QueryCache {
+    pub fn new(store: Arc<dyn CacheStore>) -> Self {
+        Self { store }
+    }
+
+    // CACHE_REDIS_URL 이 있으면 Redis, 없으면 프로세스 메모리를 쓴다
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        match std::env::var("CACHE_REDIS_URL") {
+            Ok(url) if !url.is_empty() => Ok(Self::new(Arc::new(RedisStore::new(&url)?))),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn key(connection_id: &str, query: &str, params: &[serde_json::Value]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(normalize_sql(query));
+        hasher.update([0u8]);
+        hasher.update(serde_json::Value::from(params.to_vec()).to_string());
+        let digest = hasher.finalize();
+        let hash: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}:{}", connection_prefix(connection_id), hash)
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<ResultSet>>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.store.get(key).await? {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set(&self, key: &str, result_sets: &[ResultSet], ttl: Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.store.set(key, serde_json::to_string(result_sets)?, ttl).await
+    }
+
+    pub async fn invalidate_connection(&self, connection_id: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.store.delete_prefix(&format!("{}:", connection_prefix(connection_id))).await
+    }
+
+    pub async fn invalidate_all(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.store.delete_prefix(&format!("{}:", KEY_PREFIX)).await
+    }
+}
+
+fn connection_prefix(connection_id: &str) -> String {
+    format!("{}:{}", KEY_PREFIX, connection_id)
+}
+
+// 따옴표 밖의 연속 공백을 하나로 줄이고 끝의 세미콜론을 제거한다
+pub fn normalize_sql(query: &str) -> String {
+    let mut normalized = String::with_capacity(query.len());
+    let mut quote: Option<char> = None;
+    let mut pending_space = false;
+
+    for c in query.trim().chars() {
+        match quote {
+            Some(q) => {
+                normalized.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space && !normalized.is_empty() {
+                    normalized.push(' ');
+                }
+                pending_space = false;
+                if c == '\'' || c == '"' || c == '`' {
+                    quote = Some(c);
+                }
+                normalized.push(c);
+            }
+        }
+    }
+
+    normalized.trim_end_matches(';').trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_sql() {
+        assert_eq!(
+            normalize_sql("  SELECT *\n  FROM   books\tWHERE title = 'a  b' ; "),
+            "SELECT * FROM books WHERE title = 'a  b'"
+        );
+    }
+
+    #[test]
+    fn test_cache_key_ignores_whitespace_and_scopes_connection() {
+        let a = QueryCache::key("conn-1", "SELECT 1", &[]);
+        let b = QueryCache::key("conn-1", "SELECT   1;", &[]);
+        let c = QueryCache::key("conn-2", "SELECT 1", &[]);
+        let d = QueryCache::key("conn-1", "SELECT 1", &[serde_json::json!(1)]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, d);
+        assert!(a.starts_with("sqlcache:conn-1:"));
+    }
+}
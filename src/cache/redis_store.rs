@@ -0,0 +1,48 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use crate::cache::CacheStore;
+
+#[derive(Debug, Clone)]
+pub struct RedisStore {
+    client: redis::Client,
+}
+
+impl RedisStore {
+    pub fn new(url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            client: redis::Client::open(url)?,
+        })
+    }
+}
+
+#[async_trait]
+impl CacheStore for RedisStore {
+    async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.client.get_multiplexed_tokio_connection().await?;
+        Ok(conn.get(key).await?)
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.client.get_multiplexed_tokio_connection().await?;
+        conn.set_ex::<_, _, ()>(key, value, ttl.as_secs().max(1)).await?;
+        Ok(())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.client.get_multiplexed_tokio_connection().await?;
+        let pattern = format!("{}*", prefix.replace('*', "\\*"));
+        let keys: Vec<String> = {
+            let mut iter = conn.scan_match::<_, String>(pattern).await?;
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        Ok(conn.del(keys).await?)
+    }
+}
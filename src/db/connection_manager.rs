@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 use crate::db::types::{DatabaseType, ConnectionInfo};
+use crate::db::policy::{PolicyViolation, SqlPolicy};
 use crate::db::connection::{Connection, ConnectionConfig, DatabaseConnection};
 use crate::db::implementations::{
     postgres::PostgresConnection,
@@ -12,15 +13,17 @@ use crate::db::implementations::{
     // redis::RedisConnection,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConnectionManager {
     connections: Arc<RwLock<HashMap<String, DatabaseConnection>>>,
+    policies: Arc<RwLock<HashMap<String, SqlPolicy>>>,
 }
 
 impl ConnectionManager {
     pub fn new() -> Self {
         ConnectionManager {
             connections: Arc::new(RwLock::new(HashMap::new())),
+            policies: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -28,19 +31,20 @@ impl ConnectionManager {
         &self,
         info: ConnectionInfo,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let policy = info.policy.clone();
         let connection = match info.db_type {
             DatabaseType::PostgreSQL => {
                 let conn = PostgresConnection::new(ConnectionConfig::new(
                     info.connection_string,
                     info.pool_options,
-                )).await?;
+                ).with_read_only(info.read_only)).await?;
                 DatabaseConnection::Postgres(conn)
             },
             DatabaseType::Oracle => {
                 let mut config = ConnectionConfig::new(
                     info.connection_string,
                     info.pool_options,
-                );
+                ).with_read_only(info.read_only);
                 if let (Some(username), Some(password)) = (info.username, info.password) {
                     config = config.with_credentials(username, password);
                 }
@@ -51,14 +55,14 @@ impl ConnectionManager {
                 let conn = MySQLConnection::new(ConnectionConfig::new(
                     info.connection_string,
                     info.pool_options,
-                )).await?;
+                ).with_read_only(info.read_only)).await?;
                 DatabaseConnection::MySQL(conn)
             },
             DatabaseType::MSSQL => {
                 let conn = MSSQLConnection::new(ConnectionConfig::new(
                     info.connection_string,
                     info.pool_options,
-                )).await?;
+                ).with_read_only(info.read_only)).await?;
                 DatabaseConnection::MSSQL(conn)
             },
             // DatabaseType::Redis => {
@@ -74,10 +78,21 @@ impl ConnectionManager {
         let id = Uuid::new_v4().to_string();
         let mut connections = self.connections.write().await;
         connections.insert(id.clone(), connection);
+        if let Some(policy) = policy {
+            self.policies.write().await.insert(id.clone(), policy);
+        }
 
         Ok(id)
     }
 
+    // 테스트용: 이미 만든 연결을 등록한다
+    #[cfg(test)]
+    pub async fn insert_connection(&self, connection: DatabaseConnection) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.connections.write().await.insert(id.clone(), connection);
+        id
+    }
+
     pub async fn get_connection(&self, id: &str) -> Option<Arc<DatabaseConnection>> {
         let connections = self.connections.read().await;
         connections.get(id).cloned().map(Arc::new)
@@ -88,6 +103,44 @@ impl ConnectionManager {
         if let Some(conn) = connections.remove(id) {
             conn.close().await;
         }
+        self.policies.write().await.remove(id);
+    }
+
+    pub async fn policy(&self, id: &str) -> Option<SqlPolicy> {
+        self.policies.read().await.get(id).cloned()
+    }
+
+    // None 이면 정책을 지운다
+    pub async fn set_policy(&self, id: &str, policy: Option<SqlPolicy>) {
+        let mut policies = self.policies.write().await;
+        match policy {
+            Some(policy) => policies.insert(id.to_string(), policy),
+            None => policies.remove(id),
+        };
+    }
+
+    // 정책이 없는 연결은 SQL 을 해석하지 않는다
+    pub async fn check_policy(&self, id: &str, db_type: DatabaseType, sql: &str) -> Result<(), PolicyViolation> {
+        match self.policies.read().await.get(id) {
+            Some(policy) => policy.check(db_type, sql),
+            None => Ok(()),
+        }
+    }
+
+    // SQL 없이 부르는 루틴 (/call)
+    pub async fn check_routine_policy(&self, id: &str, name: &str) -> Result<(), PolicyViolation> {
+        match self.policies.read().await.get(id) {
+            Some(policy) => policy.check_routine(name),
+            None => Ok(()),
+        }
+    }
+
+    // 가져오기와 복사 대상 테이블. create 면 CREATE TABLE 도 허용되어야 한다
+    pub async fn check_table_write_policy(&self, id: &str, table: &str, create: bool) -> Result<(), PolicyViolation> {
+        match self.policies.read().await.get(id) {
+            Some(policy) => policy.check_table_write(table, create),
+            None => Ok(()),
+        }
     }
 
     pub async fn with_connection<F, R>(&self, id: &str, f: F) -> Option<R>
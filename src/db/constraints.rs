@@ -0,0 +1,397 @@
+use serde::{Deserialize, Serialize};
+use crate::db::connection::{Connection, DatabaseConnection};
+use crate::db::schema::{bool_field, schema_filter, table_filter, text_field};
+use crate::db::types::DatabaseType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintKind {
+    PrimaryKey,
+    Unique,
+    Check,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConstraintInfo {
+    pub table: String,
+    pub name: String,
+    pub kind: ConstraintKind,
+    pub columns: Vec<String>,
+    // CHECK 제약의 조건식
+    pub definition: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexInfo {
+    pub table: String,
+    pub name: String,
+    // 식 인덱스는 드라이버가 보여주는 식 그대로
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ForeignKeyInfo {
+    pub name: String,
+    pub schema: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: String,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    // "CASCADE", "SET NULL" 등. Oracle 은 ON UPDATE 가 없어 None
+    pub on_delete: Option<String>,
+    pub on_update: Option<String>,
+}
+
+pub fn constraints_sql(db_type: DatabaseType, schema: Option<&str>, table: Option<&str>) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!(
+            "SELECT t.relname::text AS table_name, c.conname::text AS constraint_name, \
+             upper(c.contype::text) AS constraint_type, a.attname::text AS column_name, k.ord::int AS position, \
+             CASE WHEN c.contype = 'c' THEN pg_get_constraintdef(c.oid) END AS definition \
+             FROM pg_constraint c \
+             JOIN pg_class t ON t.oid = c.conrelid \
+             JOIN pg_namespace n ON n.oid = t.relnamespace \
+             LEFT JOIN LATERAL unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord) ON true \
+             LEFT JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum \
+             WHERE c.contype IN ('p', 'u', 'c') AND {} AND {} ORDER BY 1, 2, 5",
+            schema_filter(db_type, "n.nspname", schema),
+            table_filter(db_type, "t.relname", table)
+        ),
+        DatabaseType::MySQL => format!(
+            "SELECT tc.table_name AS table_name, tc.constraint_name AS constraint_name, \
+             CASE tc.constraint_type WHEN 'PRIMARY KEY' THEN 'P' WHEN 'UNIQUE' THEN 'U' ELSE 'C' END AS constraint_type, \
+             k.column_name AS column_name, k.ordinal_position AS position, cc.check_clause AS definition \
+             FROM information_schema.table_constraints tc \
+             LEFT JOIN information_schema.key_column_usage k ON k.constraint_schema = tc.constraint_schema \
+             AND k.constraint_name = tc.constraint_name AND k.table_name = tc.table_name \
+             LEFT JOIN information_schema.check_constraints cc ON cc.constraint_schema = tc.constraint_schema \
+             AND cc.constraint_name = tc.constraint_name \
+             WHERE tc.constraint_type IN ('PRIMARY KEY', 'UNIQUE', 'CHECK') AND {} AND {} ORDER BY 1, 2, 5",
+            schema_filter(db_type, "tc.table_schema", schema),
+            table_filter(db_type, "tc.table_name", table)
+        ),
+        DatabaseType::MSSQL => {
+            let filter = format!(
+                "{} AND {}",
+                schema_filter(db_type, "s.name", schema),
+                table_filter(db_type, "t.name", table)
+            );
+            format!(
+                "SELECT t.name AS table_name, kc.name AS constraint_name, IIF(kc.type = 'PK', 'P', 'U') AS constraint_type, \
+                 c.name AS column_name, CAST(ic.key_ordinal AS INT) AS position, CAST(NULL AS NVARCHAR(MAX)) AS definition \
+                 FROM sys.key_constraints kc \
+                 JOIN sys.tables t ON t.object_id = kc.parent_object_id \
+                 JOIN sys.schemas s ON s.schema_id = t.schema_id \
+                 JOIN sys.index_columns ic ON ic.object_id = kc.parent_object_id AND ic.index_id = kc.unique_index_id \
+                 JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id \
+                 WHERE {filter} \
+                 UNION ALL \
+                 SELECT t.name, cc.name, 'C', c.name, 1, cc.definition \
+                 FROM sys.check_constraints cc \
+                 JOIN sys.tables t ON t.object_id = cc.parent_object_id \
+                 JOIN sys.schemas s ON s.schema_id = t.schema_id \
+                 LEFT JOIN sys.columns c ON c.object_id = cc.parent_object_id AND c.column_id = cc.parent_column_id \
+                 WHERE {filter} ORDER BY 1, 2, 5"
+            )
+        }
+        // NOT NULL 도 이름 없는 CHECK 제약으로 저장되므로 뺀다
+        _ => format!(
+            "SELECT c.table_name AS \"table_name\", c.constraint_name AS \"constraint_name\", \
+             c.constraint_type AS \"constraint_type\", cc.column_name AS \"column_name\", cc.position AS \"position\", \
+             c.search_condition_vc AS \"definition\" \
+             FROM all_constraints c LEFT JOIN all_cons_columns cc ON cc.owner = c.owner \
+             AND cc.constraint_name = c.constraint_name \
+             WHERE c.constraint_type IN ('P', 'U', 'C') \
+             AND NOT (c.constraint_type = 'C' AND c.generated = 'GENERATED NAME' \
+             AND c.search_condition_vc LIKE '%IS NOT NULL') \
+             AND {} AND {} ORDER BY 1, 2, 5",
+            schema_filter(db_type, "c.owner", schema),
+            table_filter(db_type, "c.table_name", table)
+        ),
+    }
+}
+
+pub fn indexes_sql(db_type: DatabaseType, schema: Option<&str>, table: Option<&str>) -> String {
+    match db_type {
+        // INCLUDE 컬럼 (indnkeyatts 이후) 은 키가 아니다
+        DatabaseType::PostgreSQL => format!(
+            "SELECT t.relname::text AS table_name, i.relname::text AS index_name, \
+             pg_get_indexdef(x.indexrelid, k.ord::int, true) AS column_name, k.ord::int AS position, \
+             x.indisunique AS is_unique, x.indisprimary AS is_primary \
+             FROM pg_index x \
+             JOIN pg_class t ON t.oid = x.indrelid \
+             JOIN pg_class i ON i.oid = x.indexrelid \
+             JOIN pg_namespace n ON n.oid = t.relnamespace \
+             CROSS JOIN LATERAL unnest(x.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord) \
+             WHERE k.ord <= x.indnkeyatts AND {} AND {} ORDER BY 1, 2, 4",
+            schema_filter(db_type, "n.nspname", schema),
+            table_filter(db_type, "t.relname", table)
+        ),
+        DatabaseType::MySQL => format!(
+            "SELECT table_name AS table_name, index_name AS index_name, column_name AS column_name, \
+             seq_in_index AS position, non_unique = 0 AS is_unique, index_name = 'PRIMARY' AS is_primary \
+             FROM information_schema.statistics WHERE {} AND {} ORDER BY 1, 2, 4",
+            schema_filter(db_type, "table_schema", schema),
+            table_filter(db_type, "table_name", table)
+        ),
+        DatabaseType::MSSQL => format!(
+            "SELECT t.name AS table_name, i.name AS index_name, c.name AS column_name, \
+             CAST(ic.key_ordinal AS INT) AS position, i.is_unique AS is_unique, i.is_primary_key AS is_primary \
+             FROM sys.indexes i \
+             JOIN sys.tables t ON t.object_id = i.object_id \
+             JOIN sys.schemas s ON s.schema_id = t.schema_id \
+             JOIN sys.index_columns ic ON ic.object_id = i.object_id AND ic.index_id = i.index_id \
+             JOIN sys.columns c ON c.object_id = ic.object_id AND c.column_id = ic.column_id \
+             WHERE i.type > 0 AND ic.key_ordinal > 0 AND {} AND {} ORDER BY 1, 2, 4",
+            schema_filter(db_type, "s.name", schema),
+            table_filter(db_type, "t.name", table)
+        ),
+        _ => format!(
+            "SELECT i.table_name AS \"table_name\", i.index_name AS \"index_name\", ic.column_name AS \"column_name\", \
+             ic.column_position AS \"position\", CASE i.uniqueness WHEN 'UNIQUE' THEN 1 ELSE 0 END AS \"is_unique\", \
+             CASE WHEN EXISTS (SELECT 1 FROM all_constraints p WHERE p.owner = i.table_owner \
+             AND p.table_name = i.table_name AND p.constraint_type = 'P' AND p.index_name = i.index_name) \
+             THEN 1 ELSE 0 END AS \"is_primary\" \
+             FROM all_indexes i JOIN all_ind_columns ic ON ic.index_owner = i.owner AND ic.index_name = i.index_name \
+             WHERE {} AND {} ORDER BY 1, 2, 4",
+            schema_filter(db_type, "i.table_owner", schema),
+            table_filter(db_type, "i.table_name", table)
+        ),
+    }
+}
+
+pub fn foreign_keys_sql(db_type: DatabaseType, schema: Option<&str>, table: Option<&str>) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!(
+            "SELECT c.conname::text AS constraint_name, n.nspname::text AS table_schema, t.relname::text AS table_name, \
+             a.attname::text AS column_name, rn.nspname::text AS ref_schema, rt.relname::text AS ref_table, \
+             ra.attname::text AS ref_column, k.ord::int AS position, \
+             c.confdeltype::text AS delete_rule, c.confupdtype::text AS update_rule \
+             FROM pg_constraint c \
+             JOIN pg_class t ON t.oid = c.conrelid \
+             JOIN pg_namespace n ON n.oid = t.relnamespace \
+             JOIN pg_class rt ON rt.oid = c.confrelid \
+             JOIN pg_namespace rn ON rn.oid = rt.relnamespace \
+             CROSS JOIN LATERAL unnest(c.conkey, c.confkey) WITH ORDINALITY AS k(attnum, refnum, ord) \
+             JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum \
+             JOIN pg_attribute ra ON ra.attrelid = c.confrelid AND ra.attnum = k.refnum \
+             WHERE c.contype = 'f' AND {} AND {} ORDER BY 3, 1, 8",
+            schema_filter(db_type, "n.nspname", schema),
+            table_filter(db_type, "t.relname", table)
+        ),
+        DatabaseType::MySQL => format!(
+            "SELECT k.constraint_name AS constraint_name, k.table_schema AS table_schema, k.table_name AS table_name, \
+             k.column_name AS column_name, k.referenced_table_schema AS ref_schema, \
+             k.referenced_table_name AS ref_table, k.referenced_column_name AS ref_column, \
+             k.ordinal_position AS position, r.delete_rule AS delete_rule, r.update_rule AS update_rule \
+             FROM information_schema.key_column_usage k \
+             JOIN information_schema.referential_constraints r ON r.constraint_schema = k.constraint_schema \
+             AND r.constraint_name = k.constraint_name AND r.table_name = k.table_name \
+             WHERE k.referenced_table_name IS NOT NULL AND {} AND {} ORDER BY 3, 1, 8",
+            schema_filter(db_type, "k.table_schema", schema),
+            table_filter(db_type, "k.table_name", table)
+        ),
+        DatabaseType::MSSQL => format!(
+            "SELECT fk.name AS constraint_name, s.name AS table_schema, t.name AS table_name, c.name AS column_name, \
+             rs.name AS ref_schema, rt.name AS ref_table, rc.name AS ref_column, \
+             CAST(fkc.constraint_column_id AS INT) AS position, \
+             fk.delete_referential_action_desc AS delete_rule, fk.update_referential_action_desc AS update_rule \
+             FROM sys.foreign_keys fk \
+             JOIN sys.tables t ON t.object_id = fk.parent_object_id \
+             JOIN sys.schemas s ON s.schema_id = t.schema_id \
+             JOIN sys.tables rt ON rt.object_id = fk.referenced_object_id \
+             JOIN sys.schemas rs ON rs.schema_id = rt.schema_id \
+             JOIN sys.foreign_key_columns fkc ON fkc.constraint_object_id = fk.object_id \
+             JOIN sys.columns c ON c.object_id = fkc.parent_object_id AND c.column_id = fkc.parent_column_id \
+             JOIN sys.columns rc ON rc.object_id = fkc.referenced_object_id AND rc.column_id = fkc.referenced_column_id \
+             WHERE {} AND {} ORDER BY 3, 1, 8",
+            schema_filter(db_type, "s.name", schema),
+            table_filter(db_type, "t.name", table)
+        ),
+        _ => format!(
+            "SELECT c.constraint_name AS \"constraint_name\", c.owner AS \"table_schema\", c.table_name AS \"table_name\", \
+             cc.column_name AS \"column_name\", r.owner AS \"ref_schema\", r.table_name AS \"ref_table\", \
+             rc.column_name AS \"ref_column\", cc.position AS \"position\", c.delete_rule AS \"delete_rule\", \
+             CAST(NULL AS VARCHAR2(9)) AS \"update_rule\" \
+             FROM all_constraints c \
+             JOIN all_cons_columns cc ON cc.owner = c.owner AND cc.constraint_name = c.constraint_name \
+             JOIN all_constraints r ON r.owner = c.r_owner AND r.constraint_name = c.r_constraint_name \
+             JOIN all_cons_columns rc ON rc.owner = r.owner AND rc.constraint_name = r.constraint_name \
+             AND rc.position = cc.position \
+             WHERE c.constraint_type = 'R' AND {} AND {} ORDER BY 3, 1, 8",
+            schema_filter(db_type, "c.owner", schema),
+            table_filter(db_type, "c.table_name", table)
+        ),
+    }
+}
+
+// pg_constraint 는 한 글자 코드, sys.foreign_keys 는 NO_ACTION 처럼 밑줄을 쓴다
+pub fn referential_action(code: &str) -> String {
+    match code {
+        "a" => "NO ACTION".to_string(),
+        "r" => "RESTRICT".to_string(),
+        "c" => "CASCADE".to_string(),
+        "n" => "SET NULL".to_string(),
+        "d" => "SET DEFAULT".to_string(),
+        other => other.replace('_', " ").to_uppercase(),
+    }
+}
+
+// 제약/인덱스마다 여러 행 (컬럼당 한 행) 으로 오는 카탈로그 결과를 묶는다.
+// 결과는 이름과 위치 순으로 정렬되어 있어야 한다
+fn group_rows(
+    rows: &[serde_json::Value],
+    key: impl Fn(&serde_json::Value) -> (String, String),
+) -> Vec<(&serde_json::Value, Vec<String>)> {
+    let mut groups: Vec<((String, String), &serde_json::Value, Vec<String>)> = Vec::new();
+    for row in rows {
+        let row_key = key(row);
+        let column = text_field(row, "column_name");
+        match groups.last_mut() {
+            Some((last, _, columns)) if *last == row_key => columns.extend(column),
+            _ => groups.push((row_key, row, column.into_iter().collect())),
+        }
+    }
+    groups.into_iter().map(|(_, row, columns)| (row, columns)).collect()
+}
+
+pub fn constraints_from_rows(rows: &[serde_json::Value]) -> Vec<ConstraintInfo> {
+    group_rows(rows, |row| {
+        (
+            text_field(row, "table_name").unwrap_or_default(),
+            text_field(row, "constraint_name").unwrap_or_default(),
+        )
+    })
+    .into_iter()
+    .map(|(row, columns)| ConstraintInfo {
+        table: text_field(row, "table_name").unwrap_or_default(),
+        name: text_field(row, "constraint_name").unwrap_or_default(),
+        kind: match text_field(row, "constraint_type").as_deref() {
+            Some("P") => ConstraintKind::PrimaryKey,
+            Some("U") => ConstraintKind::Unique,
+            _ => ConstraintKind::Check,
+        },
+        columns,
+        definition: text_field(row, "definition"),
+    })
+    .collect()
+}
+
+pub fn indexes_from_rows(rows: &[serde_json::Value]) -> Vec<IndexInfo> {
+    group_rows(rows, |row| {
+        (
+            text_field(row, "table_name").unwrap_or_default(),
+            text_field(row, "index_name").unwrap_or_default(),
+        )
+    })
+    .into_iter()
+    .map(|(row, columns)| IndexInfo {
+        table: text_field(row, "table_name").unwrap_or_default(),
+        name: text_field(row, "index_name").unwrap_or_default(),
+        columns,
+        unique: bool_field(row, "is_unique"),
+        primary: bool_field(row, "is_primary"),
+    })
+    .collect()
+}
+
+pub fn foreign_keys_from_rows(rows: &[serde_json::Value]) -> Vec<ForeignKeyInfo> {
+    let mut keys: Vec<ForeignKeyInfo> = Vec::new();
+    for row in rows {
+        let name = text_field(row, "constraint_name").unwrap_or_default();
+        let table = text_field(row, "table_name").unwrap_or_default();
+        let column = text_field(row, "column_name").unwrap_or_default();
+        let referenced = text_field(row, "ref_column").unwrap_or_default();
+        match keys.last_mut() {
+            Some(last) if last.name == name && last.table == table => {
+                last.columns.push(column);
+                last.referenced_columns.push(referenced);
+            }
+            _ => keys.push(ForeignKeyInfo {
+                name,
+                schema: text_field(row, "table_schema").unwrap_or_default(),
+                table,
+                columns: vec![column],
+                referenced_schema: text_field(row, "ref_schema").unwrap_or_default(),
+                referenced_table: text_field(row, "ref_table").unwrap_or_default(),
+                referenced_columns: vec![referenced],
+                on_delete: text_field(row, "delete_rule").map(|v| referential_action(&v)),
+                on_update: text_field(row, "update_rule").map(|v| referential_action(&v)),
+            }),
+        }
+    }
+    keys
+}
+
+pub async fn list_constraints(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+    table: Option<&str>,
+) -> Result<Vec<ConstraintInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection
+        .execute_query(&constraints_sql(connection.db_type(), schema, table))
+        .await?;
+    Ok(constraints_from_rows(&rows))
+}
+
+pub async fn list_indexes(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+    table: Option<&str>,
+) -> Result<Vec<IndexInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection
+        .execute_query(&indexes_sql(connection.db_type(), schema, table))
+        .await?;
+    Ok(indexes_from_rows(&rows))
+}
+
+pub async fn list_foreign_keys(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+    table: Option<&str>,
+) -> Result<Vec<ForeignKeyInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection
+        .execute_query(&foreign_keys_sql(connection.db_type(), schema, table))
+        .await?;
+    Ok(foreign_keys_from_rows(&rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rows_group_by_constraint() {
+        let rows = vec![
+            json!({"table_name": "orders", "constraint_name": "orders_pk", "constraint_type": "P", "column_name": "id", "position": 1}),
+            json!({"table_name": "orders", "constraint_name": "orders_uq", "constraint_type": "U", "column_name": "a", "position": 1}),
+            json!({"table_name": "orders", "constraint_name": "orders_uq", "constraint_type": "U", "column_name": "b", "position": 2}),
+            json!({"table_name": "orders", "constraint_name": "qty_ck", "constraint_type": "C", "column_name": null, "definition": "qty > 0"}),
+        ];
+        let constraints = constraints_from_rows(&rows);
+        assert_eq!(constraints.len(), 3);
+        assert_eq!(constraints[0].kind, ConstraintKind::PrimaryKey);
+        assert_eq!(constraints[1].columns, vec!["a", "b"]);
+        assert!(constraints[2].columns.is_empty());
+        assert_eq!(constraints[2].definition.as_deref(), Some("qty > 0"));
+    }
+
+    #[test]
+    fn test_foreign_key_rules() {
+        let rows = vec![
+            json!({"constraint_name": "fk", "table_schema": "s", "table_name": "line", "column_name": "order_id",
+                   "ref_schema": "s", "ref_table": "orders", "ref_column": "id", "delete_rule": "c", "update_rule": "a"}),
+            json!({"constraint_name": "fk", "table_schema": "s", "table_name": "line", "column_name": "tenant",
+                   "ref_schema": "s", "ref_table": "orders", "ref_column": "tenant", "delete_rule": "c", "update_rule": "a"}),
+        ];
+        let keys = foreign_keys_from_rows(&rows);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].referenced_columns, vec!["id", "tenant"]);
+        assert_eq!(keys[0].on_delete.as_deref(), Some("CASCADE"));
+        assert_eq!(referential_action("SET_NULL"), "SET NULL");
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::DateTime;
+use serde::Deserialize;
+use crate::db::import::{is_valid_column_name, ColumnMapping};
+use crate::db::source::{is_valid_identifier, SourceSpec};
+use crate::db::types::{CompactResultSet, DatabaseType};
+use crate::formats::typed::{column_kind, parse_decimal, ColumnKind};
+use base64ct::{Base64, Encoding};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CopyRequest {
+    pub source_connection_id: String,
+    #[serde(flatten)]
+    pub source: SourceSpec,
+    pub target_connection_id: String,
+    pub target_table: String,
+    // 비어 있으면 원본 컬럼 이름을 그대로 쓴다
+    #[serde(default)]
+    pub columns: Vec<ColumnMapping>,
+    #[serde(default)]
+    pub create_table: bool,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_batch_size() -> usize {
+    1000
+}
+
+impl CopyRequest {
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.source.validate()?;
+        if !is_valid_identifier(&self.target_table) {
+            return Err(format!("Invalid table name: {}", self.target_table).into());
+        }
+        if let Some(mapping) = self.columns.iter().find(|m| !is_valid_column_name(&m.target)) {
+            return Err(format!("Invalid column name: {}", mapping.target).into());
+        }
+        if self.batch_size == 0 {
+            return Err("batch_size must be greater than 0".into());
+        }
+        Ok(())
+    }
+
+    // Oracle 연결은 원본을 다 읽을 때까지 잠겨 있어 같은 연결로는 대상에 쓸 수 없다.
+    // ConnectionManager 는 조회할 때마다 새 Arc 를 만들므로 연결 ID 로 비교한다
+    pub fn validate_connections(&self, source_db: DatabaseType) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if source_db == DatabaseType::Oracle && self.source_connection_id == self.target_connection_id {
+            return Err("Copying within one Oracle connection is not supported; use INSERT ... SELECT".into());
+        }
+        Ok(())
+    }
+}
+
+// 원본 결과 집합의 어느 열을 대상의 어느 컬럼에 넣을지
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyPlan {
+    pub source_indexes: Vec<usize>,
+    pub targets: Vec<String>,
+    pub kinds: Vec<ColumnKind>,
+}
+
+impl CopyPlan {
+    pub fn new(
+        mappings: &[ColumnMapping],
+        source: &CompactResultSet,
+        source_db: DatabaseType,
+    ) -> Result<Self, String> {
+        let pairs = if mappings.is_empty() {
+            source
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    if is_valid_column_name(&column.name) {
+                        Ok((i, column.name.clone()))
+                    } else {
+                        Err(format!("Source column {:?} is not a valid target column name; alias it or add a mapping", column.name))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            mappings
+                .iter()
+                .map(|mapping| {
+                    source
+                        .columns
+                        .iter()
+                        .position(|column| column.name.eq_ignore_ascii_case(&mapping.source))
+                        .map(|i| (i, mapping.target.clone()))
+                        .ok_or_else(|| format!("Source column not found: {}", mapping.source))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        Ok(Self {
+            kinds: pairs
+                .iter()
+                .map(|(i, _)| column_kind(source_db, &source.columns[*i].data_type))
+                .collect(),
+            source_indexes: pairs.iter().map(|(i, _)| *i).collect(),
+            targets: pairs.into_iter().map(|(_, target)| target).collect(),
+        })
+    }
+
+    // 대상 컬럼 순서로 값을 골라 대상 드라이버가 받아들이는 표현으로 바꾼다
+    pub fn target_row(&self, row: &[serde_json::Value], target_db: DatabaseType) -> Vec<serde_json::Value> {
+        self.source_indexes
+            .iter()
+            .zip(&self.kinds)
+            .map(|(i, kind)| prepare_value(*kind, target_db, row.get(*i).unwrap_or(&serde_json::Value::Null)))
+            .collect()
+    }
+
+    pub fn create_table_sql(&self, table: &str, rows: &[Vec<serde_json::Value>], target_db: DatabaseType) -> String {
+        let columns = self
+            .source_indexes
+            .iter()
+            .zip(&self.kinds)
+            .zip(&self.targets)
+            .map(|((i, kind), target)| {
+                // 소수 자릿수는 타입 이름에 없는 경우가 많아 값에서 정한다
+                let scale = rows
+                    .iter()
+                    .filter_map(|row| parse_decimal(row.get(*i)?))
+                    .map(|d| d.scale())
+                    .max()
+                    .unwrap_or(0);
+                format!("{} {}", target, target_column_type(*kind, target_db, scale))
+            })
+            .collect::<Vec<_>>();
+        format!("CREATE TABLE {} ({})", table, columns.join(", "))
+    }
+}
+
+pub fn target_column_type(kind: ColumnKind, target_db: DatabaseType, scale: u32) -> String {
+    let scale = scale.min(30);
+    let name = match (kind, target_db) {
+        (ColumnKind::Boolean, DatabaseType::MSSQL) => "BIT",
+        (ColumnKind::Boolean, DatabaseType::Oracle) => "NUMBER(1)",
+        (ColumnKind::Boolean, _) => "BOOLEAN",
+        (ColumnKind::Int64, DatabaseType::Oracle) => "NUMBER(19)",
+        (ColumnKind::Int64, _) => "BIGINT",
+        (ColumnKind::UInt64, DatabaseType::MySQL) => "BIGINT UNSIGNED",
+        (ColumnKind::UInt64, DatabaseType::Oracle) => "NUMBER(20)",
+        (ColumnKind::UInt64, _) => "NUMERIC(20)",
+        (ColumnKind::Float64, DatabaseType::PostgreSQL) => "DOUBLE PRECISION",
+        (ColumnKind::Float64, DatabaseType::MySQL) => "DOUBLE",
+        (ColumnKind::Float64, DatabaseType::Oracle) => "BINARY_DOUBLE",
+        (ColumnKind::Float64, _) => "FLOAT",
+        (ColumnKind::Decimal, DatabaseType::PostgreSQL) => "NUMERIC",
+        (ColumnKind::Decimal, DatabaseType::Oracle) => "NUMBER",
+        (ColumnKind::Decimal, _) => return format!("DECIMAL(38,{})", scale),
+        (ColumnKind::Timestamp, DatabaseType::MySQL) => "DATETIME(6)",
+        (ColumnKind::Timestamp, DatabaseType::MSSQL) => "DATETIME2",
+        (ColumnKind::Timestamp, _) => "TIMESTAMP",
+        (ColumnKind::TimestampTz, DatabaseType::PostgreSQL) => "TIMESTAMPTZ",
+        // MySQL 에는 시간대를 담는 타입이 없어 UTC 로 바꿔 넣는다
+        (ColumnKind::TimestampTz, DatabaseType::MySQL) => "DATETIME(6)",
+        (ColumnKind::TimestampTz, DatabaseType::MSSQL) => "DATETIMEOFFSET",
+        (ColumnKind::TimestampTz, _) => "TIMESTAMP WITH TIME ZONE",
+        (ColumnKind::Date, _) => "DATE",
+        (ColumnKind::Time, DatabaseType::MySQL) => "TIME(6)",
+        (ColumnKind::Time, DatabaseType::Oracle) => "VARCHAR2(20)",
+        (ColumnKind::Time, _) => "TIME",
+        (ColumnKind::Binary, DatabaseType::PostgreSQL) => "BYTEA",
+        (ColumnKind::Binary, DatabaseType::MySQL) => "LONGBLOB",
+        (ColumnKind::Binary, DatabaseType::MSSQL) => "VARBINARY(MAX)",
+        (ColumnKind::Binary, _) => "BLOB",
+        (ColumnKind::Utf8, DatabaseType::PostgreSQL) => "TEXT",
+        (ColumnKind::Utf8, DatabaseType::MySQL) => "LONGTEXT",
+        (ColumnKind::Utf8, DatabaseType::MSSQL) => "NVARCHAR(MAX)",
+        (ColumnKind::Utf8, _) => "VARCHAR2(4000)",
+    };
+    name.to_string()
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 원본 드라이버의 JSON 표현(values.rs)을 대상 드라이버의 적재 경로가 받는 문자열로 바꾼다
+pub fn prepare_value(kind: ColumnKind, target_db: DatabaseType, value: &serde_json::Value) -> serde_json::Value {
+    let Some(text) = value.as_str() else {
+        return value.clone();
+    };
+    let converted = match (kind, target_db) {
+        // 바이너리는 Base64 로 온다. MSSQL 대량 적재는 Base64 를 그대로 받는다
+        (ColumnKind::Binary, DatabaseType::PostgreSQL) => {
+            Base64::decode_vec(text).ok().map(|bytes| format!("\\x{}", hex(&bytes)))
+        }
+        (ColumnKind::Binary, DatabaseType::Oracle) => Base64::decode_vec(text).ok().map(|bytes| hex(&bytes)),
+        (ColumnKind::Timestamp, _) => Some(text.replacen('T', " ", 1)),
+        (ColumnKind::TimestampTz, DatabaseType::MySQL) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|v| v.naive_utc().format("%Y-%m-%d %H:%M:%S%.6f").to_string()),
+        (ColumnKind::TimestampTz, DatabaseType::Oracle) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|v| v.naive_utc().format("%Y-%m-%d %H:%M:%S%.6f +00:00").to_string()),
+        _ => None,
+    };
+    converted.map(serde_json::Value::String).unwrap_or_else(|| value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::ColumnMeta;
+    use serde_json::json;
+
+    #[test]
+    fn test_same_oracle_connection_is_refused() {
+        let request = |target: &str| {
+            serde_json::from_value::<CopyRequest>(json!({
+                "source_connection_id": "c1",
+                "table": "books",
+                "target_connection_id": target,
+                "target_table": "books_copy",
+            }))
+            .unwrap()
+        };
+        assert!(request("c1").validate_connections(DatabaseType::Oracle).is_err());
+        assert!(request("c2").validate_connections(DatabaseType::Oracle).is_ok());
+        // 풀을 쓰는 드라이버는 같은 연결 안에서도 복사할 수 있다
+        assert!(request("c1").validate_connections(DatabaseType::PostgreSQL).is_ok());
+    }
+
+    fn source() -> CompactResultSet {
+        CompactResultSet {
+            columns: vec![
+                ColumnMeta { name: "ID".into(), data_type: "NUMBER(10)".into() },
+                ColumnMeta { name: "PRICE".into(), data_type: "NUMBER(10,2)".into() },
+                ColumnMeta { name: "CREATED".into(), data_type: "DATE".into() },
+                ColumnMeta { name: "PHOTO".into(), data_type: "BLOB".into() },
+            ],
+            rows: vec![vec![json!(1), json!("12.50"), json!("2024-01-02T03:04:05"), json!("AQI=")]],
+            affected_rows: None,
+        }
+    }
+
+    #[test]
+    fn test_oracle_to_postgres_plan() {
+        let source = source();
+        let plan = CopyPlan::new(&[], &source, DatabaseType::Oracle).unwrap();
+
+        assert_eq!(
+            plan.create_table_sql("books", &source.rows, DatabaseType::PostgreSQL),
+            "CREATE TABLE books (ID BIGINT, PRICE NUMERIC, CREATED TIMESTAMP, PHOTO BYTEA)"
+        );
+        assert_eq!(
+            plan.target_row(&source.rows[0], DatabaseType::PostgreSQL),
+            vec![json!(1), json!("12.50"), json!("2024-01-02 03:04:05"), json!("\\x0102")]
+        );
+        assert_eq!(
+            plan.create_table_sql("books", &source.rows, DatabaseType::MSSQL),
+            "CREATE TABLE books (ID BIGINT, PRICE DECIMAL(38,2), CREATED DATETIME2, PHOTO VARBINARY(MAX))"
+        );
+    }
+
+    #[test]
+    fn test_mapping_selects_columns() {
+        let mappings = vec![ColumnMapping { source: "price".into(), target: "amount".into() }];
+        let plan = CopyPlan::new(&mappings, &source(), DatabaseType::Oracle).unwrap();
+        assert_eq!(plan.source_indexes, vec![1]);
+        assert_eq!(plan.targets, vec!["amount"]);
+
+        let missing = vec![ColumnMapping { source: "nope".into(), target: "x".into() }];
+        assert!(CopyPlan::new(&missing, &source(), DatabaseType::Oracle).is_err());
+    }
+}
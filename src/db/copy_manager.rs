@@ -0,0 +1,209 @@
+use std::sync::Arc;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use crate::db::connection::{Connection, DatabaseConnection};
+use crate::db::copy::{CopyPlan, CopyRequest};
+use crate::db::import::{load_batch, ImportReport};
+use crate::db::job_manager::{JobOptions, JobStatus};
+use crate::db::job_registry::JobRegistry;
+use crate::db::types::{CompactResultSet, ResultSet};
+
+// 보고서에 담을 거부 행 수
+const MAX_REJECTS: usize = 1000;
+// 원본에서 읽어 두는 배치 수. 대상 쓰기가 느리면 원본 읽기가 여기서 멈춘다
+const PREFETCH_BATCHES: usize = 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyInfo {
+    pub id: String,
+    pub status: JobStatus,
+    // 제출한 Principal 의 subject
+    pub submitted_by: String,
+    pub source_connection_id: String,
+    pub target_connection_id: String,
+    pub target_table: String,
+    // 시작할 때 COUNT(*) 로 센 원본 행 수. 다 읽은 뒤에는 실제로 읽은 행 수.
+    // 세지 못했으면 다 읽을 때까지 None
+    pub total_rows: Option<u64>,
+    // 대상에 넣은 (거부된 행 포함) 행 수. 배치마다 갱신한다
+    pub rows_copied: u64,
+    pub progress: Option<f32>,
+    pub elapsed_ms: u128,
+    pub report: ImportReport,
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+struct CopyState {
+    request: CopyRequest,
+    submitted_by: String,
+    total_rows: Option<u64>,
+    rows_copied: u64,
+    report: ImportReport,
+}
+
+#[derive(Debug, Clone)]
+pub struct CopyManager {
+    copies: JobRegistry<CopyState>,
+}
+
+impl Default for CopyManager {
+    fn default() -> Self {
+        Self::new(JobOptions::default())
+    }
+}
+
+// 진행률의 분모. 원본 쿼리를 한 번 더 실행하며, 세지 못하면 (MSSQL 의 TOP 없는 ORDER BY 등)
+// 전체 행 수 없이 복사한다
+async fn count_rows(source: &DatabaseConnection, query: &str) -> Option<u64> {
+    let rows = source
+        .execute_query(&format!("SELECT COUNT(*) FROM ({}) sub", query))
+        .await
+        .ok()?;
+    match rows.first()?.as_object()?.values().next()? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+impl CopyManager {
+    pub fn new(options: JobOptions) -> Self {
+        CopyManager {
+            copies: JobRegistry::new(options),
+        }
+    }
+
+    pub async fn submit(
+        &self,
+        request: CopyRequest,
+        submitted_by: String,
+        source: Arc<DatabaseConnection>,
+        target: Arc<DatabaseConnection>,
+    ) -> String {
+        let state = CopyState {
+            request: request.clone(),
+            submitted_by,
+            total_rows: None,
+            rows_copied: 0,
+            report: ImportReport::default(),
+        };
+        let manager = self.clone();
+        self.copies
+            .submit(state, move |id| async move {
+                manager.run(&id, &request, &source, &target).await.map_err(|e| e.to_string())
+            })
+            .await
+    }
+
+    // 원본을 batch_size 행씩 읽으면서 읽은 배치를 바로 대상에 넣는다
+    async fn run(
+        &self,
+        id: &str,
+        request: &CopyRequest,
+        source: &DatabaseConnection,
+        target: &DatabaseConnection,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let query = request.source.query();
+        let counted = count_rows(source, &query).await;
+        self.copies.update(id, |copy| copy.total_rows = counted).await;
+
+        let (sender, receiver) = mpsc::channel::<ResultSet>(PREFETCH_BATCHES);
+        let producer = source.stream_rows(&query, request.batch_size, sender);
+        // 적재가 실패하면 receiver 를 버려 원본 읽기도 멈추게 한다
+        let consumer = self.load_batches(id, request, source, target, receiver);
+        let (produced, consumed) = tokio::join!(producer, consumer);
+
+        // 적재 쪽 오류가 원인이면 원본 쪽은 "receiver closed" 로만 끝난다
+        let total_rows = consumed?;
+        produced?;
+        self.copies.update(id, |copy| copy.total_rows = Some(total_rows)).await;
+        Ok(())
+    }
+
+    async fn load_batches(
+        &self,
+        id: &str,
+        request: &CopyRequest,
+        source: &DatabaseConnection,
+        target: &DatabaseConnection,
+        mut receiver: mpsc::Receiver<ResultSet>,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut plan: Option<CopyPlan> = None;
+        let mut report = ImportReport::default();
+        // 거부 보고의 line 은 원본 결과의 1부터 시작하는 행 번호
+        let mut next_line = 1u64;
+        while let Some(batch) = receiver.recv().await {
+            let batch: CompactResultSet = batch.into();
+            let plan = match plan {
+                Some(ref plan) => plan,
+                None => {
+                    let created = CopyPlan::new(&request.columns, &batch, source.db_type())?;
+                    if request.create_table {
+                        // 소수 자릿수는 첫 배치의 값으로 정한다
+                        let sql = created.create_table_sql(&request.target_table, &batch.rows, target.db_type());
+                        target.execute_result_sets(&sql).await?;
+                    }
+                    plan.insert(created)
+                }
+            };
+
+            let lines = (next_line..next_line + batch.rows.len() as u64).collect::<Vec<_>>();
+            next_line += batch.rows.len() as u64;
+            let rows = batch
+                .rows
+                .iter()
+                .map(|row| plan.target_row(row, target.db_type()))
+                .collect::<Vec<_>>();
+            let loaded = load_batch(target, &request.target_table, &plan.targets, &lines, &rows, MAX_REJECTS, &mut report).await;
+            let (snapshot, rows_copied) = (report.clone(), next_line - 1);
+            self.copies
+                .update(id, move |copy| {
+                    copy.report = snapshot;
+                    copy.rows_copied = rows_copied;
+                })
+                .await;
+            loaded?;
+        }
+
+        if plan.is_none() && request.create_table {
+            return Err("Source query returned no rows; cannot create the target table".into());
+        }
+        Ok(next_line - 1)
+    }
+
+    pub async fn get_copy(&self, id: &str) -> Option<CopyInfo> {
+        self.copies
+            .get(id, |copy, _| {
+                // 미리 센 뒤에 원본 행이 늘었을 수 있다
+                let progress = match (copy.status, copy.data.total_rows) {
+                    (JobStatus::Queued, _) => Some(0.0),
+                    (JobStatus::Completed, _) => Some(1.0),
+                    (_, Some(0)) => Some(1.0),
+                    (_, Some(total)) => Some((copy.data.rows_copied as f32 / total as f32).min(1.0)),
+                    (_, None) => None,
+                };
+
+                CopyInfo {
+                    id: id.to_string(),
+                    status: copy.status,
+                    submitted_by: copy.data.submitted_by.clone(),
+                    source_connection_id: copy.data.request.source_connection_id.clone(),
+                    target_connection_id: copy.data.request.target_connection_id.clone(),
+                    target_table: copy.data.request.target_table.clone(),
+                    total_rows: copy.data.total_rows,
+                    rows_copied: copy.data.rows_copied,
+                    progress,
+                    elapsed_ms: copy.elapsed().as_millis(),
+                    report: copy.data.report.clone(),
+                    error: copy.error.clone(),
+                }
+            })
+            .await
+    }
+
+    // 이미 적재된 배치는 되돌리지 않는다
+    pub async fn cancel(&self, id: &str) -> Option<JobStatus> {
+        self.copies.cancel(id).await
+    }
+}
@@ -0,0 +1,520 @@
+use serde::{Deserialize, Serialize};
+use crate::db::connection::DatabaseConnection;
+use crate::db::constraints::{self, ConstraintInfo, ConstraintKind, ForeignKeyInfo, IndexInfo};
+use crate::db::copy::target_column_type;
+use crate::db::dump::string_literal;
+use crate::db::schema::{self, ColumnInfo, TableKind};
+use crate::db::types::DatabaseType;
+use crate::formats::typed::{column_kind, ColumnKind};
+
+// 카탈로그에서 읽은 테이블 하나의 구조
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableDefinition {
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<ColumnInfo>,
+    pub constraints: Vec<ConstraintInfo>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+// 컬럼이 하나도 없으면 테이블이 없는 것으로 본다
+pub async fn load_table(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+    table: &str,
+) -> Result<Option<TableDefinition>, Box<dyn std::error::Error + Send + Sync>> {
+    let columns = schema::list_columns(connection, schema, table).await?;
+    if columns.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(TableDefinition {
+        schema: schema.map(str::to_string),
+        name: table.to_string(),
+        columns,
+        constraints: constraints::list_constraints(connection, schema, Some(table)).await?,
+        indexes: constraints::list_indexes(connection, schema, Some(table)).await?,
+        foreign_keys: constraints::list_foreign_keys(connection, schema, Some(table)).await?,
+    }))
+}
+
+// 스키마의 모든 테이블. 카탈로그 조회는 테이블 수와 관계없이 다섯 번이다
+pub async fn load_schema(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+) -> Result<Vec<TableDefinition>, Box<dyn std::error::Error + Send + Sync>> {
+    let tables = schema::list_tables(connection, schema, TableKind::Table).await?;
+    let columns = schema::list_schema_columns(connection, schema).await?;
+    let constraints = constraints::list_constraints(connection, schema, None).await?;
+    let indexes = constraints::list_indexes(connection, schema, None).await?;
+    let foreign_keys = constraints::list_foreign_keys(connection, schema, None).await?;
+
+    Ok(tables
+        .into_iter()
+        .map(|table| TableDefinition {
+            columns: columns
+                .iter()
+                .filter(|(owner, _)| *owner == table.name)
+                .map(|(_, column)| column.clone())
+                .collect(),
+            constraints: constraints.iter().filter(|c| c.table == table.name).cloned().collect(),
+            indexes: indexes.iter().filter(|i| i.table == table.name).cloned().collect(),
+            foreign_keys: foreign_keys.iter().filter(|fk| fk.table == table.name).cloned().collect(),
+            schema: Some(table.schema),
+            name: table.name,
+        })
+        .collect())
+}
+
+// 일반 식별자는 그대로 두고, 그 밖의 이름만 방언의 방식으로 감싼다
+pub fn quote_ident(name: &str, dialect: DatabaseType) -> String {
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        return name.to_string();
+    }
+    match dialect {
+        DatabaseType::MySQL => format!("`{}`", name.replace('`', "``")),
+        DatabaseType::MSSQL => format!("[{}]", name.replace(']', "]]")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+fn length_suffix(length: Option<i64>) -> String {
+    match length {
+        Some(-1) => "(MAX)".to_string(),
+        Some(n) if n > 0 => format!("({})", n),
+        _ => String::new(),
+    }
+}
+
+fn precision_suffix(precision: Option<i64>, scale: Option<i64>) -> String {
+    match (precision, scale) {
+        (Some(p), Some(s)) if s > 0 => format!("({},{})", p, s),
+        (Some(p), _) => format!("({})", p),
+        _ => String::new(),
+    }
+}
+
+// 원본과 같은 방언으로 다시 쓸 때의 타입
+pub fn native_type(column: &ColumnInfo, db_type: DatabaseType) -> String {
+    let data_type = column.data_type.as_str();
+    let lower = data_type.to_ascii_lowercase();
+    match (db_type, lower.as_str()) {
+        (DatabaseType::PostgreSQL, "character varying" | "character" | "bit varying" | "bit")
+        | (DatabaseType::MySQL, "varchar" | "char" | "varbinary" | "binary")
+        | (DatabaseType::MSSQL, "varchar" | "nvarchar" | "char" | "nchar" | "varbinary" | "binary")
+        | (DatabaseType::Oracle, "varchar2" | "nvarchar2" | "char" | "nchar" | "raw") => {
+            format!("{}{}", data_type, length_suffix(column.max_length))
+        }
+        (DatabaseType::PostgreSQL, "numeric")
+        | (DatabaseType::MySQL, "decimal")
+        | (DatabaseType::MSSQL, "decimal" | "numeric")
+        | (DatabaseType::Oracle, "number") => {
+            format!("{}{}", data_type, precision_suffix(column.numeric_precision, column.numeric_scale))
+        }
+        // sys.columns.scale 은 초 이하 자릿수
+        (DatabaseType::MSSQL, "datetime2" | "datetimeoffset" | "time") => {
+            format!("{}({})", data_type, column.numeric_scale.unwrap_or(7))
+        }
+        // information_schema 는 배열 타입을 udt_name (_int4) 으로 보여준다
+        (DatabaseType::PostgreSQL, _) if data_type.starts_with('_') => format!("{}[]", &data_type[1..]),
+        _ => data_type.to_string(),
+    }
+}
+
+// column_kind 가 Oracle NUMBER 의 정수 여부를 알 수 있도록 자릿수를 붙인다
+pub fn column_kind_of(column: &ColumnInfo, db_type: DatabaseType) -> ColumnKind {
+    if db_type == DatabaseType::Oracle && column.data_type.eq_ignore_ascii_case("NUMBER") {
+        let name = format!("NUMBER{}", precision_suffix(column.numeric_precision, column.numeric_scale));
+        return column_kind(db_type, &name);
+    }
+    column_kind(db_type, &column.data_type)
+}
+
+// 다른 방언으로 옮길 때의 타입. 길이와 자릿수는 가능한 한 유지한다
+fn mapped_type(column: &ColumnInfo, source: DatabaseType, target: DatabaseType) -> String {
+    let kind = column_kind_of(column, source);
+    match (kind, column.max_length) {
+        (ColumnKind::Utf8, Some(n)) if n > 0 => {
+            return match target {
+                DatabaseType::MySQL if n <= 16383 => format!("VARCHAR({})", n),
+                DatabaseType::MySQL => "LONGTEXT".to_string(),
+                DatabaseType::MSSQL if n <= 4000 => format!("NVARCHAR({})", n),
+                DatabaseType::MSSQL => "NVARCHAR(MAX)".to_string(),
+                DatabaseType::Oracle if n <= 4000 => format!("VARCHAR2({})", n),
+                DatabaseType::Oracle => "CLOB".to_string(),
+                _ => format!("VARCHAR({})", n),
+            };
+        }
+        (ColumnKind::Binary, Some(n)) if n > 0 => {
+            return match target {
+                DatabaseType::PostgreSQL => "BYTEA".to_string(),
+                DatabaseType::MySQL => format!("VARBINARY({})", n),
+                DatabaseType::MSSQL if n <= 8000 => format!("VARBINARY({})", n),
+                DatabaseType::Oracle if n <= 2000 => format!("RAW({})", n),
+                _ => target_column_type(kind, target, 0),
+            };
+        }
+        _ => {}
+    }
+    if let (ColumnKind::Decimal, Some(precision)) = (kind, column.numeric_precision) {
+        let max = if target == DatabaseType::MySQL { 65 } else { 38 };
+        let suffix = precision_suffix(Some(precision.min(max)), column.numeric_scale);
+        return match target {
+            DatabaseType::PostgreSQL => format!("NUMERIC{}", suffix),
+            DatabaseType::Oracle => format!("NUMBER{}", suffix),
+            _ => format!("DECIMAL{}", suffix),
+        };
+    }
+    let scale = column.numeric_scale.unwrap_or(0).clamp(0, 30) as u32;
+    target_column_type(kind, target, scale)
+}
+
+pub fn column_type(column: &ColumnInfo, source: DatabaseType, target: DatabaseType) -> String {
+    if source == target {
+        native_type(column, source)
+    } else {
+        mapped_type(column, source, target)
+    }
+}
+
+// MySQL 의 column_default 는 문자열 기본값을 따옴표 없이 돌려준다
+fn mysql_default(column: &ColumnInfo, default: &str) -> String {
+    let numeric = !matches!(column_kind(DatabaseType::MySQL, &column.data_type), ColumnKind::Utf8);
+    if default.contains('(')
+        || default.eq_ignore_ascii_case("CURRENT_TIMESTAMP")
+        || (numeric && default.parse::<f64>().is_ok())
+    {
+        default.to_string()
+    } else {
+        string_literal(default, DatabaseType::MySQL)
+    }
+}
+
+// 다른 방언으로 옮길 수 있는 기본값 (숫자, 문자열, 현재 시각) 만 남긴다
+fn portable_default(default: &str) -> Option<String> {
+    let mut value = default.trim();
+    // MSSQL 은 ((0)), ('x') 처럼 괄호로 감싸 저장한다
+    while value.starts_with('(') && value.ends_with(')') {
+        value = value[1..value.len() - 1].trim();
+    }
+    // PostgreSQL 은 'x'::character varying 처럼 형변환을 붙인다
+    if let Some(pos) = value.rfind("::") {
+        if value[..pos].ends_with('\'') {
+            value = &value[..pos];
+        }
+    }
+    let upper = value.to_ascii_uppercase();
+    if matches!(
+        upper.as_str(),
+        "CURRENT_TIMESTAMP" | "NOW()" | "GETDATE()" | "SYSDATETIME()" | "SYSDATE" | "SYSTIMESTAMP"
+    ) {
+        return Some("CURRENT_TIMESTAMP".to_string());
+    }
+    let quoted = value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'');
+    if quoted || value.parse::<f64>().is_ok() {
+        Some(value.to_string())
+    } else {
+        None
+    }
+}
+
+pub fn default_clause(column: &ColumnInfo, source: DatabaseType, target: DatabaseType) -> Option<String> {
+    let default = column.default.as_deref()?.trim();
+    if default.is_empty() || default.eq_ignore_ascii_case("NULL") {
+        return None;
+    }
+    let default = if source == DatabaseType::MySQL {
+        mysql_default(column, default)
+    } else {
+        default.to_string()
+    };
+    if source == target {
+        Some(default)
+    } else {
+        portable_default(&default)
+    }
+}
+
+// MySQL 의 PRIMARY, Oracle 의 SYS_C... 처럼 시스템이 붙인 이름은 옮기지 않는다
+pub fn constraint_prefix(name: &str, dialect: DatabaseType) -> String {
+    if name.eq_ignore_ascii_case("PRIMARY") || name.starts_with("SYS_C") {
+        String::new()
+    } else {
+        format!("CONSTRAINT {} ", quote_ident(name, dialect))
+    }
+}
+
+fn column_list(columns: &[String], dialect: DatabaseType) -> String {
+    columns
+        .iter()
+        .map(|column| quote_ident(column, dialect))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// NO ACTION 은 기본값이라 쓰지 않고, 대상이 지원하지 않는 동작은 뺀다
+fn referential_clause(event: &str, action: Option<&str>, target: DatabaseType) -> String {
+    let supported = match (action, target) {
+        (None | Some("NO ACTION"), _) => false,
+        (Some(_), DatabaseType::Oracle) => event == "DELETE" && matches!(action, Some("CASCADE" | "SET NULL")),
+        (Some("RESTRICT"), DatabaseType::MSSQL) => false,
+        (Some("SET DEFAULT"), DatabaseType::MySQL) => false,
+        _ => true,
+    };
+    match action {
+        Some(action) if supported => format!(" ON {} {}", event, action),
+        _ => String::new(),
+    }
+}
+
+fn check_clause(definition: &str) -> String {
+    let definition = definition.trim();
+    if definition.to_ascii_uppercase().starts_with("CHECK") {
+        definition.to_string()
+    } else {
+        format!("CHECK ({})", definition)
+    }
+}
+
+// CREATE TABLE 안에 들어가는 컬럼 정의
+pub fn column_definition(column: &ColumnInfo, source: DatabaseType, target: DatabaseType) -> String {
+    let mut line = format!("{} {}", quote_ident(&column.name, target), column_type(column, source, target));
+    if let Some(default) = default_clause(column, source, target) {
+        line.push_str(&format!(" DEFAULT {}", default));
+    }
+    if !column.nullable {
+        line.push_str(" NOT NULL");
+    }
+    if target == DatabaseType::MySQL {
+        if let Some(comment) = &column.comment {
+            line.push_str(&format!(" COMMENT {}", string_literal(comment, target)));
+        }
+    }
+    line
+}
+
+// CHECK 조건식은 방언마다 문법이 달라 같은 방언일 때만 옮긴다
+pub fn constraint_definition(
+    constraint: &ConstraintInfo,
+    source: DatabaseType,
+    target: DatabaseType,
+) -> Option<String> {
+    let prefix = constraint_prefix(&constraint.name, target);
+    match constraint.kind {
+        ConstraintKind::PrimaryKey => Some(format!("{}PRIMARY KEY ({})", prefix, column_list(&constraint.columns, target))),
+        ConstraintKind::Unique => Some(format!("{}UNIQUE ({})", prefix, column_list(&constraint.columns, target))),
+        ConstraintKind::Check => match &constraint.definition {
+            Some(check) if source == target => Some(format!("{}{}", prefix, check_clause(check))),
+            _ => None,
+        },
+    }
+}
+
+// 컬럼 이름이 아닌 항목은 식 인덱스의 식이라 다른 방언으로 옮기지 않는다
+pub fn index_statement(
+    table: &str,
+    index: &IndexInfo,
+    column_names: &[&str],
+    source: DatabaseType,
+    target: DatabaseType,
+) -> Option<String> {
+    let expression = index.columns.iter().any(|c| !column_names.contains(&c.as_str()));
+    if expression && source != target {
+        return None;
+    }
+    let columns = index
+        .columns
+        .iter()
+        .map(|c| if column_names.contains(&c.as_str()) { quote_ident(c, target) } else { c.clone() })
+        .collect::<Vec<_>>();
+    Some(format!(
+        "CREATE {}INDEX {} ON {} ({});\n",
+        if index.unique { "UNIQUE " } else { "" },
+        quote_ident(&index.name, target),
+        quote_ident(table, target),
+        columns.join(", ")
+    ))
+}
+
+pub fn foreign_key_statement(table: &str, fk: &ForeignKeyInfo, target: DatabaseType) -> String {
+    let referenced = if fk.referenced_schema == fk.schema {
+        quote_ident(&fk.referenced_table, target)
+    } else {
+        format!("{}.{}", quote_ident(&fk.referenced_schema, target), quote_ident(&fk.referenced_table, target))
+    };
+    format!(
+        "ALTER TABLE {} ADD {}FOREIGN KEY ({}) REFERENCES {} ({}){}{};\n",
+        quote_ident(table, target),
+        constraint_prefix(&fk.name, target),
+        column_list(&fk.columns, target),
+        referenced,
+        column_list(&fk.referenced_columns, target),
+        referential_clause("DELETE", fk.on_delete.as_deref(), target),
+        referential_clause("UPDATE", fk.on_update.as_deref(), target)
+    )
+}
+
+// 제약 조건이 만든 인덱스는 CREATE TABLE 에 이미 들어 있다
+pub fn is_constraint_index(index: &IndexInfo, constraints: &[ConstraintInfo]) -> bool {
+    index.primary || constraints.iter().any(|c| c.name == index.name)
+}
+
+// CREATE TABLE, 인덱스, 외래 키, 주석 순서의 스크립트
+pub fn render_ddl(definition: &TableDefinition, source: DatabaseType, target: DatabaseType) -> String {
+    let table = quote_ident(&definition.name, target);
+    let mut notes = Vec::new();
+    let mut lines = definition
+        .columns
+        .iter()
+        .map(|column| format!("    {}", column_definition(column, source, target)))
+        .collect::<Vec<_>>();
+
+    for constraint in &definition.constraints {
+        match constraint_definition(constraint, source, target) {
+            Some(line) => lines.push(format!("    {}", line)),
+            None => notes.push(format!("-- CHECK constraint {} was not translated", constraint.name)),
+        }
+    }
+
+    let mut script = format!("CREATE TABLE {} (\n{}\n);\n", table, lines.join(",\n"));
+
+    let column_names = definition.columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+    for index in &definition.indexes {
+        if is_constraint_index(index, &definition.constraints) {
+            continue;
+        }
+        match index_statement(&definition.name, index, &column_names, source, target) {
+            Some(statement) => script.push_str(&statement),
+            None => notes.push(format!("-- Expression index {} was not translated", index.name)),
+        }
+    }
+
+    for fk in &definition.foreign_keys {
+        script.push_str(&foreign_key_statement(&definition.name, fk, target));
+    }
+
+    if matches!(target, DatabaseType::PostgreSQL | DatabaseType::Oracle) {
+        for column in &definition.columns {
+            if let Some(comment) = &column.comment {
+                script.push_str(&format!(
+                    "COMMENT ON COLUMN {}.{} IS {};\n",
+                    table,
+                    quote_ident(&column.name, target),
+                    string_literal(comment, target)
+                ));
+            }
+        }
+    }
+
+    for note in notes {
+        script.push_str(&note);
+        script.push('\n');
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.into(),
+            ordinal: 1,
+            data_type: data_type.into(),
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            nullable,
+            default: None,
+            comment: None,
+        }
+    }
+
+    fn books() -> TableDefinition {
+        let mut title = column("title", "character varying", false);
+        title.max_length = Some(200);
+        title.default = Some("'untitled'::character varying".into());
+        let mut price = column("price", "numeric", true);
+        price.numeric_precision = Some(10);
+        price.numeric_scale = Some(2);
+        price.comment = Some("list price".into());
+
+        TableDefinition {
+            schema: None,
+            name: "books".into(),
+            columns: vec![column("id", "integer", false), title, price, column("author_id", "integer", true)],
+            constraints: vec![
+                ConstraintInfo {
+                    table: "books".into(),
+                    name: "books_pkey".into(),
+                    kind: ConstraintKind::PrimaryKey,
+                    columns: vec!["id".into()],
+                    definition: None,
+                },
+                ConstraintInfo {
+                    table: "books".into(),
+                    name: "price_check".into(),
+                    kind: ConstraintKind::Check,
+                    columns: vec!["price".into()],
+                    definition: Some("CHECK ((price > (0)::numeric))".into()),
+                },
+            ],
+            indexes: vec![
+                IndexInfo { table: "books".into(), name: "books_pkey".into(), columns: vec!["id".into()], unique: true, primary: true },
+                IndexInfo { table: "books".into(), name: "books_title_idx".into(), columns: vec!["title".into()], unique: false, primary: false },
+            ],
+            foreign_keys: vec![ForeignKeyInfo {
+                name: "books_author_fk".into(),
+                schema: "public".into(),
+                table: "books".into(),
+                columns: vec!["author_id".into()],
+                referenced_schema: "public".into(),
+                referenced_table: "authors".into(),
+                referenced_columns: vec!["id".into()],
+                on_delete: Some("SET NULL".into()),
+                on_update: Some("CASCADE".into()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_same_dialect() {
+        let script = render_ddl(&books(), DatabaseType::PostgreSQL, DatabaseType::PostgreSQL);
+        assert_eq!(
+            script,
+            "CREATE TABLE books (\n\
+             \x20   id integer NOT NULL,\n\
+             \x20   title character varying(200) DEFAULT 'untitled'::character varying NOT NULL,\n\
+             \x20   price numeric(10,2),\n\
+             \x20   author_id integer,\n\
+             \x20   CONSTRAINT books_pkey PRIMARY KEY (id),\n\
+             \x20   CONSTRAINT price_check CHECK ((price > (0)::numeric))\n\
+             );\n\
+             CREATE INDEX books_title_idx ON books (title);\n\
+             ALTER TABLE books ADD CONSTRAINT books_author_fk FOREIGN KEY (author_id) REFERENCES authors (id) \
+             ON DELETE SET NULL ON UPDATE CASCADE;\n\
+             COMMENT ON COLUMN books.price IS 'list price';\n"
+        );
+    }
+
+    #[test]
+    fn test_render_for_oracle() {
+        let script = render_ddl(&books(), DatabaseType::PostgreSQL, DatabaseType::Oracle);
+        assert!(script.contains("    id NUMBER(19) NOT NULL,\n"));
+        assert!(script.contains("    title VARCHAR2(200) DEFAULT 'untitled' NOT NULL,\n"));
+        assert!(script.contains("    price NUMBER(10,2),\n"));
+        // Oracle 에는 ON UPDATE 가 없다
+        assert!(script.contains("REFERENCES authors (id) ON DELETE SET NULL;\n"));
+        assert!(script.contains("-- CHECK constraint price_check was not translated\n"));
+    }
+
+    #[test]
+    fn test_portable_defaults() {
+        assert_eq!(portable_default("((0))").as_deref(), Some("0"));
+        assert_eq!(portable_default("(getdate())").as_deref(), Some("CURRENT_TIMESTAMP"));
+        assert_eq!(portable_default("nextval('books_id_seq'::regclass)"), None);
+    }
+}
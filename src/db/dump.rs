@@ -0,0 +1,265 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime};
+use serde::Deserialize;
+use crate::db::copy::{hex, CopyPlan};
+use crate::db::import::{is_valid_column_name, ColumnMapping};
+use crate::db::source::{is_valid_identifier, SourceSpec};
+use crate::db::types::{CompactResultSet, DatabaseType};
+use crate::formats::typed::{parse_date_days, parse_decimal, parse_time_micros, parse_timestamp_micros, ColumnKind};
+use base64ct::{Base64, Encoding};
+
+// MSSQL 의 VALUES 절은 1000행까지
+const MAX_ROWS_PER_INSERT: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DumpRequest {
+    pub connection_id: String,
+    #[serde(flatten)]
+    pub source: SourceSpec,
+    pub dialect: DatabaseType,
+    // 없으면 table 이름을 쓴다 (query 로 내보낼 때는 필수)
+    pub target_table: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<ColumnMapping>,
+    #[serde(default = "default_create_table")]
+    pub create_table: bool,
+    #[serde(default = "default_rows_per_insert")]
+    pub rows_per_insert: usize,
+}
+
+fn default_create_table() -> bool {
+    true
+}
+
+fn default_rows_per_insert() -> usize {
+    100
+}
+
+impl DumpRequest {
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.source.validate()?;
+        let target_table = self.target_table().ok_or("target_table is required when exporting a query")?;
+        if !is_valid_identifier(target_table) {
+            return Err(format!("Invalid table name: {}", target_table).into());
+        }
+        if let Some(mapping) = self.columns.iter().find(|m| !is_valid_column_name(&m.target)) {
+            return Err(format!("Invalid column name: {}", mapping.target).into());
+        }
+        if self.dialect == DatabaseType::Redis {
+            return Err("Redis is not a SQL dialect".into());
+        }
+        if self.rows_per_insert == 0 || self.rows_per_insert > MAX_ROWS_PER_INSERT {
+            return Err(format!("rows_per_insert must be between 1 and {}", MAX_ROWS_PER_INSERT).into());
+        }
+        Ok(())
+    }
+
+    pub fn target_table(&self) -> Option<&str> {
+        self.target_table.as_deref().or(self.source.table.as_deref())
+    }
+}
+
+pub fn string_literal(text: &str, dialect: DatabaseType) -> String {
+    match dialect {
+        // MySQL 은 기본 sql_mode 에서 백슬래시도 이스케이프 문자다
+        DatabaseType::MySQL => format!("'{}'", text.replace('\\', "\\\\").replace('\'', "''")),
+        DatabaseType::MSSQL => format!("N'{}'", text.replace('\'', "''")),
+        _ => format!("'{}'", text.replace('\'', "''")),
+    }
+}
+
+fn typed_literal(kind: ColumnKind, dialect: DatabaseType, text: &str) -> Option<String> {
+    let literal = match (kind, dialect) {
+        (ColumnKind::Boolean, _) => {
+            let value = matches!(text, "true" | "1");
+            match dialect {
+                DatabaseType::PostgreSQL | DatabaseType::MySQL => value.to_string().to_uppercase(),
+                _ => (value as u8).to_string(),
+            }
+        }
+        (ColumnKind::Int64 | ColumnKind::UInt64 | ColumnKind::Float64 | ColumnKind::Decimal, _) => {
+            parse_decimal(&serde_json::Value::String(text.to_string()))?.to_string()
+        }
+        // 시간 값은 해석한 값으로 다시 써서 원본 문자열이 리터럴에 그대로 들어가지 않게 한다
+        (ColumnKind::Timestamp, _) => {
+            let micros = parse_timestamp_micros(&serde_json::Value::String(text.to_string()))?;
+            let v = DateTime::from_timestamp_micros(micros)?.naive_utc();
+            match dialect {
+                DatabaseType::PostgreSQL | DatabaseType::Oracle => format!("TIMESTAMP '{}'", v.format("%Y-%m-%d %H:%M:%S%.f")),
+                DatabaseType::MySQL => format!("'{}'", v.format("%Y-%m-%d %H:%M:%S%.f")),
+                // MSSQL 은 'T' 가 들어간 ISO 8601 만 언어 설정과 무관하게 해석한다
+                _ => format!("'{}'", v.format("%Y-%m-%dT%H:%M:%S%.f")),
+            }
+        }
+        (ColumnKind::TimestampTz, _) => {
+            let v = DateTime::parse_from_rfc3339(text).ok()?;
+            match dialect {
+                DatabaseType::PostgreSQL => format!("TIMESTAMPTZ '{}'", v.to_rfc3339()),
+                DatabaseType::MySQL => format!("'{}'", v.naive_utc().format("%Y-%m-%d %H:%M:%S%.6f")),
+                DatabaseType::Oracle => format!("TIMESTAMP '{}'", v.format("%Y-%m-%d %H:%M:%S%.6f %:z")),
+                _ => format!("'{}'", v.to_rfc3339()),
+            }
+        }
+        (ColumnKind::Date, DatabaseType::PostgreSQL | DatabaseType::Oracle) => {
+            let days = parse_date_days(&serde_json::Value::String(text.to_string()))?;
+            let v = NaiveDate::from_ymd_opt(1970, 1, 1)? + Duration::days(i64::from(days));
+            format!("DATE '{}'", v.format("%Y-%m-%d"))
+        }
+        (ColumnKind::Time, DatabaseType::PostgreSQL) => {
+            let micros = parse_time_micros(&serde_json::Value::String(text.to_string()))?;
+            let v = NaiveTime::from_num_seconds_from_midnight_opt(
+                u32::try_from(micros / 1_000_000).ok()?,
+                u32::try_from(micros % 1_000_000 * 1_000).ok()?,
+            )?;
+            format!("TIME '{}'", v.format("%H:%M:%S%.f"))
+        }
+        (ColumnKind::Binary, _) => {
+            let bytes = hex(&Base64::decode_vec(text).ok()?);
+            match dialect {
+                DatabaseType::PostgreSQL => format!("decode('{}', 'hex')", bytes),
+                DatabaseType::MySQL => format!("X'{}'", bytes),
+                DatabaseType::MSSQL => format!("0x{}", bytes),
+                _ => format!("HEXTORAW('{}')", bytes),
+            }
+        }
+        _ => return None,
+    };
+    Some(literal)
+}
+
+// 값 하나를 대상 방언의 SQL 리터럴로 쓴다. 타입에 맞게 해석할 수 없으면 문자열 리터럴로 남긴다
+pub fn sql_literal(kind: ColumnKind, dialect: DatabaseType, value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::Null => return "NULL".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        other => other.to_string(),
+    };
+    typed_literal(kind, dialect, &text).unwrap_or_else(|| string_literal(&text, dialect))
+}
+
+fn insert_statement(
+    table: &str,
+    columns: &str,
+    rows: &[String],
+    dialect: DatabaseType,
+) -> String {
+    match dialect {
+        // 23c 이전 Oracle 은 다중 행 VALUES 를 지원하지 않는다
+        DatabaseType::Oracle => {
+            let targets = rows
+                .iter()
+                .map(|row| format!("  INTO {} ({}) VALUES {}", table, columns, row))
+                .collect::<Vec<_>>();
+            format!("INSERT ALL\n{}\nSELECT 1 FROM DUAL;\n", targets.join("\n"))
+        }
+        _ => format!("INSERT INTO {} ({}) VALUES\n  {};\n", table, columns, rows.join(",\n  ")),
+    }
+}
+
+// CREATE TABLE 과 rows_per_insert 행씩 묶은 INSERT 로 이루어진 스크립트
+pub fn render_dump(
+    request: &DumpRequest,
+    result_set: &CompactResultSet,
+    source_db: DatabaseType,
+) -> Result<String, String> {
+    let plan = CopyPlan::new(&request.columns, result_set, source_db)?;
+    let table = request.target_table().unwrap_or_default();
+    let dialect = request.dialect;
+    let mut script = String::new();
+
+    if request.create_table {
+        script.push_str(&plan.create_table_sql(table, &result_set.rows, dialect));
+        script.push_str(";\n\n");
+    }
+
+    let columns = plan.targets.join(", ");
+    for rows in result_set.rows.chunks(request.rows_per_insert) {
+        let values = rows
+            .iter()
+            .map(|row| {
+                let literals = plan
+                    .source_indexes
+                    .iter()
+                    .zip(&plan.kinds)
+                    .map(|(i, kind)| sql_literal(*kind, dialect, row.get(*i).unwrap_or(&serde_json::Value::Null)))
+                    .collect::<Vec<_>>();
+                format!("({})", literals.join(", "))
+            })
+            .collect::<Vec<_>>();
+        script.push_str(&insert_statement(table, &columns, &values, dialect));
+    }
+    if dialect == DatabaseType::Oracle && !result_set.rows.is_empty() {
+        script.push_str("COMMIT;\n");
+    }
+
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::ColumnMeta;
+    use serde_json::json;
+
+    #[test]
+    fn test_literals() {
+        let text = json!("O'Reilly \\ co");
+        assert_eq!(sql_literal(ColumnKind::Utf8, DatabaseType::PostgreSQL, &text), "'O''Reilly \\ co'");
+        assert_eq!(sql_literal(ColumnKind::Utf8, DatabaseType::MySQL, &text), "'O''Reilly \\\\ co'");
+        assert_eq!(sql_literal(ColumnKind::Utf8, DatabaseType::MSSQL, &text), "N'O''Reilly \\ co'");
+
+        let binary = json!("3q2+7w==");
+        assert_eq!(sql_literal(ColumnKind::Binary, DatabaseType::MSSQL, &binary), "0xdeadbeef");
+        assert_eq!(sql_literal(ColumnKind::Binary, DatabaseType::Oracle, &binary), "HEXTORAW('deadbeef')");
+
+        let timestamp = json!("2024-01-02T03:04:05");
+        assert_eq!(
+            sql_literal(ColumnKind::Timestamp, DatabaseType::Oracle, &timestamp),
+            "TIMESTAMP '2024-01-02 03:04:05'"
+        );
+        assert_eq!(
+            sql_literal(ColumnKind::Timestamp, DatabaseType::MSSQL, &json!("2024-01-02 03:04:05.25")),
+            "'2024-01-02T03:04:05.250'"
+        );
+        assert_eq!(sql_literal(ColumnKind::Date, DatabaseType::PostgreSQL, &json!("2024-01-02")), "DATE '2024-01-02'");
+        assert_eq!(sql_literal(ColumnKind::Time, DatabaseType::PostgreSQL, &json!("03:04:05")), "TIME '03:04:05'");
+        // 해석할 수 없는 시간 값은 이스케이프된 문자열 리터럴로 남긴다
+        assert_eq!(
+            sql_literal(ColumnKind::Timestamp, DatabaseType::Oracle, &json!("2024-01-02'); DROP TABLE t; --")),
+            "'2024-01-02''); DROP TABLE t; --'"
+        );
+        assert_eq!(sql_literal(ColumnKind::Date, DatabaseType::Oracle, &json!("x' || 'y")), "'x'' || ''y'");
+        assert_eq!(sql_literal(ColumnKind::Boolean, DatabaseType::MSSQL, &json!(true)), "1");
+        assert_eq!(sql_literal(ColumnKind::Int64, DatabaseType::MySQL, &json!(null)), "NULL");
+        // 숫자 컬럼에 숫자가 아닌 값이 오면 문자열 리터럴로 남긴다
+        assert_eq!(sql_literal(ColumnKind::Int64, DatabaseType::MySQL, &json!("1; DROP")), "'1; DROP'");
+    }
+
+    #[test]
+    fn test_render_oracle_dump() {
+        let request: DumpRequest = serde_json::from_value(json!({
+            "connection_id": "pg",
+            "table": "books",
+            "dialect": "Oracle",
+            "rows_per_insert": 2
+        }))
+        .unwrap();
+        let result_set = CompactResultSet {
+            columns: vec![
+                ColumnMeta { name: "id".into(), data_type: "INT4".into() },
+                ColumnMeta { name: "title".into(), data_type: "TEXT".into() },
+            ],
+            rows: vec![vec![json!(1), json!("A")], vec![json!(2), json!("B")], vec![json!(3), json!(null)]],
+            affected_rows: None,
+        };
+
+        let script = render_dump(&request, &result_set, DatabaseType::PostgreSQL).unwrap();
+        assert_eq!(
+            script,
+            "CREATE TABLE books (id NUMBER(19), title VARCHAR2(4000));\n\n\
+             INSERT ALL\n  INTO books (id, title) VALUES (1, 'A')\n  INTO books (id, title) VALUES (2, 'B')\nSELECT 1 FROM DUAL;\n\
+             INSERT ALL\n  INTO books (id, title) VALUES (3, NULL)\nSELECT 1 FROM DUAL;\n\
+             COMMIT;\n"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+
+// 드라이버별 실행 계획을 하나의 형태로 정규화한 노드
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PlanNode {
+    pub operation: String,
+    pub object_name: Option<String>,
+    pub estimated_cost: Option<f64>,
+    pub estimated_rows: Option<f64>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            object_name: None,
+            estimated_cost: None,
+            estimated_rows: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainPlan {
+    pub raw: serde_json::Value,
+    pub plan: Vec<PlanNode>,
+}
+
+fn as_f64(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+// EXPLAIN (FORMAT JSON) 결과: [{"Plan": {...}}]
+pub fn normalize_postgres(raw: &serde_json::Value) -> Vec<PlanNode> {
+    fn node(plan: &serde_json::Value) -> PlanNode {
+        let mut result = PlanNode::new(plan["Node Type"].as_str().unwrap_or("Unknown"));
+        result.object_name = plan["Relation Name"]
+            .as_str()
+            .or_else(|| plan["Index Name"].as_str())
+            .map(str::to_string);
+        result.estimated_cost = as_f64(&plan["Total Cost"]);
+        result.estimated_rows = as_f64(&plan["Plan Rows"]);
+        if let Some(children) = plan["Plans"].as_array() {
+            result.children = children.iter().map(node).collect();
+        }
+        result
+    }
+
+    raw.as_array()
+        .map(|plans| plans.iter().map(|p| node(&p["Plan"])).collect())
+        .unwrap_or_default()
+}
+
+// EXPLAIN FORMAT=JSON 결과: {"query_block": {...}}
+pub fn normalize_mysql(raw: &serde_json::Value) -> Vec<PlanNode> {
+    fn node(name: &str, value: &serde_json::Value) -> PlanNode {
+        let mut node = match (name, value["access_type"].as_str()) {
+            ("table", Some(access_type)) => PlanNode::new(format!("table ({})", access_type)),
+            _ => PlanNode::new(name),
+        };
+        node.object_name = value["table_name"].as_str().map(str::to_string);
+        let cost_info = &value["cost_info"];
+        node.estimated_cost = as_f64(&cost_info["query_cost"])
+            .or_else(|| as_f64(&cost_info["prefix_cost"]))
+            .or_else(|| as_f64(&cost_info["sort_cost"]));
+        node.estimated_rows = as_f64(&value["rows_produced_per_join"])
+            .or_else(|| as_f64(&value["rows_examined_per_scan"]));
+        node.children = children(value);
+        node
+    }
+    fn children(value: &serde_json::Value) -> Vec<PlanNode> {
+        let mut result = Vec::new();
+        if let Some(map) = value.as_object() {
+            for (key, child) in map {
+                if key == "cost_info" {
+                    continue;
+                }
+                match child {
+                    serde_json::Value::Object(_) => result.push(node(key, child)),
+                    serde_json::Value::Array(items) if items.iter().any(|i| i.is_object()) => {
+                        let mut group = PlanNode::new(key.as_str());
+                        for item in items {
+                            group.children.extend(children(item));
+                        }
+                        result.push(group);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        result
+    }
+
+    children(raw)
+}
+
+// SET SHOWPLAN_XML ON 결과: 중첩된 <RelOp> 요소
+pub fn normalize_mssql(raw: &str) -> Result<Vec<PlanNode>, Box<dyn std::error::Error + Send + Sync>> {
+    fn attr(element: &BytesStart, name: &[u8]) -> Option<String> {
+        element
+            .attributes()
+            .flatten()
+            .find(|a| a.key.local_name().as_ref() == name)
+            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+    }
+
+    let mut reader = Reader::from_str(raw);
+    let mut roots = Vec::new();
+    let mut stack: Vec<PlanNode> = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"RelOp" => {
+                let mut node = PlanNode::new(attr(&e, b"PhysicalOp").unwrap_or_default());
+                node.estimated_cost = attr(&e, b"EstimatedTotalSubtreeCost").and_then(|v| v.parse().ok());
+                node.estimated_rows = attr(&e, b"EstimateRows").and_then(|v| v.parse().ok());
+                stack.push(node);
+            }
+            Event::End(e) if e.local_name().as_ref() == b"RelOp" => {
+                if let Some(node) = stack.pop() {
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => roots.push(node),
+                    }
+                }
+            }
+            Event::Empty(e) | Event::Start(e) if e.local_name().as_ref() == b"Object" => {
+                if let Some(current) = stack.last_mut() {
+                    if current.object_name.is_none() {
+                        current.object_name = attr(&e, b"Table")
+                            .map(|t| t.trim_matches(|c| c == '[' || c == ']').to_string());
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(roots)
+}
+
+// PLAN_TABLE 의 한 행 (id, parent_id, operation, options, object_name, cost, cardinality)
+#[derive(Debug, Clone)]
+pub struct OraclePlanRow {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub operation: String,
+    pub options: Option<String>,
+    pub object_name: Option<String>,
+    pub cost: Option<f64>,
+    pub cardinality: Option<f64>,
+}
+
+pub fn normalize_oracle(rows: &[OraclePlanRow]) -> Vec<PlanNode> {
+    fn build(rows: &[OraclePlanRow], parent_id: Option<i64>) -> Vec<PlanNode> {
+        rows.iter()
+            .filter(|row| row.parent_id == parent_id)
+            .map(|row| {
+                let operation = match &row.options {
+                    Some(options) => format!("{} {}", row.operation, options),
+                    None => row.operation.clone(),
+                };
+                let mut node = PlanNode::new(operation);
+                node.object_name = row.object_name.clone();
+                node.estimated_cost = row.cost;
+                node.estimated_rows = row.cardinality;
+                node.children = build(rows, Some(row.id));
+                node
+            })
+            .collect()
+    }
+
+    build(rows, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_postgres() {
+        let raw = json!([{
+            "Plan": {
+                "Node Type": "Hash Join",
+                "Total Cost": 35.5,
+                "Plan Rows": 120,
+                "Plans": [
+                    {"Node Type": "Seq Scan", "Relation Name": "books", "Total Cost": 12.0, "Plan Rows": 200},
+                    {"Node Type": "Index Scan", "Index Name": "users_pkey", "Total Cost": 8.3, "Plan Rows": 1}
+                ]
+            }
+        }]);
+
+        let plan = normalize_postgres(&raw);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].operation, "Hash Join");
+        assert_eq!(plan[0].estimated_cost, Some(35.5));
+        assert_eq!(plan[0].children.len(), 2);
+        assert_eq!(plan[0].children[0].object_name.as_deref(), Some("books"));
+        assert_eq!(plan[0].children[1].object_name.as_deref(), Some("users_pkey"));
+    }
+
+    #[test]
+    fn test_normalize_mysql() {
+        let raw = json!({
+            "query_block": {
+                "select_id": 1,
+                "cost_info": {"query_cost": "20.25"},
+                "nested_loop": [
+                    {"table": {"table_name": "books", "access_type": "ALL", "rows_examined_per_scan": 100, "cost_info": {"prefix_cost": "10.25"}}},
+                    {"table": {"table_name": "users", "access_type": "eq_ref", "rows_produced_per_join": 100, "cost_info": {"prefix_cost": "20.25"}}}
+                ]
+            }
+        });
+
+        let plan = normalize_mysql(&raw);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].operation, "query_block");
+        assert_eq!(plan[0].estimated_cost, Some(20.25));
+        let nested_loop = &plan[0].children[0];
+        assert_eq!(nested_loop.operation, "nested_loop");
+        assert_eq!(nested_loop.children[0].operation, "table (ALL)");
+        assert_eq!(nested_loop.children[0].object_name.as_deref(), Some("books"));
+        assert_eq!(nested_loop.children[1].estimated_rows, Some(100.0));
+    }
+
+    #[test]
+    fn test_normalize_mssql() {
+        let raw = r#"<ShowPlanXML xmlns="http://schemas.microsoft.com/sqlserver/2004/07/showplan">
+            <RelOp NodeId="0" PhysicalOp="Nested Loops" EstimateRows="10" EstimatedTotalSubtreeCost="0.5">
+                <NestedLoops>
+                    <RelOp NodeId="1" PhysicalOp="Clustered Index Scan" EstimateRows="10" EstimatedTotalSubtreeCost="0.2">
+                        <IndexScan><Object Database="[test]" Schema="[dbo]" Table="[books]" /></IndexScan>
+                    </RelOp>
+                </NestedLoops>
+            </RelOp>
+        </ShowPlanXML>"#;
+
+        let plan = normalize_mssql(raw).unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].operation, "Nested Loops");
+        assert_eq!(plan[0].object_name, None);
+        assert_eq!(plan[0].children[0].object_name.as_deref(), Some("books"));
+        assert_eq!(plan[0].children[0].estimated_cost, Some(0.2));
+    }
+
+    #[test]
+    fn test_normalize_oracle() {
+        let rows = vec![
+            OraclePlanRow { id: 0, parent_id: None, operation: "SELECT STATEMENT".into(), options: None, object_name: None, cost: Some(3.0), cardinality: Some(1.0) },
+            OraclePlanRow { id: 1, parent_id: Some(0), operation: "TABLE ACCESS".into(), options: Some("FULL".into()), object_name: Some("BOOKS".into()), cost: Some(3.0), cardinality: Some(1.0) },
+        ];
+
+        let plan = normalize_oracle(&rows);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].children[0].operation, "TABLE ACCESS FULL");
+        assert_eq!(plan[0].children[0].object_name.as_deref(), Some("BOOKS"));
+    }
+}
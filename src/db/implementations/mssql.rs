@@ -1,60 +1,555 @@
-use tiberius::{Client, Config};
+use tiberius::numeric::Numeric;
+use tiberius::time::{DateTime as SqlDateTime, SmallDateTime, Time};
+use tiberius::{Client, ColumnData, Config, IntoSql, QueryItem, QueryStream, Row, ToSql, TokenRow};
 use tokio::net::TcpStream;
-use tokio_util::compat::{TokioAsyncWriteCompatExt, Compat};
-use crate::db::connection::{Connection, ConnectionConfig};
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+use crate::db::connection::{Connection, ConnectionConfig, RowBatcher, RECEIVER_CLOSED};
+use crate::db::explain::{normalize_mssql, ExplainPlan};
+use crate::db::import::BatchOutcome;
+use crate::db::procedure::{ParamType, ProcedureCall, ProcedureResult};
+use crate::db::statement::statement_words;
+use crate::db::types::{ColumnMeta, DatabaseType, ResultSet};
+use crate::db::values;
+use base64ct::{Base64, Encoding};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
+use tokio::sync::mpsc;
 
 #[derive(Debug,Clone)]
 pub struct MSSQLConnection {
     config: Config,
+    read_only: bool,
 }
 
 impl MSSQLConnection {
     pub async fn new(config: ConnectionConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let mut tiberius_config = Config::from_ado_string(&config.connection_string)?;
         tiberius_config.trust_cert();
+        // ApplicationIntent=ReadOnly. 가용성 그룹이면 읽기 전용 보조 복제본으로 연결되지만 주 서버는
+        // 이 설정을 무시한다. 그래서 MSSQL 의 읽기 전용은 게이트웨이의 문장 분류와 요청마다 롤백하는
+        // 트랜잭션(connect, finish)에 기대고, 확실히 막으려면 db_datareader 역할만 가진 로그인을 쓴다
+        if config.read_only {
+            tiberius_config.readonly(true);
+        }
 
-        Ok(Self { config: tiberius_config })
+        Ok(Self { config: tiberius_config, read_only: config.read_only })
     }
-}
 
-#[async_trait::async_trait]
-impl Connection for MSSQLConnection {
-    async fn execute_query(&self, query: &str) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
-        let mut results = Vec::new();
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub async fn stream_rows(
+        &self,
+        query: &str,
+        batch_size: usize,
+        sender: mpsc::Sender<ResultSet>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = self.connect().await?;
+        let result = async {
+            let mut stream = client.simple_query(query).await?;
+            let mut batcher = RowBatcher::new(batch_size);
+            while let Some(item) = stream.try_next().await? {
+                match item {
+                    // 두 번째 결과 집합부터는 읽지 않는다
+                    QueryItem::Metadata(_) if batcher.has_columns() => break,
+                    QueryItem::Metadata(meta) => batcher.set_columns(
+                        meta.columns()
+                            .iter()
+                            .map(|column| ColumnMeta {
+                                name: column.name().to_string(),
+                                data_type: format!("{:?}", column.column_type()),
+                            })
+                            .collect(),
+                    ),
+                    QueryItem::Row(row) => {
+                        if let Some(batch) = batcher.push(row_to_json(&row)) {
+                            sender.send(batch).await.map_err(|_| RECEIVER_CLOSED)?;
+                        }
+                    }
+                }
+            }
+            if let Some(batch) = batcher.finish() {
+                sender.send(batch).await.map_err(|_| RECEIVER_CLOSED)?;
+            }
+            Ok(())
+        }
+        .await;
+        self.finish(client, result).await
+    }
+
+    // SQL Server 에는 세션 단위 읽기 전용 모드가 없다. 읽기 전용 연결은 호출마다 새로 연결해서
+    // 트랜잭션을 열고, 요청이 끝나면 finish 가 커밋하지 않고 롤백한다
+    async fn connect(&self) -> Result<Client<Compat<TcpStream>>, Box<dyn std::error::Error + Send + Sync>> {
         let tcp = TcpStream::connect(self.config.get_addr()).await?;
         tcp.set_nodelay(true)?;
         let mut client = Client::connect(self.config.clone(), tcp.compat_write()).await?;
-        let mut stream = client.query(query, &[]).await?;
-        let mut row_stream = stream.into_row_stream();
-
-        while let Some(row_result) = row_stream.next().await {
-            let row = row_result?;
-            let mut row_map = HashMap::new();
-            for (i, column) in row.columns().iter().enumerate() {
-                let value = match row.get::<i32, _>(i) {
-                    Some(v) => serde_json::Value::Number(v.into()),
-                    None => match row.get::<&str, _>(i) {
-                        Some(v) => serde_json::Value::String(v.to_string()),
-                        None => match row.get::<bool, _>(i) {
-                            Some(v) => serde_json::Value::Bool(v),
-                            None => match row.get::<f64, _>(i) {
-                                Some(v) => serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into())),
-                                None => serde_json::Value::Null,
-                            },
+        if self.read_only {
+            client
+                .simple_query("SET XACT_ABORT ON; SET IMPLICIT_TRANSACTIONS OFF; BEGIN TRANSACTION")
+                .await?
+                .into_results()
+                .await?;
+        }
+        Ok(client)
+    }
+
+    // 요청이 끝나면 읽기 전용 연결이 연 트랜잭션을 명시적으로 롤백하고 연결을 닫는다.
+    // 요청이 실패했으면 그 오류를 돌려준다
+    async fn finish<T>(
+        &self,
+        mut client: Client<Compat<TcpStream>>,
+        result: Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        let rolled_back = if self.read_only {
+            match client.simple_query("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION").await {
+                Ok(stream) => stream.into_results().await.map(|_| ()),
+                Err(e) => Err(e),
+            }
+        } else {
+            Ok(())
+        };
+        let value = result?;
+        rolled_back?;
+        client.close().await?;
+        Ok(value)
+    }
+}
+
+fn row_to_json(row: &Row) -> serde_json::Value {
+    let mut row_map = HashMap::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = match row.try_get::<i32, _>(i).ok().flatten() {
+            Some(v) => serde_json::Value::Number(v.into()),
+            None => match row.try_get::<i64, _>(i).ok().flatten() {
+                Some(v) => serde_json::Value::Number(v.into()),
+                None => match row.try_get::<&str, _>(i).ok().flatten() {
+                    Some(v) => serde_json::Value::String(v.to_string()),
+                    None => match row.try_get::<bool, _>(i).ok().flatten() {
+                        Some(v) => serde_json::Value::Bool(v),
+                        None => match row.try_get::<f64, _>(i).ok().flatten() {
+                            Some(v) => serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into())),
+                            None => typed_value(row, i),
                         },
                     },
-                };
-                row_map.insert(column.name().to_string(), value);
+                },
+            },
+        };
+        row_map.insert(column.name().to_string(), value);
+    }
+    serde_json::Value::Object(serde_json::Map::from_iter(row_map))
+}
+
+// 기본 타입으로 읽히지 않는 컬럼 (작은 정수, 소수, 날짜/시간, 바이너리)
+fn typed_value(row: &Row, i: usize) -> serde_json::Value {
+    if let Some(v) = row.try_get::<u8, _>(i).ok().flatten() {
+        serde_json::Value::Number(v.into())
+    } else if let Some(v) = row.try_get::<i16, _>(i).ok().flatten() {
+        serde_json::Value::Number(v.into())
+    } else if let Some(v) = row.try_get::<f32, _>(i).ok().flatten() {
+        values::float_value(v.into())
+    } else if let Some(v) = row.try_get::<Decimal, _>(i).ok().flatten() {
+        values::decimal_value(v)
+    } else if let Some(v) = row.try_get::<DateTime<Utc>, _>(i).ok().flatten() {
+        values::datetime_tz_value(v)
+    } else if let Some(v) = row.try_get::<NaiveDateTime, _>(i).ok().flatten() {
+        values::datetime_value(v)
+    } else if let Some(v) = row.try_get::<NaiveDate, _>(i).ok().flatten() {
+        values::date_value(v)
+    } else if let Some(v) = row.try_get::<NaiveTime, _>(i).ok().flatten() {
+        values::time_value(v)
+    } else if let Some(v) = row.try_get::<&[u8], _>(i).ok().flatten() {
+        values::binary_value(v)
+    } else if let Some(v) = row.try_get::<tiberius::Uuid, _>(i).ok().flatten() {
+        serde_json::Value::String(v.to_string())
+    } else {
+        serde_json::Value::Null
+    }
+}
+
+// 결과 집합마다 메타데이터가 먼저 오므로 그 단위로 나눈다 (영향받은 행 수는 tiberius 가 노출하지 않는다)
+async fn collect_result_sets(mut stream: QueryStream<'_>) -> Result<Vec<ResultSet>, tiberius::error::Error> {
+    let mut result_sets: Vec<ResultSet> = Vec::new();
+    while let Some(item) = stream.try_next().await? {
+        match item {
+            QueryItem::Metadata(meta) => result_sets.push(ResultSet {
+                columns: meta
+                    .columns()
+                    .iter()
+                    .map(|column| ColumnMeta {
+                        name: column.name().to_string(),
+                        data_type: format!("{:?}", column.column_type()),
+                    })
+                    .collect(),
+                ..Default::default()
+            }),
+            QueryItem::Row(row) => {
+                if let Some(current) = result_sets.last_mut() {
+                    current.rows.push(row_to_json(&row));
+                }
             }
-            results.push(serde_json::Value::Object(serde_json::Map::from_iter(row_map)));
         }
+    }
+    Ok(result_sets)
+}
+
+// QueryStream 은 DONE 토큰의 영향 행 수를 돌려주지 않고, execute 는 행을 버린다. 결과 집합을 만들 수 없는
+// 배치 (OUTPUT 절, 프로시저 호출이 없는 DML/DDL. INSERT ... SELECT 외의 SELECT 도 없어야 한다) 만
+// execute 로 보내 문장별 영향 행 수를 받는다
+fn returns_no_rows(query: &str) -> bool {
+    let statements = statement_words(query);
+    !statements.is_empty()
+        && statements.iter().all(|words| {
+            let first = words[0].as_str();
+            matches!(first, "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "TRUNCATE" | "CREATE" | "ALTER" | "DROP")
+                && !words.iter().any(|word| matches!(word.as_str(), "OUTPUT" | "EXEC" | "EXECUTE"))
+                && (first == "INSERT" || !words.iter().any(|word| word == "SELECT"))
+        })
+}
+
+fn json_to_sql(value: &serde_json::Value, param_type: ParamType) -> Box<dyn ToSql> {
+    match (param_type, value) {
+        (_, serde_json::Value::Null) => Box::new(None::<String>),
+        (ParamType::Integer, v) => Box::new(v.as_i64()),
+        (ParamType::Float, v) => Box::new(v.as_f64()),
+        (ParamType::Boolean, v) => Box::new(v.as_bool()),
+        (_, serde_json::Value::String(v)) => Box::new(v.clone()),
+        (_, v) => Box::new(v.to_string()),
+    }
+}
+
+// 대량 적재 대상 컬럼 (sys.columns 기준, 값을 넣을 수 없는 컬럼 제외)
+struct BulkColumn {
+    name: String,
+    type_name: String,
+    max_length: i16,
+    scale: u8,
+}
+
+async fn bulk_columns(
+    client: &mut Client<Compat<TcpStream>>,
+    table: &str,
+) -> Result<Vec<BulkColumn>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = client
+        .query(
+            "SELECT c.name, TYPE_NAME(c.system_type_id), c.max_length, c.scale \
+             FROM sys.columns c \
+             WHERE c.object_id = OBJECT_ID(@P1) AND c.is_identity = 0 AND c.is_computed = 0 \
+             AND TYPE_NAME(c.system_type_id) <> 'timestamp' \
+             ORDER BY c.column_id",
+            &[&table],
+        )
+        .await?
+        .into_first_result()
+        .await?;
+    if rows.is_empty() {
+        return Err(format!("Table not found: {}", table).into());
+    }
+
+    Ok(rows
+        .iter()
+        .map(|row| BulkColumn {
+            name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+            type_name: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+            max_length: row.get::<i16, _>(2).unwrap_or_default(),
+            scale: row.get::<u8, _>(3).unwrap_or_default(),
+        })
+        .collect())
+}
+
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+fn days_since_1900(date: NaiveDate) -> i64 {
+    (date - NaiveDate::from_ymd_opt(1900, 1, 1).unwrap_or_default()).num_days()
+}
+
+// 대량 적재는 서버 컬럼 타입과 정확히 같은 TDS 값을 요구한다
+fn bulk_value(column: &BulkColumn, value: &serde_json::Value) -> Result<ColumnData<'static>, String> {
+    let invalid = || format!("Invalid value for column {} ({}): {}", column.name, column.type_name, value);
+    let text = match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    };
+    let text = text.as_deref();
 
-        Ok(results)
+    let data = match column.type_name.as_str() {
+        "bit" => ColumnData::Bit(match text {
+            Some("true" | "1") => Some(true),
+            Some("false" | "0") => Some(false),
+            Some(_) => return Err(invalid()),
+            None => None,
+        }),
+        "tinyint" => ColumnData::U8(text.map(str::parse).transpose().map_err(|_| invalid())?),
+        "smallint" => ColumnData::I16(text.map(str::parse).transpose().map_err(|_| invalid())?),
+        "int" => ColumnData::I32(text.map(str::parse).transpose().map_err(|_| invalid())?),
+        "bigint" => ColumnData::I64(text.map(str::parse).transpose().map_err(|_| invalid())?),
+        "real" => ColumnData::F32(text.map(str::parse).transpose().map_err(|_| invalid())?),
+        "float" => ColumnData::F64(text.map(str::parse).transpose().map_err(|_| invalid())?),
+        "decimal" | "numeric" => ColumnData::Numeric(match text {
+            Some(text) => {
+                let mut decimal = text.parse::<Decimal>().map_err(|_| invalid())?;
+                decimal.rescale(u32::from(column.scale));
+                Some(Numeric::new_with_scale(decimal.mantissa(), column.scale))
+            }
+            None => None,
+        }),
+        "char" | "varchar" | "nchar" | "nvarchar" => ColumnData::String(text.map(|t| t.to_string().into())),
+        "uniqueidentifier" => ColumnData::Guid(
+            text.map(tiberius::Uuid::parse_str).transpose().map_err(|_| invalid())?,
+        ),
+        "binary" | "varbinary" => ColumnData::Binary(
+            text.map(Base64::decode_vec).transpose().map_err(|_| invalid())?.map(Into::into),
+        ),
+        "date" => match text {
+            Some(text) => NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| invalid())?.into_sql(),
+            None => ColumnData::Date(None),
+        },
+        "time" => ColumnData::Time(match text {
+            Some(text) => {
+                let time = NaiveTime::parse_from_str(text, "%H:%M:%S%.f").map_err(|_| invalid())?;
+                let nanos = u64::from(time.num_seconds_from_midnight()) * 1_000_000_000 + u64::from(time.nanosecond());
+                Some(Time::new(nanos / 10u64.pow(9 - u32::from(column.scale.min(7))), column.scale))
+            }
+            None => None,
+        }),
+        "datetime2" => match text {
+            Some(text) => parse_datetime(text).ok_or_else(invalid)?.into_sql(),
+            None => ColumnData::DateTime2(None),
+        },
+        "datetimeoffset" => match text {
+            Some(text) => DateTime::parse_from_rfc3339(text).map_err(|_| invalid())?.into_sql(),
+            None => ColumnData::DateTimeOffset(None),
+        },
+        "datetime" => ColumnData::DateTime(match text {
+            Some(text) => {
+                let v = parse_datetime(text).ok_or_else(invalid)?;
+                let nanos = u64::from(v.num_seconds_from_midnight()) * 1_000_000_000 + u64::from(v.nanosecond());
+                // 1/300 초 단위
+                let fragments = (nanos * 3 / 10_000_000) as u32;
+                Some(SqlDateTime::new(days_since_1900(v.date()) as i32, fragments))
+            }
+            None => None,
+        }),
+        "smalldatetime" => ColumnData::SmallDateTime(match text {
+            Some(text) => {
+                let v = parse_datetime(text).ok_or_else(invalid)?;
+                let days = u16::try_from(days_since_1900(v.date())).map_err(|_| invalid())?;
+                Some(SmallDateTime::new(days, (v.num_seconds_from_midnight() / 60) as u16))
+            }
+            None => None,
+        }),
+        other => return Err(format!("Column {} has a type not supported by bulk load: {}", column.name, other)),
+    };
+    if let ColumnData::String(Some(s)) = &data {
+        // nvarchar 의 max_length 는 바이트 수, MAX 는 -1
+        let max_chars = match column.type_name.as_str() {
+            "nchar" | "nvarchar" => column.max_length / 2,
+            _ => column.max_length,
+        };
+        if column.max_length > 0 && s.chars().count() > max_chars as usize {
+            return Err(format!("Value too long for column {} (max {})", column.name, max_chars));
+        }
+    }
+    Ok(data)
+}
+
+#[async_trait::async_trait]
+impl Connection for MSSQLConnection {
+    async fn execute_query(&self, query: &str) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = self.connect().await?;
+        let result = async {
+            let mut results = Vec::new();
+            let stream = client.query(query, &[]).await?;
+            let mut row_stream = stream.into_row_stream();
+
+            while let Some(row_result) = row_stream.next().await {
+                results.push(row_to_json(&row_result?));
+            }
+            Ok(results)
+        }
+        .await;
+        self.finish(client, result).await
+    }
+
+    async fn execute_result_sets(&self, query: &str) -> Result<Vec<ResultSet>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = self.connect().await?;
+        let result = async {
+            if returns_no_rows(query) {
+                let executed = client.execute(query, &[]).await?;
+                return Ok(executed
+                    .rows_affected()
+                    .iter()
+                    .map(|&rows| ResultSet { affected_rows: Some(rows), ..Default::default() })
+                    .collect());
+            }
+            Ok(collect_result_sets(client.query(query, &[]).await?).await?)
+        }
+        .await;
+        self.finish(client, result).await
+    }
+
+    async fn explain(&self, query: &str) -> Result<ExplainPlan, Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = self.connect().await?;
+
+        // SHOWPLAN_XML 은 배치의 유일한 문장이어야 한다
+        let result = async {
+            client.simple_query("SET SHOWPLAN_XML ON").await?.into_results().await?;
+            let rows = client.simple_query(query).await?.into_first_result().await?;
+            client.simple_query("SET SHOWPLAN_XML OFF").await?.into_results().await?;
+            Ok(rows)
+        }
+        .await;
+        let rows = self.finish(client, result).await?;
+
+        let xml = rows
+            .iter()
+            .filter_map(|row| row.get::<&str, _>(0))
+            .collect::<Vec<_>>()
+            .concat();
+        let plan = normalize_mssql(&xml)?;
+
+        Ok(ExplainPlan { raw: serde_json::Value::String(xml), plan })
+    }
+
+    async fn call_procedure(&self, call: &ProcedureCall) -> Result<ProcedureResult, Box<dyn std::error::Error + Send + Sync>> {
+        call.validate()?;
+        // 프로시저 안의 COMMIT 은 열어 둔 트랜잭션을 끝내 버린다
+        if self.read_only {
+            return Err("Procedures cannot be called on a read-only MSSQL connection".into());
+        }
+        if call.params.iter().any(|p| p.param_type == ParamType::Cursor) || call.returns == Some(ParamType::Cursor) {
+            return Err("MSSQL does not support cursor parameters".into());
+        }
+
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+        let mut batch = Vec::new();
+        let mut args = Vec::new();
+        for (i, param) in call.params.iter().enumerate() {
+            if param.is_input() {
+                values.push(json_to_sql(&param.value, param.param_type));
+            }
+            let placeholder = format!("@P{}", values.len());
+            if param.is_output() {
+                let variable = format!("@_p{}", i + 1);
+                batch.push(format!("DECLARE {} {};", variable, param.sql_type_for(DatabaseType::MSSQL)));
+                if param.is_input() {
+                    batch.push(format!("SET {} = {};", variable, placeholder));
+                }
+                args.push(format!("{} OUTPUT", variable));
+            } else {
+                args.push(placeholder);
+            }
+        }
+
+        match call.returns {
+            Some(_) => {
+                batch.push(format!("SELECT {}({}) AS [return_value];", call.name, args.join(", ")));
+            }
+            None => {
+                let mut outputs = vec!["@_ret AS [return_value]".to_string()];
+                for (i, param) in call.params.iter().enumerate().filter(|(_, p)| p.is_output()) {
+                    outputs.push(format!("@_p{} AS [{}]", i + 1, param.name.replace(']', "]]")));
+                }
+                batch.push("DECLARE @_ret INT;".to_string());
+                batch.push(format!("EXEC @_ret = {} {};", call.name, args.join(", ")));
+                batch.push(format!("SELECT {};", outputs.join(", ")));
+            }
+        }
+
+        let params: Vec<&dyn ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        let mut client = self.connect().await?;
+        let stream = client.query(batch.join("\n"), &params).await?;
+        let mut result_sets = collect_result_sets(stream).await?;
+
+        // 마지막 결과 집합이 반환값과 OUT 파라미터
+        let mut result = ProcedureResult::default();
+        if let Some(serde_json::Value::Object(mut outputs)) = result_sets
+            .pop()
+            .and_then(|result_set| result_set.rows.into_iter().next())
+        {
+            result.return_value = outputs.remove("return_value");
+            result.out_params = outputs;
+        }
+        result.result_sets = result_sets;
+
+        Ok(result)
+    }
+
+    async fn bulk_insert(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<serde_json::Value>],
+    ) -> Result<BatchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        if self.read_only {
+            return Err("Connection is read-only".into());
+        }
+        let mut client = self.connect().await?;
+        let targets = bulk_columns(&mut client, table).await?;
+        if let Some(missing) = columns
+            .iter()
+            .find(|name| !targets.iter().any(|t| t.name.eq_ignore_ascii_case(name)))
+        {
+            return Err(format!("Column not found in {}: {}", table, missing).into());
+        }
+        // 매핑되지 않은 컬럼은 NULL 로 채운다
+        let positions = targets
+            .iter()
+            .map(|target| columns.iter().position(|name| target.name.eq_ignore_ascii_case(name)))
+            .collect::<Vec<_>>();
+
+        // 변환할 수 없는 행은 적재 전에 거부한다
+        let mut outcome = BatchOutcome::default();
+        let mut token_rows = Vec::with_capacity(rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            let mut token_row = TokenRow::with_capacity(targets.len());
+            let converted = targets.iter().zip(&positions).try_for_each(|(target, position)| {
+                let value = position.and_then(|p| row.get(p)).unwrap_or(&serde_json::Value::Null);
+                token_row.push(bulk_value(target, value)?);
+                Ok::<_, String>(())
+            });
+            match converted {
+                Ok(()) => token_rows.push(token_row),
+                Err(error) => outcome.rejected.push((i, error)),
+            }
+        }
+        if token_rows.is_empty() {
+            return Ok(outcome);
+        }
+
+        let mut request = client.bulk_insert(table).await?;
+        for token_row in token_rows {
+            request.send(token_row).await?;
+        }
+        outcome.loaded = request.finalize().await?.total();
+
+        Ok(outcome)
     }
 
     async fn close(&self) {
         // Tiberius client doesn't have an explicit close method
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_returns_no_rows() {
+        assert!(returns_no_rows("UPDATE t SET a = 1; DELETE FROM t WHERE a = 2"));
+        assert!(returns_no_rows("INSERT INTO t SELECT * FROM s"));
+        assert!(!returns_no_rows("INSERT INTO t OUTPUT inserted.id VALUES (1)"));
+        assert!(!returns_no_rows("UPDATE t SET a = 1 SELECT * FROM t"));
+        assert!(!returns_no_rows("DELETE FROM t; SELECT @@ROWCOUNT"));
+        assert!(!returns_no_rows("EXEC refresh_totals"));
+        assert!(!returns_no_rows("SELECT 1"));
+    }
+}
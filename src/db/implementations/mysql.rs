@@ -1,24 +1,174 @@
-use sqlx::{mysql::MySqlPoolOptions, MySqlPool, Row, Column};
-use crate::db::connection::{Connection, ConnectionConfig};
+use sqlx::{mysql::{MySqlPoolOptions, MySqlQueryResult, MySqlRow}, Either, Executor, MySqlPool, Row, Column, TypeInfo};
+use crate::db::connection::{Connection, ConnectionConfig, RowBatcher, RECEIVER_CLOSED};
+use crate::db::explain::{normalize_mysql, ExplainPlan};
+use crate::db::import::{param_type_of, BatchOutcome};
+use crate::db::procedure::{ParamType, ProcedureCall, ProcedureResult};
+use crate::db::types::{ColumnMeta, ResultSet};
+use crate::db::values;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use rust_decimal::Decimal;
 use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+// 문장 하나에 바인드할 수 있는 최대 자리표시자 수
+const MAX_PLACEHOLDERS: usize = 65_535;
+
+// 이후 트랜잭션에서 테이블 변경을 막는다. DDL 은 막지 못하므로 게이트웨이의 문장 분류에 맡긴다
+const READ_ONLY_SESSION: &str = "SET SESSION TRANSACTION READ ONLY";
 
 #[derive(Debug,Clone)]
 pub struct MySQLConnection {
     pool: MySqlPool,
+    read_only: bool,
 }
 
 impl MySQLConnection {
     pub async fn new(config: ConnectionConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let pool = MySqlPoolOptions::new()
+        let mut options = MySqlPoolOptions::new()
             .max_connections(config.pool_options.max_connections)
             .min_connections(config.pool_options.min_connections)
             .acquire_timeout(config.get_timeout_duration())
             .idle_timeout(config.get_idle_timeout())
-            .max_lifetime(config.get_max_lifetime())
-            .connect(&config.connection_string)
-            .await?;
+            .max_lifetime(config.get_max_lifetime());
+        // 새 연결과 풀에서 꺼내는 연결 모두에 적용해 쿼리로 세션 설정을 바꿔 둔 경우도 되돌린다
+        if config.read_only {
+            options = options
+                .after_connect(|conn, _| Box::pin(async move { conn.execute(READ_ONLY_SESSION).await.map(|_| ()) }))
+                .before_acquire(|conn, _| Box::pin(async move { conn.execute(READ_ONLY_SESSION).await.map(|_| true) }));
+        }
+        let pool = options.connect(&config.connection_string).await?;
+
+        Ok(Self { pool, read_only: config.read_only })
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub async fn stream_rows(
+        &self,
+        query: &str,
+        batch_size: usize,
+        sender: mpsc::Sender<ResultSet>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut rows = self.pool.fetch(query);
+        let mut batcher = RowBatcher::new(batch_size);
+        while let Some(row) = rows.try_next().await? {
+            if !batcher.has_columns() {
+                batcher.set_columns(columns_of(&row));
+            }
+            if let Some(batch) = batcher.push(row_to_json(&row)) {
+                sender.send(batch).await.map_err(|_| RECEIVER_CLOSED)?;
+            }
+        }
+        if let Some(batch) = batcher.finish() {
+            sender.send(batch).await.map_err(|_| RECEIVER_CLOSED)?;
+        }
+        Ok(())
+    }
+}
+
+fn row_to_json(row: &MySqlRow) -> serde_json::Value {
+    let mut row_map = HashMap::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = match row.try_get::<i32, _>(i) {
+            Ok(v) => serde_json::Value::Number(v.into()),
+            Err(_) => match row.try_get::<i64, _>(i) {
+                Ok(v) => serde_json::Value::Number(v.into()),
+                Err(_) => match row.try_get::<String, _>(i) {
+                    Ok(v) => serde_json::Value::String(v),
+                    Err(_) => match row.try_get::<bool, _>(i) {
+                        Ok(v) => serde_json::Value::Bool(v),
+                        Err(_) => match row.try_get::<f64, _>(i) {
+                            Ok(v) => serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into())),
+                            Err(_) => typed_value(row, i),
+                        },
+                    },
+                },
+            },
+        };
+        row_map.insert(column.name().to_string(), value);
+    }
+    serde_json::Value::Object(serde_json::Map::from_iter(row_map))
+}
+
+// 기본 타입으로 읽히지 않는 컬럼 (작은 정수, 소수, 날짜/시간, 바이너리)
+fn typed_value(row: &MySqlRow, i: usize) -> serde_json::Value {
+    if let Ok(v) = row.try_get::<i16, _>(i) {
+        serde_json::Value::Number(v.into())
+    } else if let Ok(v) = row.try_get::<i8, _>(i) {
+        serde_json::Value::Number(v.into())
+    } else if let Ok(v) = row.try_get::<u64, _>(i) {
+        serde_json::Value::Number(v.into())
+    } else if let Ok(v) = row.try_get::<f32, _>(i) {
+        values::float_value(v.into())
+    } else if let Ok(v) = row.try_get::<Decimal, _>(i) {
+        values::decimal_value(v)
+    } else if let Ok(v) = row.try_get::<DateTime<Utc>, _>(i) {
+        values::datetime_tz_value(v)
+    } else if let Ok(v) = row.try_get::<NaiveDateTime, _>(i) {
+        values::datetime_value(v)
+    } else if let Ok(v) = row.try_get::<NaiveDate, _>(i) {
+        values::date_value(v)
+    } else if let Ok(v) = row.try_get::<NaiveTime, _>(i) {
+        values::time_value(v)
+    } else if let Ok(v) = row.try_get::<Vec<u8>, _>(i) {
+        values::binary_value(&v)
+    } else {
+        serde_json::Value::Null
+    }
+}
+
+fn columns_of(row: &MySqlRow) -> Vec<ColumnMeta> {
+    row.columns()
+        .iter()
+        .map(|column| ColumnMeta {
+            name: column.name().to_string(),
+            data_type: column.type_info().name().to_string(),
+        })
+        .collect()
+}
+
+// 결과 집합마다 OK 패킷(Left) 이 뒤따르므로 그 단위로 나눈다
+async fn collect_result_sets(
+    mut stream: BoxStream<'_, Result<Either<MySqlQueryResult, MySqlRow>, sqlx::Error>>,
+) -> Result<Vec<ResultSet>, sqlx::Error> {
+    let mut result_sets = Vec::new();
+    let mut current = ResultSet::default();
+    while let Some(item) = stream.try_next().await? {
+        match item {
+            Either::Left(done) => {
+                current.affected_rows = Some(done.rows_affected());
+                result_sets.push(std::mem::take(&mut current));
+            }
+            Either::Right(row) => {
+                if current.columns.is_empty() {
+                    current.columns = columns_of(&row);
+                }
+                current.rows.push(row_to_json(&row));
+            }
+        }
+    }
+    if !current.rows.is_empty() {
+        result_sets.push(current);
+    }
+    Ok(result_sets)
+}
 
-        Ok(Self { pool })
+fn bind_json<'q>(
+    query: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
+    value: &serde_json::Value,
+    param_type: ParamType,
+) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+    match (param_type, value) {
+        (_, serde_json::Value::Null) => query.bind(None::<String>),
+        (ParamType::Integer, v) => query.bind(v.as_i64()),
+        (ParamType::Float, v) => query.bind(v.as_f64()),
+        (ParamType::Boolean, v) => query.bind(v.as_bool()),
+        (_, serde_json::Value::String(v)) => query.bind(v.clone()),
+        (_, v) => query.bind(v.to_string()),
     }
 }
 
@@ -29,29 +179,122 @@ impl Connection for MySQLConnection {
             .fetch_all(&self.pool)
             .await?;
 
-        let mut results = Vec::new();
-        for row in rows {
-            let mut row_map = HashMap::new();
-            for (i, column) in row.columns().iter().enumerate() {
-                let value = match row.try_get::<i32, _>(i) {
-                    Ok(v) => serde_json::Value::Number(v.into()),
-                    Err(_) => match row.try_get::<String, _>(i) {
-                        Ok(v) => serde_json::Value::String(v),
-                        Err(_) => match row.try_get::<bool, _>(i) {
-                            Ok(v) => serde_json::Value::Bool(v),
-                            Err(_) => match row.try_get::<f64, _>(i) {
-                                Ok(v) => serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into())),
-                                Err(_) => serde_json::Value::Null,
-                            },
-                        },
-                    },
-                };
-                row_map.insert(column.name().to_string(), value);
+        Ok(rows.iter().map(row_to_json).collect())
+    }
+
+    async fn execute_result_sets(&self, query: &str) -> Result<Vec<ResultSet>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(collect_result_sets(self.pool.fetch_many(query)).await?)
+    }
+
+    async fn explain(&self, query: &str) -> Result<ExplainPlan, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(&format!("EXPLAIN FORMAT=JSON {}", query))
+            .fetch_one(&self.pool)
+            .await?;
+        let raw: serde_json::Value = match row.try_get::<String, _>(0) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(_) => row.try_get(0)?,
+        };
+        let plan = normalize_mysql(&raw);
+
+        Ok(ExplainPlan { raw, plan })
+    }
+
+    async fn call_procedure(&self, call: &ProcedureCall) -> Result<ProcedureResult, Box<dyn std::error::Error + Send + Sync>> {
+        call.validate()?;
+        if call.params.iter().any(|p| p.param_type == ParamType::Cursor) || call.returns == Some(ParamType::Cursor) {
+            return Err("MySQL does not support cursor parameters".into());
+        }
+
+        // OUT/INOUT 파라미터는 같은 세션의 사용자 변수로 주고받는다
+        let mut conn = self.pool.acquire().await?;
+        let mut result = ProcedureResult::default();
+
+        if call.returns.is_some() {
+            let placeholders = vec!["?"; call.params.len()].join(", ");
+            let sql = format!("SELECT {}({}) AS return_value", call.name, placeholders);
+            let mut query = sqlx::query(&sql);
+            for param in &call.params {
+                query = bind_json(query, &param.value, param.param_type);
+            }
+            let row = query.fetch_one(&mut *conn).await?;
+            result.return_value = row_to_json(&row).get("return_value").cloned();
+            return Ok(result);
+        }
+
+        let mut args = Vec::new();
+        for (i, param) in call.params.iter().enumerate() {
+            if param.is_output() {
+                let variable = format!("@_p{}", i + 1);
+                if param.is_input() {
+                    bind_json(sqlx::query(&format!("SET {} = ?", variable)), &param.value, param.param_type)
+                        .execute(&mut *conn)
+                        .await?;
+                }
+                args.push(variable);
+            } else {
+                args.push("?".to_string());
+            }
+        }
+
+        let sql = format!("CALL {}({})", call.name, args.join(", "));
+        let mut query = sqlx::query(&sql);
+        for param in call.params.iter().filter(|p| !p.is_output()) {
+            query = bind_json(query, &param.value, param.param_type);
+        }
+
+        // CALL 의 마지막 OK 패킷은 컬럼이 없는 상태 결과라서 제외한다
+        result.result_sets = collect_result_sets(conn.fetch_many(query))
+            .await?
+            .into_iter()
+            .filter(|result_set| !result_set.columns.is_empty())
+            .collect();
+
+        let outputs: Vec<String> = call
+            .params
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_output())
+            .map(|(i, p)| format!("@_p{} AS `{}`", i + 1, p.name.replace('`', "``")))
+            .collect();
+        if !outputs.is_empty() {
+            let row = sqlx::query(&format!("SELECT {}", outputs.join(", ")))
+                .fetch_one(&mut *conn)
+                .await?;
+            if let serde_json::Value::Object(values) = row_to_json(&row) {
+                result.out_params = values;
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn bulk_insert(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<serde_json::Value>],
+    ) -> Result<BatchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let placeholders = format!("({})", vec!["?"; columns.len()].join(", "));
+        let rows_per_statement = (MAX_PLACEHOLDERS / columns.len().max(1)).max(1);
+
+        let mut tx = self.pool.begin().await?;
+        let mut loaded = 0;
+        for chunk in rows.chunks(rows_per_statement) {
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                table,
+                columns.join(", "),
+                vec![placeholders.as_str(); chunk.len()].join(", ")
+            );
+            let mut query = sqlx::query(&sql);
+            for value in chunk.iter().flatten() {
+                query = bind_json(query, value, param_type_of(value));
             }
-            results.push(serde_json::Value::Object(serde_json::Map::from_iter(row_map)));
+            loaded += query.execute(&mut *tx).await?.rows_affected();
         }
+        tx.commit().await?;
 
-        Ok(results)
+        Ok(BatchOutcome { loaded, rejected: Vec::new() })
     }
 
     async fn close(&self) {
@@ -1,10 +1,23 @@
-use oracle::Connection;
-use crate::db::connection::{Connection as DbConnection, ConnectionConfig};
+use oracle::sql_type::{OracleType, RefCursor, ToSql};
+use oracle::{Connection, ResultSet as OracleResultSet, Row, SqlValue};
+use crate::db::connection::{Connection as DbConnection, ConnectionConfig, RowBatcher, RECEIVER_CLOSED};
+use crate::db::explain::{normalize_oracle, ExplainPlan, OraclePlanRow};
+use crate::db::import::BatchOutcome;
+use crate::db::procedure::{ParamType, ProcedureCall, ProcedureParam, ProcedureResult};
+use crate::db::types::{ColumnMeta, ResultSet};
+use crate::db::values;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::mpsc;
 
+// oracle::Connection 하나를 여러 요청이 같이 쓴다. 한 세션에서 트랜잭션이 섞이지 않도록
+// 호출마다 잠그고, 잠근 동안 다른 요청은 기다린다. oracle 크레이트는 동기 API 라
+// 잠금과 OCI 호출은 블로킹 스레드에서 한다 (blocking)
 #[derive(Debug)]
 pub struct OracleConnection {
-    conn: Connection,
+    conn: Arc<Mutex<Connection>>,
+    read_only: bool,
 }
 
 impl OracleConnection {
@@ -15,40 +28,482 @@ impl OracleConnection {
             return Err("Oracle connection requires username and password".into());
         };
         
-        Ok(Self { conn })
+        Ok(Self { conn: Arc::new(Mutex::new(conn)), read_only: config.read_only })
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    // 블로킹 스레드에서 호출한다 (DatabaseConnection::stream_rows). 다 읽을 때까지 연결을 잠그므로
+    // 같은 연결에 쓰는 복사는 할 수 없다
+    pub fn stream_rows_blocking(
+        &self,
+        query: &str,
+        batch_size: usize,
+        sender: mpsc::Sender<ResultSet>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.transaction(|conn| {
+            let mut stmt = conn.statement(query).build()?;
+            let rows = stmt.query(&[])?;
+            let mut batcher = RowBatcher::new(batch_size);
+            batcher.set_columns(
+                rows.column_info()
+                    .iter()
+                    .map(|column| ColumnMeta {
+                        name: column.name().to_string(),
+                        data_type: column.oracle_type().to_string(),
+                    })
+                    .collect(),
+            );
+            for row in rows {
+                if let Some(batch) = batcher.push(row_to_json(&row?)) {
+                    sender.blocking_send(batch).map_err(|_| RECEIVER_CLOSED)?;
+                }
+            }
+            if let Some(batch) = batcher.finish() {
+                sender.blocking_send(batch).map_err(|_| RECEIVER_CLOSED)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn lock(conn: &Mutex<Connection>) -> Result<MutexGuard<'_, Connection>, Box<dyn std::error::Error + Send + Sync>> {
+        conn.lock().map_err(|_| "Oracle connection lock is poisoned".into())
+    }
+
+    // 블로킹 스레드에서 연결을 잠그고 f 를 실행한다. 트랜잭션 처리는 f 가 정한다
+    async fn blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection, bool) -> Result<T, Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        let (conn, read_only) = (self.conn.clone(), self.read_only);
+        tokio::task::spawn_blocking(move || f(&*Self::lock(&conn)?, read_only)).await?
+    }
+
+    // 블로킹 스레드에서 transaction 과 같이 실행한다
+    async fn blocking_transaction<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        self.blocking(move |conn, read_only| Self::transaction_on(conn, read_only, f)).await
+    }
+
+    // 앞뒤로 롤백해서 f 가 남긴 작업을 커밋하지 않는다
+    fn rollback_scope<T>(
+        conn: &Connection,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        conn.rollback()?;
+        let result = f(conn);
+        conn.rollback()?;
+        result
+    }
+
+    // 읽기 전용 연결은 호출마다 SET TRANSACTION READ ONLY 로 시작해 롤백으로 끝낸다.
+    // DDL 은 암시적으로 커밋되어 막지 못하므로 게이트웨이의 문장 분류에 맡긴다
+    fn read_only_scope<T>(
+        conn: &Connection,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        Self::rollback_scope(conn, |conn| {
+            conn.execute("SET TRANSACTION READ ONLY", &[])?;
+            f(conn)
+        })
+    }
+
+    // oracle 크레이트는 자동 커밋하지 않는다. 성공하면 커밋하고 실패하면 롤백해서
+    // 공유 연결에 미결 작업과 행 잠금을 남기지 않는다
+    fn commit_or_rollback_on<T>(
+        conn: &Connection,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        match f(conn) {
+            Ok(value) => {
+                conn.commit()?;
+                Ok(value)
+            }
+            Err(e) => {
+                // 원래 오류를 돌려준다
+                let _ = conn.rollback();
+                Err(e)
+            }
+        }
+    }
+
+    // 연결을 잠근 채로 f 를 실행한다. 블로킹 스레드에서만 부른다
+    fn transaction<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        Self::transaction_on(&*Self::lock(&self.conn)?, self.read_only, f)
+    }
+
+    fn transaction_on<T>(
+        conn: &Connection,
+        read_only: bool,
+        f: impl FnOnce(&Connection) -> Result<T, Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        if read_only {
+            Self::read_only_scope(conn, f)
+        } else {
+            Self::commit_or_rollback_on(conn, f)
+        }
+    }
+
+    // EXPLAIN PLAN 으로 plan_table 에 넣은 행을 읽고 지운다
+    fn explain_plan(conn: &Connection, query: &str) -> Result<ExplainPlan, Box<dyn std::error::Error + Send + Sync>> {
+        // STATEMENT_ID 는 최대 30자
+        let statement_id = uuid::Uuid::new_v4().simple().to_string()[..30].to_string();
+        conn.execute(
+            &format!("EXPLAIN PLAN SET STATEMENT_ID = '{}' FOR {}", statement_id, query),
+            &[],
+        )?;
+
+        let mut raw = Vec::new();
+        for row in conn.query(
+            "SELECT plan_table_output FROM TABLE(DBMS_XPLAN.DISPLAY('PLAN_TABLE', :1, 'TYPICAL'))",
+            &[&statement_id],
+        )? {
+            raw.push(row?.get::<usize, Option<String>>(0)?.unwrap_or_default());
+        }
+
+        let mut plan_rows = Vec::new();
+        for row in conn.query(
+            "SELECT id, parent_id, operation, options, object_name, cost, cardinality \
+             FROM plan_table WHERE statement_id = :1 ORDER BY id",
+            &[&statement_id],
+        )? {
+            let row = row?;
+            plan_rows.push(OraclePlanRow {
+                id: row.get(0)?,
+                parent_id: row.get(1)?,
+                operation: row.get(2)?,
+                options: row.get(3)?,
+                object_name: row.get(4)?,
+                cost: row.get(5)?,
+                cardinality: row.get(6)?,
+            });
+        }
+
+        conn.execute("DELETE FROM plan_table WHERE statement_id = :1", &[&statement_id])?;
+
+        Ok(ExplainPlan {
+            raw: serde_json::Value::String(raw.join("\n")),
+            plan: normalize_oracle(&plan_rows),
+        })
+    }
+
+    // 배치 하나를 넣고 커밋한다. 실패한 행만 거부하고 나머지는 적재된다
+    fn insert_batch(
+        conn: &Connection,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<serde_json::Value>],
+    ) -> Result<BatchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let binds = (1..=columns.len()).map(|i| format!(":{}", i)).collect::<Vec<_>>();
+        let sql = format!("INSERT INTO {} ({}) VALUES ({})", table, columns.join(", "), binds.join(", "));
+
+        // 문자열로 넘긴 날짜/숫자를 ISO 형식으로 해석하도록 세션 형식을 맞춘다
+        conn.execute(
+            "ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD HH24:MI:SS' \
+             NLS_TIMESTAMP_FORMAT = 'YYYY-MM-DD HH24:MI:SS.FF' \
+             NLS_TIMESTAMP_TZ_FORMAT = 'YYYY-MM-DD HH24:MI:SS.FF TZH:TZM' \
+             NLS_NUMERIC_CHARACTERS = '.,'",
+            &[],
+        )?;
+        let mut batch = conn.batch(&sql, rows.len().max(1)).with_batch_errors().build()?;
+        for i in 1..=columns.len() {
+            batch.set_type(i, &OracleType::Varchar2(4000))?;
+        }
+        for row in rows {
+            let values = row.iter().map(bulk_text).collect::<Vec<_>>();
+            let params = values.iter().map(|v| v as &dyn ToSql).collect::<Vec<_>>();
+            batch.append_row(&params)?;
+        }
+
+        // with_batch_errors: 실패한 행만 오류로 돌려주고 나머지는 적재된다
+        let mut outcome = BatchOutcome::default();
+        if let Err(e) = batch.execute() {
+            match e.batch_errors() {
+                Some(errors) => {
+                    outcome.rejected = errors
+                        .iter()
+                        .map(|error| (error.offset() as usize, error.message().to_string()))
+                        .collect();
+                }
+                None => {
+                    conn.rollback()?;
+                    return Err(e.into());
+                }
+            }
+        }
+        conn.commit()?;
+        outcome.loaded = (rows.len() - outcome.rejected.len()) as u64;
+
+        Ok(outcome)
     }
 }
 
+fn row_to_json(row: &Row) -> serde_json::Value {
+    let mut row_map = HashMap::new();
+    for (i, column) in row.column_info().iter().enumerate() {
+        let value = if let Some(val) = typed_value(row, i, column.oracle_type()) {
+            val
+        } else if let Ok(val) = row.get::<usize, i64>(i) {
+            serde_json::Value::Number(val.into())
+        } else if let Ok(val) = row.get::<usize, String>(i) {
+            serde_json::Value::String(val)
+        } else if let Ok(val) = row.get::<usize, bool>(i) {
+            serde_json::Value::Bool(val)
+        } else if let Ok(val) = row.get::<usize, f64>(i) {
+            serde_json::Value::Number(serde_json::Number::from_f64(val).unwrap_or(0.into()))
+        } else {
+            serde_json::Value::Null
+        };
+        row_map.insert(column.name().to_string(), value);
+    }
+    serde_json::Value::Object(serde_json::Map::from_iter(row_map))
+}
+
+// 문자열 변환에 맡기면 정보가 사라지는 타입 (바이너리, 날짜/시간, 소수)
+fn typed_value(row: &Row, i: usize, oracle_type: &OracleType) -> Option<serde_json::Value> {
+    let value = match oracle_type {
+        OracleType::Raw(_) | OracleType::LongRaw | OracleType::BLOB => {
+            row.get::<usize, Option<Vec<u8>>>(i).ok()?.map(|v| values::binary_value(&v))
+        }
+        OracleType::Date | OracleType::Timestamp(_) => {
+            row.get::<usize, Option<NaiveDateTime>>(i).ok()?.map(values::datetime_value)
+        }
+        OracleType::TimestampTZ(_) | OracleType::TimestampLTZ(_) => {
+            row.get::<usize, Option<DateTime<Utc>>>(i).ok()?.map(values::datetime_tz_value)
+        }
+        OracleType::BinaryFloat | OracleType::BinaryDouble => {
+            row.get::<usize, Option<f64>>(i).ok()?.map(values::float_value)
+        }
+        OracleType::Number(_, scale) if *scale > 0 => {
+            row.get::<usize, Option<String>>(i).ok()?.map(serde_json::Value::String)
+        }
+        _ => return None,
+    };
+    Some(value.unwrap_or(serde_json::Value::Null))
+}
+
+// 배열 바인드는 모든 값을 VARCHAR2 로 넘기고 변환은 서버에 맡긴다
+fn bulk_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(b) => Some(if *b { "1" } else { "0" }.to_string()),
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn result_set(rows: OracleResultSet<Row>) -> Result<ResultSet, Box<dyn std::error::Error + Send + Sync>> {
+    let mut result_set = ResultSet {
+        columns: rows
+            .column_info()
+            .iter()
+            .map(|column| ColumnMeta {
+                name: column.name().to_string(),
+                data_type: column.oracle_type().to_string(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+    for row in rows {
+        result_set.rows.push(row_to_json(&row?));
+    }
+    Ok(result_set)
+}
+
+fn oracle_type(param_type: ParamType) -> OracleType {
+    match param_type {
+        ParamType::Integer => OracleType::Int64,
+        ParamType::Float => OracleType::BinaryDouble,
+        ParamType::String => OracleType::Varchar2(32767),
+        ParamType::Boolean => OracleType::Boolean,
+        ParamType::Cursor => OracleType::RefCursor,
+    }
+}
+
+// IN/OUT/INOUT 를 하나의 바인드 타입으로 표현한다 (OUT 은 값이 NULL)
+struct OracleArg {
+    value: serde_json::Value,
+    param_type: ParamType,
+}
+
+impl OracleArg {
+    fn new(param: &ProcedureParam) -> Self {
+        Self {
+            value: if param.is_input() { param.value.clone() } else { serde_json::Value::Null },
+            param_type: param.param_type,
+        }
+    }
+}
+
+impl ToSql for OracleArg {
+    fn oratype(&self, _conn: &Connection) -> oracle::Result<OracleType> {
+        Ok(oracle_type(self.param_type))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> oracle::Result<()> {
+        match (self.param_type, &self.value) {
+            (_, serde_json::Value::Null) => val.set_null(),
+            (ParamType::Integer, v) => match v.as_i64() {
+                Some(v) => v.to_sql(val),
+                None => val.set_null(),
+            },
+            (ParamType::Float, v) => match v.as_f64() {
+                Some(v) => v.to_sql(val),
+                None => val.set_null(),
+            },
+            (ParamType::Boolean, v) => match v.as_bool() {
+                Some(v) => v.to_sql(val),
+                None => val.set_null(),
+            },
+            (_, serde_json::Value::String(v)) => v.to_sql(val),
+            (_, v) => v.to_string().to_sql(val),
+        }
+    }
+}
+
+fn read_output(
+    stmt: &oracle::Statement,
+    index: usize,
+    param_type: ParamType,
+) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match param_type {
+        ParamType::Integer => stmt.bind_value::<usize, Option<i64>>(index)?.map(Into::into).unwrap_or_default(),
+        ParamType::Float => stmt.bind_value::<usize, Option<f64>>(index)?.map(Into::into).unwrap_or_default(),
+        ParamType::Boolean => stmt.bind_value::<usize, Option<bool>>(index)?.map(Into::into).unwrap_or_default(),
+        ParamType::String => stmt.bind_value::<usize, Option<String>>(index)?.map(Into::into).unwrap_or_default(),
+        ParamType::Cursor => match stmt.bind_value::<usize, Option<RefCursor>>(index)? {
+            Some(mut cursor) => serde_json::Value::Array(result_set(cursor.query()?)?.rows),
+            None => serde_json::Value::Null,
+        },
+    })
+}
+
 #[async_trait::async_trait]
 impl DbConnection for OracleConnection {
     async fn execute_query(&self, query: &str) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
-        let mut stmt = self.conn.statement(query).build()?;
-        let rows = stmt.query(&[])?;
-        let mut results = Vec::new();
-
-        for row_result in rows {
-            let row = row_result?;
-            let mut row_map = HashMap::new();
-            
-            for i in 0..row.column_info().len() {
-                let value = if let Ok(val) = row.get::<usize, i32>(i + 1) {
-                    serde_json::Value::Number(val.into())
-                } else if let Ok(val) = row.get::<usize, String>(i + 1) {
-                    serde_json::Value::String(val)
-                } else if let Ok(val) = row.get::<usize, bool>(i + 1) {
-                    serde_json::Value::Bool(val)
-                } else if let Ok(val) = row.get::<usize, f64>(i + 1) {
-                    serde_json::Value::Number(serde_json::Number::from_f64(val).unwrap_or(0.into()))
+        let query = query.to_string();
+        self.blocking_transaction(move |conn| {
+            let mut stmt = conn.statement(&query).build()?;
+            let rows = stmt.query(&[])?;
+            let mut results = Vec::new();
+
+            for row_result in rows {
+                results.push(row_to_json(&row_result?));
+            }
+
+            Ok(results)
+        })
+        .await
+    }
+
+    async fn execute_result_sets(&self, query: &str) -> Result<Vec<ResultSet>, Box<dyn std::error::Error + Send + Sync>> {
+        let query = query.to_string();
+        self.blocking_transaction(move |conn| {
+            let mut stmt = conn.statement(&query).build()?;
+            if stmt.is_query() {
+                return Ok(vec![result_set(stmt.query(&[])?)?]);
+            }
+
+            stmt.execute(&[])?;
+            let mut result_sets = vec![ResultSet {
+                affected_rows: Some(stmt.row_count()?),
+                ..Default::default()
+            }];
+            // PL/SQL 블록이 DBMS_SQL.RETURN_RESULT 로 돌려준 결과 집합
+            while let Some(mut cursor) = stmt.implicit_result()? {
+                result_sets.push(result_set(cursor.query()?)?);
+            }
+            Ok(result_sets)
+        })
+        .await
+    }
+
+    async fn explain(&self, query: &str) -> Result<ExplainPlan, Box<dyn std::error::Error + Send + Sync>> {
+        // EXPLAIN PLAN 은 plan_table 에 행을 넣으므로 읽기 전용 트랜잭션으로는 감쌀 수 없다.
+        // 읽기 전용 연결은 롤백으로 끝내 커밋하지 않고, 아니면 넣은 행을 지우고 커밋한다
+        let query = query.to_string();
+        self.blocking(move |conn, read_only| {
+            if read_only {
+                Self::rollback_scope(conn, |conn| Self::explain_plan(conn, &query))
+            } else {
+                Self::commit_or_rollback_on(conn, |conn| Self::explain_plan(conn, &query))
+            }
+        })
+        .await
+    }
+
+    async fn call_procedure(&self, call: &ProcedureCall) -> Result<ProcedureResult, Box<dyn std::error::Error + Send + Sync>> {
+        let call = call.clone();
+        self.blocking_transaction(move |conn| {
+            call.validate()?;
+
+            // 함수 호출이면 :1 이 반환값 자리
+            let offset = if call.returns.is_some() { 1 } else { 0 };
+            let placeholders: Vec<String> = (1..=call.params.len())
+                .map(|i| format!(":{}", i + offset))
+                .collect();
+            let sql = match call.returns {
+                Some(_) => format!("BEGIN :1 := {}({}); END;", call.name, placeholders.join(", ")),
+                None => format!("BEGIN {}({}); END;", call.name, placeholders.join(", ")),
+            };
+
+            let return_arg = call.returns.map(|param_type| OracleArg {
+                value: serde_json::Value::Null,
+                param_type,
+            });
+            let args: Vec<OracleArg> = call.params.iter().map(OracleArg::new).collect();
+            let mut binds: Vec<&dyn ToSql> = Vec::new();
+            if let Some(arg) = &return_arg {
+                binds.push(arg);
+            }
+            binds.extend(args.iter().map(|arg| arg as &dyn ToSql));
+
+            let mut stmt = conn.statement(&sql).build()?;
+            stmt.execute(&binds)?;
+
+            let mut result = ProcedureResult::default();
+            if let Some(param_type) = call.returns {
+                if param_type == ParamType::Cursor {
+                    if let Some(mut cursor) = stmt.bind_value::<usize, Option<RefCursor>>(1)? {
+                        result.result_sets.push(result_set(cursor.query()?)?);
+                    }
                 } else {
-                    serde_json::Value::Null
-                };
-                let column_name = format!("column_{}", i + 1);
-                row_map.insert(column_name, value);
+                    result.return_value = Some(read_output(&stmt, 1, param_type)?);
+                }
+            }
+            for (i, param) in call.params.iter().enumerate().filter(|(_, p)| p.is_output()) {
+                let value = read_output(&stmt, i + 1 + offset, param.param_type)?;
+                result.out_params.insert(param.name.clone(), value);
             }
-            results.push(serde_json::Value::Object(serde_json::Map::from_iter(row_map)));
-        }
 
-        Ok(results)
+            // DBMS_SQL.RETURN_RESULT 로 반환된 암시적 결과 집합
+            while let Some(mut cursor) = stmt.implicit_result()? {
+                result.result_sets.push(result_set(cursor.query()?)?);
+            }
+
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn bulk_insert(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<serde_json::Value>],
+    ) -> Result<BatchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        if self.read_only {
+            return Err("Connection is read-only".into());
+        }
+        let (table, columns, rows) = (table.to_string(), columns.to_vec(), rows.to_vec());
+        self.blocking(move |conn, _| Self::insert_batch(conn, &table, &columns, &rows)).await
     }
 
     async fn close(&self) {
@@ -56,6 +511,7 @@ impl DbConnection for OracleConnection {
     }
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
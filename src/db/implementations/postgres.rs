@@ -1,24 +1,186 @@
-use sqlx::{postgres::PgPoolOptions, PgPool, Row, Column};
-use crate::db::connection::{Connection, ConnectionConfig};
+use sqlx::{postgres::{PgPoolCopyExt, PgPoolOptions, PgQueryResult, PgRow}, Either, Executor, PgPool, Row, Column, TypeInfo};
+use crate::db::connection::{Connection, ConnectionConfig, RowBatcher, RECEIVER_CLOSED};
+use crate::db::explain::{normalize_postgres, ExplainPlan};
+use crate::db::import::{copy_csv_row, BatchOutcome};
+use crate::db::procedure::{ParamType, ProcedureCall, ProcedureResult};
+use crate::db::types::{ColumnMeta, DatabaseType, ResultSet};
+use crate::db::values;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use rust_decimal::Decimal;
 use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+// 이후의 모든 트랜잭션을 읽기 전용으로 시작한다. 한 번에 보낸 여러 문장도 같은 트랜잭션이다
+const READ_ONLY_SESSION: &str = "SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY";
 
 #[derive(Debug,Clone)]
 pub struct PostgresConnection {
     pool: PgPool,
+    read_only: bool,
 }
 
 impl PostgresConnection {
     pub async fn new(config: ConnectionConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let pool = PgPoolOptions::new()
+        let pool = Self::pool_options(&config).connect(&config.connection_string).await?;
+
+        Ok(Self { pool, read_only: config.read_only })
+    }
+
+    // 테스트용: 첫 쿼리를 실행할 때 접속한다
+    #[cfg(test)]
+    pub fn lazy(config: ConnectionConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let pool = Self::pool_options(&config).connect_lazy(&config.connection_string)?;
+        Ok(Self { pool, read_only: config.read_only })
+    }
+
+    fn pool_options(config: &ConnectionConfig) -> PgPoolOptions {
+        let mut options = PgPoolOptions::new()
             .max_connections(config.pool_options.max_connections)
             .min_connections(config.pool_options.min_connections)
             .acquire_timeout(config.get_timeout_duration())
             .idle_timeout(config.get_idle_timeout())
-            .max_lifetime(config.get_max_lifetime())
-            .connect(&config.connection_string)
-            .await?;
+            .max_lifetime(config.get_max_lifetime());
+        // 새 연결과 풀에서 꺼내는 연결 모두에 적용해 쿼리로 세션 설정을 바꿔 둔 경우도 되돌린다
+        if config.read_only {
+            options = options
+                .after_connect(|conn, _| Box::pin(async move { conn.execute(READ_ONLY_SESSION).await.map(|_| ()) }))
+                .before_acquire(|conn, _| Box::pin(async move { conn.execute(READ_ONLY_SESSION).await.map(|_| true) }));
+        }
+        options
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub async fn stream_rows(
+        &self,
+        query: &str,
+        batch_size: usize,
+        sender: mpsc::Sender<ResultSet>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut rows = self.pool.fetch(query);
+        let mut batcher = RowBatcher::new(batch_size);
+        while let Some(row) = rows.try_next().await? {
+            if !batcher.has_columns() {
+                batcher.set_columns(columns_of(&row));
+            }
+            if let Some(batch) = batcher.push(row_to_json(&row)) {
+                sender.send(batch).await.map_err(|_| RECEIVER_CLOSED)?;
+            }
+        }
+        if let Some(batch) = batcher.finish() {
+            sender.send(batch).await.map_err(|_| RECEIVER_CLOSED)?;
+        }
+        Ok(())
+    }
+}
 
-        Ok(Self { pool })
+fn row_to_json(row: &PgRow) -> serde_json::Value {
+    let mut row_map = HashMap::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = match row.try_get::<i32, _>(i) {
+            Ok(v) => serde_json::Value::Number(v.into()),
+            Err(_) => match row.try_get::<i64, _>(i) {
+                Ok(v) => serde_json::Value::Number(v.into()),
+                Err(_) => match row.try_get::<String, _>(i) {
+                    Ok(v) => serde_json::Value::String(v),
+                    Err(_) => match row.try_get::<bool, _>(i) {
+                        Ok(v) => serde_json::Value::Bool(v),
+                        Err(_) => match row.try_get::<f64, _>(i) {
+                            Ok(v) => serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into())),
+                            Err(_) => typed_value(row, i),
+                        },
+                    },
+                },
+            },
+        };
+        row_map.insert(column.name().to_string(), value);
+    }
+    serde_json::Value::Object(serde_json::Map::from_iter(row_map))
+}
+
+// 기본 타입으로 읽히지 않는 컬럼 (작은 정수, 소수, 날짜/시간, 바이너리)
+fn typed_value(row: &PgRow, i: usize) -> serde_json::Value {
+    if let Ok(v) = row.try_get::<i16, _>(i) {
+        serde_json::Value::Number(v.into())
+    } else if let Ok(v) = row.try_get::<f32, _>(i) {
+        values::float_value(v.into())
+    } else if let Ok(v) = row.try_get::<Decimal, _>(i) {
+        values::decimal_value(v)
+    } else if let Ok(v) = row.try_get::<DateTime<Utc>, _>(i) {
+        values::datetime_tz_value(v)
+    } else if let Ok(v) = row.try_get::<NaiveDateTime, _>(i) {
+        values::datetime_value(v)
+    } else if let Ok(v) = row.try_get::<NaiveDate, _>(i) {
+        values::date_value(v)
+    } else if let Ok(v) = row.try_get::<NaiveTime, _>(i) {
+        values::time_value(v)
+    } else if let Ok(v) = row.try_get::<Vec<u8>, _>(i) {
+        values::binary_value(&v)
+    } else {
+        serde_json::Value::Null
+    }
+}
+
+fn columns_of(row: &PgRow) -> Vec<ColumnMeta> {
+    row.columns()
+        .iter()
+        .map(|column| ColumnMeta {
+            name: column.name().to_string(),
+            data_type: column.type_info().name().to_string(),
+        })
+        .collect()
+}
+
+fn result_set(rows: &[PgRow]) -> ResultSet {
+    ResultSet {
+        columns: rows.first().map(columns_of).unwrap_or_default(),
+        rows: rows.iter().map(row_to_json).collect(),
+        affected_rows: None,
+    }
+}
+
+// 각 문장은 CommandComplete(Left) 로 끝나므로 그 단위로 결과 집합을 나눈다
+async fn collect_result_sets(
+    mut stream: BoxStream<'_, Result<Either<PgQueryResult, PgRow>, sqlx::Error>>,
+) -> Result<Vec<ResultSet>, sqlx::Error> {
+    let mut result_sets = Vec::new();
+    let mut current = ResultSet::default();
+    while let Some(item) = stream.try_next().await? {
+        match item {
+            Either::Left(done) => {
+                current.affected_rows = Some(done.rows_affected());
+                result_sets.push(std::mem::take(&mut current));
+            }
+            Either::Right(row) => {
+                if current.columns.is_empty() {
+                    current.columns = columns_of(&row);
+                }
+                current.rows.push(row_to_json(&row));
+            }
+        }
+    }
+    if !current.rows.is_empty() {
+        result_sets.push(current);
+    }
+    Ok(result_sets)
+}
+
+fn bind_json<'q>(
+    query: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    value: &serde_json::Value,
+    param_type: ParamType,
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    match (param_type, value) {
+        (_, serde_json::Value::Null) => query.bind(None::<String>),
+        (ParamType::Integer, v) => query.bind(v.as_i64()),
+        (ParamType::Float, v) => query.bind(v.as_f64()),
+        (ParamType::Boolean, v) => query.bind(v.as_bool()),
+        (_, serde_json::Value::String(v)) => query.bind(v.clone()),
+        (_, v) => query.bind(v.to_string()),
     }
 }
 
@@ -29,32 +191,124 @@ impl Connection for PostgresConnection {
             .fetch_all(&self.pool)
             .await?;
 
-        let mut results = Vec::new();
-        for row in rows {
-            let mut row_map = HashMap::new();
-            for (i, column) in row.columns().iter().enumerate() {
-                let value = match row.try_get::<i32, _>(i) {
-                    Ok(v) => serde_json::Value::Number(v.into()),
-                    Err(_) => match row.try_get::<String, _>(i) {
-                        Ok(v) => serde_json::Value::String(v),
-                        Err(_) => match row.try_get::<bool, _>(i) {
-                            Ok(v) => serde_json::Value::Bool(v),
-                            Err(_) => match row.try_get::<f64, _>(i) {
-                                Ok(v) => serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into())),
-                                Err(_) => serde_json::Value::Null,
-                            },
-                        },
-                    },
-                };
-                row_map.insert(column.name().to_string(), value);
+        Ok(rows.iter().map(row_to_json).collect())
+    }
+
+    async fn execute_result_sets(&self, query: &str) -> Result<Vec<ResultSet>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(collect_result_sets(self.pool.fetch_many(query)).await?)
+    }
+
+    async fn explain(&self, query: &str) -> Result<ExplainPlan, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(&format!("EXPLAIN (FORMAT JSON) {}", query))
+            .fetch_one(&self.pool)
+            .await?;
+        let raw: serde_json::Value = row.try_get(0)?;
+        let plan = normalize_postgres(&raw);
+
+        Ok(ExplainPlan { raw, plan })
+    }
+
+    async fn call_procedure(&self, call: &ProcedureCall) -> Result<ProcedureResult, Box<dyn std::error::Error + Send + Sync>> {
+        call.validate()?;
+
+        // 프로시저의 OUT 파라미터는 타입이 지정된 NULL 로 전달한다
+        let mut args = Vec::new();
+        let mut index = 0;
+        for param in &call.params {
+            let sql_type = param.sql_type_for(DatabaseType::PostgreSQL);
+            if param.is_input() {
+                index += 1;
+                args.push(format!("${}::{}", index, sql_type));
+            } else if call.returns.is_none() {
+                args.push(format!("NULL::{}", sql_type));
             }
-            results.push(serde_json::Value::Object(serde_json::Map::from_iter(row_map)));
         }
+        let sql = match call.returns {
+            Some(_) => format!("SELECT * FROM {}({})", call.name, args.join(", ")),
+            None => format!("CALL {}({})", call.name, args.join(", ")),
+        };
 
-        Ok(results)
+        let mut query = sqlx::query(&sql);
+        for param in call.params.iter().filter(|p| p.is_input()) {
+            query = bind_json(query, &param.value, param.param_type);
+        }
+
+        // refcursor 는 트랜잭션 안에서만 유효하다
+        let mut tx = self.pool.begin().await?;
+        let rows = query.fetch_all(&mut *tx).await?;
+
+        let mut result = ProcedureResult::default();
+        let has_outputs = call.params.iter().any(|p| p.is_output());
+        let mut values = result_set(&rows);
+
+        if has_outputs {
+            if let Some(serde_json::Value::Object(row)) = values.rows.first() {
+                for param in call.params.iter().filter(|p| p.is_output()) {
+                    let value = row.get(&param.name).cloned().unwrap_or(serde_json::Value::Null);
+                    let value = match (param.param_type, value) {
+                        (ParamType::Cursor, serde_json::Value::String(cursor)) => {
+                            serde_json::Value::Array(fetch_cursor(&mut tx, &cursor).await?.rows)
+                        }
+                        (_, value) => value,
+                    };
+                    result.out_params.insert(param.name.clone(), value);
+                }
+            }
+        } else if call.returns.is_some() {
+            let single = values.rows.len() == 1 && values.columns.len() == 1;
+            if single {
+                let value = values.rows.remove(0).as_object_mut().and_then(|row| {
+                    let key = row.keys().next().cloned()?;
+                    row.remove(&key)
+                });
+                match (call.returns, value) {
+                    (Some(ParamType::Cursor), Some(serde_json::Value::String(cursor))) => {
+                        result.result_sets.push(fetch_cursor(&mut tx, &cursor).await?);
+                    }
+                    (_, value) => result.return_value = value,
+                }
+            } else {
+                result.result_sets.push(values);
+            }
+        }
+
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    async fn bulk_insert(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<serde_json::Value>],
+    ) -> Result<BatchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let mut data = Vec::new();
+        for row in rows {
+            copy_csv_row(row, &mut data);
+        }
+
+        let sql = format!("COPY {} ({}) FROM STDIN WITH (FORMAT csv)", table, columns.join(", "));
+        let mut copy = self.pool.copy_in_raw(&sql).await?;
+        if let Err(e) = copy.send(data).await {
+            copy.abort(e.to_string()).await?;
+            return Err(e.into());
+        }
+        let loaded = copy.finish().await?;
+
+        Ok(BatchOutcome { loaded, rejected: Vec::new() })
     }
 
     async fn close(&self) {
         self.pool.close().await;
     }
-} 
\ No newline at end of file
+} 
+
+async fn fetch_cursor(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    cursor: &str,
+) -> Result<ResultSet, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = sqlx::query(&format!("FETCH ALL FROM \"{}\"", cursor.replace('"', "\"\"")))
+        .fetch_all(&mut **tx)
+        .await?;
+    Ok(result_set(&rows))
+}
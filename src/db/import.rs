@@ -0,0 +1,536 @@
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use crate::db::connection::{Connection, DatabaseConnection};
+use crate::db::procedure::ParamType;
+use crate::db::source::is_valid_identifier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Ndjson,
+}
+
+// source: CSV 헤더 이름 (헤더가 없으면 0부터 시작하는 열 번호) 또는 NDJSON 키
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnMapping {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportOptions {
+    pub table: String,
+    pub format: ImportFormat,
+    // 비어 있으면 CSV 헤더 또는 첫 NDJSON 객체의 키를 그대로 사용한다
+    #[serde(default)]
+    pub columns: Vec<ColumnMapping>,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    pub delimiter: Option<char>,
+    #[serde(default = "default_header")]
+    pub header: bool,
+    // 이 값과 같은 CSV 필드는 NULL 로 넣는다
+    #[serde(default)]
+    pub null_value: String,
+    // 보고서에 담을 거부 행 수 (개수는 모두 센다)
+    #[serde(default = "default_max_rejects")]
+    pub max_rejects: usize,
+}
+
+fn default_batch_size() -> usize {
+    1000
+}
+
+fn default_header() -> bool {
+    true
+}
+
+fn default_max_rejects() -> usize {
+    1000
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RowReject {
+    pub line: u64,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub rows_loaded: u64,
+    pub rows_rejected: u64,
+    pub batches: u64,
+    pub rejects: Vec<RowReject>,
+}
+
+impl ImportReport {
+    fn reject(&mut self, line: u64, error: String, max_rejects: usize) {
+        self.rows_rejected += 1;
+        if self.rejects.len() < max_rejects {
+            self.rejects.push(RowReject { line, error });
+        }
+    }
+}
+
+// 적재를 중단시킨 오류와 그때까지의 보고서 (거부된 행 포함)
+#[derive(Debug)]
+pub struct ImportError {
+    pub error: Box<dyn std::error::Error + Send + Sync>,
+    pub report: ImportReport,
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+// 드라이버가 배치 하나를 넣은 결과. rejected 의 번호는 배치 안에서의 행 위치
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    pub loaded: u64,
+    pub rejected: Vec<(usize, String)>,
+}
+
+pub fn is_valid_column_name(name: &str) -> bool {
+    !name.contains('.') && is_valid_identifier(name)
+}
+
+impl ImportOptions {
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if !is_valid_identifier(&self.table) {
+            return Err(format!("Invalid table name: {}", self.table).into());
+        }
+        if let Some(mapping) = self.columns.iter().find(|m| !is_valid_column_name(&m.target)) {
+            return Err(format!("Invalid column name: {}", mapping.target).into());
+        }
+        if self.batch_size == 0 {
+            return Err("batch_size must be greater than 0".into());
+        }
+        if self.format == ImportFormat::Csv && !self.header && self.columns.is_empty() {
+            return Err("CSV without a header row requires a column mapping".into());
+        }
+        // 128 이상의 문자는 UTF-8 로 한 바이트가 아니다
+        if let Some(delimiter) = self.delimiter.filter(|d| !d.is_ascii()) {
+            return Err(format!("Delimiter must be a single-byte ASCII character: {:?}", delimiter).into());
+        }
+        Ok(())
+    }
+}
+
+// 바인드 타입을 값에서 추론한다
+pub fn param_type_of(value: &serde_json::Value) -> ParamType {
+    match value {
+        serde_json::Value::Bool(_) => ParamType::Boolean,
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => ParamType::Integer,
+        serde_json::Value::Number(_) => ParamType::Float,
+        _ => ParamType::String,
+    }
+}
+
+// Postgres COPY ... WITH (FORMAT csv) 한 행. 따옴표 없는 빈 필드만 NULL 이다
+pub fn copy_csv_row(row: &[serde_json::Value], out: &mut Vec<u8>) {
+    for (i, value) in row.iter().enumerate() {
+        if i > 0 {
+            out.push(b',');
+        }
+        match value {
+            serde_json::Value::Null => {}
+            serde_json::Value::Bool(b) => out.extend_from_slice(b.to_string().as_bytes()),
+            serde_json::Value::Number(n) => out.extend_from_slice(n.to_string().as_bytes()),
+            serde_json::Value::String(s) => quote_into(s, out),
+            other => quote_into(&other.to_string(), out),
+        }
+    }
+    out.push(b'\n');
+}
+
+fn quote_into(text: &str, out: &mut Vec<u8>) {
+    out.push(b'"');
+    out.extend_from_slice(text.replace('"', "\"\"").as_bytes());
+    out.push(b'"');
+}
+
+enum Source {
+    Index(usize),
+    Key(String),
+}
+
+// 청크로 들어오는 파일을 레코드 단위로 자르고 대상 컬럼 순서의 값 배열로 바꾼다.
+// CSV 는 따옴표 안의 줄바꿈을 레코드 끝으로 보지 않는다.
+struct RecordParser {
+    format: ImportFormat,
+    delimiter: u8,
+    header: bool,
+    null_value: String,
+    mappings: Vec<ColumnMapping>,
+    buffer: Vec<u8>,
+    scanned: usize,
+    in_quotes: bool,
+    line: u64,
+    record_line: u64,
+    sources: Option<Vec<Source>>,
+    targets: Vec<String>,
+}
+
+type ParsedRow = (u64, Result<Vec<serde_json::Value>, String>);
+
+impl RecordParser {
+    fn new(options: &ImportOptions) -> Self {
+        Self {
+            format: options.format,
+            delimiter: options.delimiter.map(|d| d as u8).unwrap_or(b','),
+            header: options.header,
+            null_value: options.null_value.clone(),
+            mappings: options.columns.clone(),
+            buffer: Vec::new(),
+            scanned: 0,
+            in_quotes: false,
+            line: 1,
+            record_line: 1,
+            sources: None,
+            targets: options.columns.iter().map(|m| m.target.clone()).collect(),
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> Result<Vec<ParsedRow>, String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut rows = Vec::new();
+        let mut start = 0;
+
+        while self.scanned < self.buffer.len() {
+            let byte = self.buffer[self.scanned];
+            self.scanned += 1;
+            match byte {
+                b'"' if self.format == ImportFormat::Csv => self.in_quotes = !self.in_quotes,
+                b'\n' => {
+                    self.line += 1;
+                    if !self.in_quotes {
+                        let record = self.buffer[start..self.scanned - 1].to_vec();
+                        start = self.scanned;
+                        if let Some(row) = self.record(&record)? {
+                            rows.push(row);
+                        }
+                        self.record_line = self.line;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.buffer.drain(..start);
+        self.scanned -= start;
+        Ok(rows)
+    }
+
+    fn finish(&mut self) -> Result<Vec<ParsedRow>, String> {
+        let record = std::mem::take(&mut self.buffer);
+        self.scanned = 0;
+        Ok(self.record(&record)?.into_iter().collect())
+    }
+
+    fn record(&mut self, record: &[u8]) -> Result<Option<ParsedRow>, String> {
+        let record = record.strip_suffix(b"\r").unwrap_or(record);
+        if record.iter().all(|b| b.is_ascii_whitespace()) {
+            return Ok(None);
+        }
+        let line = self.record_line;
+        let text = match std::str::from_utf8(record) {
+            Ok(text) => text,
+            Err(e) => return Ok(Some((line, Err(format!("Invalid UTF-8: {}", e))))),
+        };
+
+        match self.format {
+            ImportFormat::Csv => {
+                let fields = match self.csv_fields(text) {
+                    Ok(fields) => fields,
+                    Err(e) => return Ok(Some((line, Err(e)))),
+                };
+                if self.sources.is_none() {
+                    self.resolve_csv(&fields)?;
+                    if self.header {
+                        return Ok(None);
+                    }
+                }
+                Ok(Some((line, self.csv_row(fields))))
+            }
+            ImportFormat::Ndjson => {
+                let object = match serde_json::from_str::<serde_json::Value>(text) {
+                    Ok(serde_json::Value::Object(object)) => object,
+                    Ok(_) => return Ok(Some((line, Err("Expected a JSON object".into())))),
+                    Err(e) => return Ok(Some((line, Err(e.to_string())))),
+                };
+                if self.sources.is_none() {
+                    self.resolve_ndjson(&object);
+                }
+                Ok(Some((line, Ok(self.ndjson_row(object)))))
+            }
+        }
+    }
+
+    fn csv_fields(&self, text: &str) -> Result<Vec<String>, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(self.delimiter)
+            .from_reader(text.as_bytes());
+        match reader.records().next() {
+            Some(Ok(record)) => Ok(record.iter().map(str::to_string).collect()),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn resolve_csv(&mut self, first: &[String]) -> Result<(), String> {
+        let sources = if self.mappings.is_empty() {
+            for name in first {
+                if !is_valid_column_name(name) {
+                    return Err(format!("Invalid column name in header: {}", name));
+                }
+            }
+            self.targets = first.to_vec();
+            (0..first.len()).map(Source::Index).collect()
+        } else {
+            self.mappings
+                .iter()
+                .map(|mapping| {
+                    let index = if self.header {
+                        first.iter().position(|name| name == &mapping.source)
+                    } else {
+                        mapping.source.parse().ok()
+                    };
+                    index
+                        .map(Source::Index)
+                        .ok_or_else(|| format!("Source column not found: {}", mapping.source))
+                })
+                .collect::<Result<_, _>>()?
+        };
+        self.sources = Some(sources);
+        Ok(())
+    }
+
+    fn resolve_ndjson(&mut self, first: &serde_json::Map<String, serde_json::Value>) {
+        if self.mappings.is_empty() {
+            self.targets = first.keys().cloned().collect();
+            self.sources = Some(first.keys().cloned().map(Source::Key).collect());
+        } else {
+            self.sources = Some(self.mappings.iter().map(|m| Source::Key(m.source.clone())).collect());
+        }
+    }
+
+    fn csv_row(&self, mut fields: Vec<String>) -> Result<Vec<serde_json::Value>, String> {
+        let sources = self.sources.as_deref().unwrap_or_default();
+        sources
+            .iter()
+            .map(|source| match source {
+                Source::Index(i) => match fields.get_mut(*i) {
+                    Some(field) if *field == self.null_value => Ok(serde_json::Value::Null),
+                    Some(field) => Ok(serde_json::Value::String(std::mem::take(field))),
+                    None => Err(format!("Expected at least {} fields, found {}", i + 1, fields.len())),
+                },
+                Source::Key(_) => Ok(serde_json::Value::Null),
+            })
+            .collect()
+    }
+
+    fn ndjson_row(&self, mut object: serde_json::Map<String, serde_json::Value>) -> Vec<serde_json::Value> {
+        let sources = self.sources.as_deref().unwrap_or_default();
+        sources
+            .iter()
+            .map(|source| match source {
+                Source::Key(key) => match object.remove(key) {
+                    Some(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+                        serde_json::Value::String(value.to_string())
+                    }
+                    Some(value) => value,
+                    None => serde_json::Value::Null,
+                },
+                Source::Index(_) => serde_json::Value::Null,
+            })
+            .collect()
+    }
+}
+
+struct Batch {
+    lines: Vec<u64>,
+    rows: Vec<Vec<serde_json::Value>>,
+}
+
+impl Batch {
+    fn clear(&mut self) {
+        self.lines.clear();
+        self.rows.clear();
+    }
+}
+
+// 배치 하나를 넣고 결과를 보고서에 더한다. lines 는 각 행의 원본 위치 (거부 보고용)
+pub async fn load_batch(
+    connection: &DatabaseConnection,
+    table: &str,
+    columns: &[String],
+    lines: &[u64],
+    rows: &[Vec<serde_json::Value>],
+    max_rejects: usize,
+    report: &mut ImportReport,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    report.batches += 1;
+    match connection.bulk_insert(table, columns, rows).await {
+        Ok(outcome) => {
+            report.rows_loaded += outcome.loaded;
+            for (i, error) in outcome.rejected {
+                report.reject(lines[i], error, max_rejects);
+            }
+        }
+        Err(batch_error) => {
+            // 배치가 통째로 실패하면 한 행씩 다시 넣어 거부된 행을 찾는다
+            let mut loaded = 0;
+            for (line, row) in lines.iter().zip(rows) {
+                match connection.bulk_insert(table, columns, std::slice::from_ref(row)).await {
+                    Ok(outcome) => loaded += outcome.loaded,
+                    Err(e) => report.reject(*line, e.to_string(), max_rejects),
+                }
+            }
+            // 아무 행도 들어가지 않았다면 테이블/컬럼 자체의 문제로 보고 중단한다
+            if loaded == 0 && report.rows_loaded == 0 {
+                return Err(batch_error);
+            }
+            report.rows_loaded += loaded;
+        }
+    }
+    Ok(())
+}
+
+// 파일 스트림을 읽으면서 batch_size 단위로 넣는다. 중단되면 오류와 함께 보고서를 돌려준다
+pub async fn import_stream<S, B, E>(
+    connection: &DatabaseConnection,
+    options: &ImportOptions,
+    stream: S,
+) -> Result<ImportReport, ImportError>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: std::fmt::Display,
+{
+    let mut report = ImportReport::default();
+    match import_batches(connection, options, stream, &mut report).await {
+        Ok(()) => Ok(report),
+        Err(error) => Err(ImportError { error, report }),
+    }
+}
+
+async fn import_batches<S, B, E>(
+    connection: &DatabaseConnection,
+    options: &ImportOptions,
+    stream: S,
+    report: &mut ImportReport,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: std::fmt::Display,
+{
+    options.validate()?;
+    futures::pin_mut!(stream);
+
+    let mut parser = RecordParser::new(options);
+    let mut batch = Batch { lines: Vec::new(), rows: Vec::new() };
+    let mut finished = false;
+
+    while !finished {
+        let rows = match stream.next().await {
+            Some(chunk) => parser.push(chunk.map_err(|e| e.to_string())?.as_ref())?,
+            None => {
+                finished = true;
+                parser.finish()?
+            }
+        };
+        for (line, row) in rows {
+            match row {
+                Ok(row) => {
+                    batch.lines.push(line);
+                    batch.rows.push(row);
+                }
+                Err(error) => report.reject(line, error, options.max_rejects),
+            }
+            if batch.rows.len() >= options.batch_size {
+                load_batch(connection, &options.table, &parser.targets, &batch.lines, &batch.rows, options.max_rejects, report).await?;
+                batch.clear();
+            }
+        }
+    }
+    load_batch(connection, &options.table, &parser.targets, &batch.lines, &batch.rows, options.max_rejects, report).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(value: serde_json::Value) -> ImportOptions {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_csv_records_across_chunks() {
+        let options = options(json!({
+            "table": "books",
+            "format": "csv",
+            "columns": [{"source": "Title", "target": "title"}, {"source": "Year", "target": "year"}]
+        }));
+        let mut parser = RecordParser::new(&options);
+
+        let mut rows = parser.push(b"Year,Title\r\n2001,\"Multi\nline\"\r\n19").unwrap();
+        rows.extend(parser.push(b"99,\nbroken\n").unwrap());
+        rows.extend(parser.finish().unwrap());
+
+        assert_eq!(rows[0], (2, Ok(vec![json!("Multi\nline"), json!("2001")])));
+        assert_eq!(rows[1], (4, Ok(vec![json!(null), json!("1999")])));
+        assert!(rows[2].1.is_err());
+        assert_eq!(rows[2].0, 5);
+    }
+
+    #[test]
+    fn test_ndjson_records() {
+        let options = options(json!({"table": "books", "format": "ndjson"}));
+        let mut parser = RecordParser::new(&options);
+
+        let rows = parser.push(b"{\"id\": 1, \"tags\": [\"a\"]}\nnot json\n{\"id\": 2}\n").unwrap();
+        assert_eq!(parser.targets, vec!["id", "tags"]);
+        assert_eq!(rows[0].1, Ok(vec![json!(1), json!("[\"a\"]")]));
+        assert!(rows[1].1.is_err());
+        assert_eq!(rows[2].1, Ok(vec![json!(2), json!(null)]));
+    }
+
+    #[test]
+    fn test_copy_csv_row() {
+        let mut out = Vec::new();
+        copy_csv_row(&[json!(1), json!(null), json!(""), json!("say \"hi\"")], &mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), "1,,\"\",\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn test_delimiter_must_be_ascii() {
+        let mut options = options(json!({"table": "books", "format": "csv"}));
+        options.delimiter = Some(';');
+        assert!(options.validate().is_ok());
+        options.delimiter = Some('\u{a7}');
+        assert!(options.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_aborted_import_keeps_rejects() {
+        let options = options(json!({"table": "books", "format": "csv"}));
+        let connection = DatabaseConnection::unreachable(false);
+        let chunks = vec![Ok::<_, String>(b"id,title\n1,Dune\n".to_vec())];
+
+        let error = import_stream(&connection, &options, futures::stream::iter(chunks)).await.unwrap_err();
+        assert_eq!(error.report.rows_loaded, 0);
+        assert_eq!(error.report.rows_rejected, 1);
+        assert_eq!(error.report.rejects[0].line, 2);
+    }
+}
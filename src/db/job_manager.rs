@@ -0,0 +1,286 @@
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::db::connection::{Connection, DatabaseConnection};
+use crate::db::job_registry::JobRegistry;
+use crate::db::types::{ColumnMeta, DatabaseType, ResultSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobOptions {
+    pub max_concurrent_jobs: usize,
+    pub retention_seconds: u64,
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrent_jobs: 4,
+            retention_seconds: 3600,
+        }
+    }
+}
+
+impl JobOptions {
+    // JOB_MAX_CONCURRENT, JOB_RETENTION_SECONDS 환경 변수로 기본값을 덮어쓴다
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_concurrent_jobs: std::env::var("JOB_MAX_CONCURRENT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_concurrent_jobs),
+            retention_seconds: std::env::var("JOB_RETENTION_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.retention_seconds),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QueryJob {
+    connection_id: String,
+    submitted_by: String,
+    db_type: DatabaseType,
+    result_sets: Vec<ResultSet>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub connection_id: String,
+    // 제출한 Principal 의 subject
+    pub submitted_by: String,
+    pub status: JobStatus,
+    // 대기 중이면 0.0, 끝났으면 1.0, 실행 중에는 드라이버가 진행률을 알려주지 않으므로 None
+    pub progress: Option<f32>,
+    pub queue_position: Option<usize>,
+    pub elapsed_ms: u128,
+    pub result_set_count: usize,
+    pub row_count: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultPage {
+    pub result_set: usize,
+    pub columns: Vec<ColumnMeta>,
+    pub rows: Vec<serde_json::Value>,
+    pub affected_rows: Option<u64>,
+    pub offset: usize,
+    pub limit: usize,
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobManager {
+    jobs: JobRegistry<QueryJob>,
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        Self::new(JobOptions::default())
+    }
+}
+
+impl JobManager {
+    pub fn new(options: JobOptions) -> Self {
+        JobManager {
+            jobs: JobRegistry::new(options),
+        }
+    }
+
+    pub async fn submit(
+        &self,
+        connection_id: String,
+        submitted_by: String,
+        connection: Arc<DatabaseConnection>,
+        query: String,
+    ) -> String {
+        let job = QueryJob {
+            connection_id,
+            submitted_by,
+            db_type: connection.db_type(),
+            result_sets: Vec::new(),
+        };
+        let jobs = self.jobs.clone();
+        self.jobs
+            .submit(job, move |id| async move {
+                let result_sets = connection.execute_result_sets(&query).await.map_err(|e| e.to_string())?;
+                jobs.update(&id, |job| job.result_sets = result_sets).await;
+                Ok(())
+            })
+            .await
+    }
+
+    pub async fn get_job(&self, id: &str) -> Option<JobInfo> {
+        self.jobs
+            .get(id, |job, queue_position| JobInfo {
+                id: id.to_string(),
+                connection_id: job.data.connection_id.clone(),
+                submitted_by: job.data.submitted_by.clone(),
+                status: job.status,
+                progress: match job.status {
+                    JobStatus::Queued => Some(0.0),
+                    JobStatus::Running => None,
+                    _ => Some(1.0),
+                },
+                queue_position,
+                elapsed_ms: job.elapsed().as_millis(),
+                result_set_count: job.data.result_sets.len(),
+                row_count: job.data.result_sets.iter().map(|r| r.rows.len()).sum(),
+                error: job.error.clone(),
+            })
+            .await
+    }
+
+    pub async fn get_results(
+        &self,
+        id: &str,
+        result_set: usize,
+        offset: usize,
+        limit: usize,
+    ) -> Result<ResultPage, Box<dyn std::error::Error + Send + Sync>> {
+        self.jobs
+            .get(id, |job, _| {
+                if job.status != JobStatus::Completed {
+                    return Err(format!("Job is not completed (status: {:?})", job.status));
+                }
+                let selected = job
+                    .data
+                    .result_sets
+                    .get(result_set)
+                    .ok_or_else(|| format!("Result set {} does not exist", result_set))?;
+
+                Ok(ResultPage {
+                    result_set,
+                    columns: selected.columns.clone(),
+                    rows: selected.rows.iter().skip(offset).take(limit).cloned().collect(),
+                    affected_rows: selected.affected_rows,
+                    offset,
+                    limit,
+                    total_rows: selected.rows.len(),
+                })
+            })
+            .await
+            .ok_or("Job not found")?
+            .map_err(Into::into)
+    }
+
+    // 내보내기용: 완료된 작업의 결과 집합 전체
+    pub async fn get_result_sets(
+        &self,
+        id: &str,
+    ) -> Result<(DatabaseType, Vec<ResultSet>), Box<dyn std::error::Error + Send + Sync>> {
+        self.jobs
+            .get(id, |job, _| {
+                if job.status != JobStatus::Completed {
+                    return Err(format!("Job is not completed (status: {:?})", job.status));
+                }
+                Ok((job.data.db_type, job.data.result_sets.clone()))
+            })
+            .await
+            .ok_or("Job not found")?
+            .map_err(Into::into)
+    }
+
+    // 대기/실행 중인 작업만 취소할 수 있다. 서버에서 실행 중인 쿼리는 취소하지 않는다 (JobRegistry::cancel)
+    pub async fn cancel(&self, id: &str) -> Option<JobStatus> {
+        self.jobs.cancel(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // 실행하지 않고 결과가 있는 완료된 작업을 만든다
+    async fn completed_job(manager: &JobManager, rows: usize) -> String {
+        let result_set = ResultSet {
+            rows: (0..rows).map(|i| serde_json::json!({ "n": i })).collect(),
+            ..Default::default()
+        };
+        let job = QueryJob {
+            connection_id: "c1".into(),
+            submitted_by: "alice".into(),
+            db_type: DatabaseType::PostgreSQL,
+            result_sets: vec![result_set],
+        };
+        let id = manager.jobs.submit(job, |_| async { Ok(()) }).await;
+        wait_finished(manager, &id).await;
+        id
+    }
+
+    async fn wait_finished(manager: &JobManager, id: &str) -> JobInfo {
+        for _ in 0..100 {
+            let info = manager.get_job(id).await.unwrap();
+            if info.status.is_finished() {
+                return info;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[tokio::test]
+    async fn test_submit_runs_to_finish() {
+        let manager = JobManager::default();
+        let id = manager
+            .submit("c1".into(), "alice".into(), Arc::new(DatabaseConnection::unreachable(false)), "SELECT 1".into())
+            .await;
+        let info = manager.get_job(&id).await.unwrap();
+        assert!(matches!(info.status, JobStatus::Queued | JobStatus::Running));
+        assert_eq!(info.connection_id, "c1");
+
+        let info = wait_finished(&manager, &id).await;
+        assert_eq!(info.status, JobStatus::Failed);
+        assert!(info.error.is_some());
+        assert_eq!(info.progress, Some(1.0));
+        assert!(manager.get_results(&id, 0, 0, 10).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_result_paging_bounds() {
+        let manager = JobManager::default();
+        let id = completed_job(&manager, 25).await;
+        assert_eq!(manager.get_job(&id).await.unwrap().row_count, 25);
+
+        let page = manager.get_results(&id, 0, 20, 10).await.unwrap();
+        assert_eq!(page.rows.len(), 5);
+        assert_eq!(page.rows[0], serde_json::json!({ "n": 20 }));
+        assert_eq!(page.total_rows, 25);
+        assert!(manager.get_results(&id, 0, 100, 10).await.unwrap().rows.is_empty());
+        assert!(manager.get_results(&id, 1, 0, 10).await.is_err());
+        assert!(manager.get_results("missing", 0, 0, 10).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_job() {
+        let manager = JobManager::new(JobOptions { max_concurrent_jobs: 1, retention_seconds: 3600 });
+        let connection = Arc::new(DatabaseConnection::unreachable(false));
+        let first = manager.submit("c1".into(), "alice".into(), connection.clone(), "SELECT 1".into()).await;
+        let second = manager.submit("c1".into(), "alice".into(), connection, "SELECT 2".into()).await;
+
+        assert_eq!(manager.get_job(&second).await.unwrap().queue_position, Some(1));
+        assert_eq!(manager.cancel(&second).await, Some(JobStatus::Cancelled));
+        assert!(manager.get_results(&second, 0, 0, 10).await.is_err());
+        wait_finished(&manager, &first).await;
+        assert_eq!(manager.get_job(&second).await.unwrap().status, JobStatus::Cancelled);
+    }
+}
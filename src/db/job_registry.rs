@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, Semaphore};
+use tokio::task::AbortHandle;
+use uuid::Uuid;
+use crate::db::job_manager::{JobOptions, JobStatus};
+
+// 백그라운드 작업 하나. data 는 작업 종류별 상태 (쿼리 결과, 복사 보고서 등)
+#[derive(Debug)]
+pub struct JobEntry<T> {
+    pub status: JobStatus,
+    pub submitted_at: Instant,
+    pub started_at: Option<Instant>,
+    pub finished_at: Option<Instant>,
+    pub error: Option<String>,
+    pub data: T,
+    handle: Option<AbortHandle>,
+}
+
+impl<T> JobEntry<T> {
+    pub fn elapsed(&self) -> Duration {
+        match (self.started_at, self.finished_at) {
+            (Some(started), Some(finished)) => finished - started,
+            (Some(started), None) => started.elapsed(),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+// 작업 상태 전이(대기 → 실행 → 완료/실패/취소), 동시 실행 수 제한, 보존 기간이 지난 작업 정리를
+// JobManager 와 CopyManager 가 같이 쓴다
+#[derive(Debug)]
+pub struct JobRegistry<T> {
+    entries: Arc<RwLock<HashMap<String, JobEntry<T>>>>,
+    workers: Arc<Semaphore>,
+    options: JobOptions,
+}
+
+impl<T> Clone for JobRegistry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            workers: self.workers.clone(),
+            options: self.options.clone(),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> JobRegistry<T> {
+    pub fn new(options: JobOptions) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            workers: Arc::new(Semaphore::new(options.max_concurrent_jobs.max(1))),
+            options,
+        }
+    }
+
+    // 작업을 등록하고 작업자가 비면 run(작업 ID) 을 실행한다. Err 는 작업의 error 가 된다
+    pub async fn submit<F, Fut>(&self, data: T, run: F) -> String
+    where
+        F: FnOnce(String) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        self.purge_expired().await;
+
+        let id = Uuid::new_v4().to_string();
+        let mut entries = self.entries.write().await;
+        entries.insert(
+            id.clone(),
+            JobEntry {
+                status: JobStatus::Queued,
+                submitted_at: Instant::now(),
+                started_at: None,
+                finished_at: None,
+                error: None,
+                data,
+                handle: None,
+            },
+        );
+
+        let registry = self.clone();
+        let job_id = id.clone();
+        let task = tokio::spawn(async move {
+            // 세마포어로 동시에 실행되는 작업 수를 제한한다
+            let Ok(_permit) = registry.workers.clone().acquire_owned().await else {
+                return;
+            };
+            if !registry.mark_running(&job_id).await {
+                return;
+            }
+            let result = run(job_id.clone()).await;
+            registry.finish(&job_id, result).await;
+        });
+        if let Some(entry) = entries.get_mut(&id) {
+            entry.handle = Some(task.abort_handle());
+        }
+
+        id
+    }
+
+    async fn mark_running(&self, id: &str) -> bool {
+        let mut entries = self.entries.write().await;
+        match entries.get_mut(id) {
+            Some(entry) if entry.status == JobStatus::Queued => {
+                entry.status = JobStatus::Running;
+                entry.started_at = Some(Instant::now());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // 실행 중인 작업만 끝낸다. 그 사이 취소된 작업은 그대로 둔다
+    async fn finish(&self, id: &str, result: Result<(), String>) {
+        let mut entries = self.entries.write().await;
+        if let Some(entry) = entries.get_mut(id) {
+            if entry.status != JobStatus::Running {
+                return;
+            }
+            match result {
+                Ok(()) => entry.status = JobStatus::Completed,
+                Err(error) => {
+                    entry.status = JobStatus::Failed;
+                    entry.error = Some(error);
+                }
+            }
+            entry.finished_at = Some(Instant::now());
+            entry.handle = None;
+        }
+    }
+
+    pub async fn update(&self, id: &str, apply: impl FnOnce(&mut T)) {
+        if let Some(entry) = self.entries.write().await.get_mut(id) {
+            apply(&mut entry.data);
+        }
+    }
+
+    // view 에는 작업과 대기 중일 때의 대기열 위치(앞에 있는 작업 수)를 넘긴다
+    pub async fn get<R>(&self, id: &str, view: impl FnOnce(&JobEntry<T>, Option<usize>) -> R) -> Option<R> {
+        self.purge_expired().await;
+
+        let entries = self.entries.read().await;
+        let entry = entries.get(id)?;
+        let queue_position = (entry.status == JobStatus::Queued).then(|| {
+            entries
+                .values()
+                .filter(|other| other.status == JobStatus::Queued && other.submitted_at < entry.submitted_at)
+                .count()
+        });
+        Some(view(entry, queue_position))
+    }
+
+    // 대기/실행 중인 작업만 취소할 수 있다. 작업 태스크를 중단하고 결과를 기다리지 않을 뿐
+    // 서버에 취소 요청을 보내지는 않는다. 이미 서버로 보낸 쿼리는 끝날 때까지 실행되고
+    // (Oracle 드라이버는 동기 호출이라 태스크 중단도 쿼리가 끝난 뒤에 일어난다) 결과는 버린다
+    pub async fn cancel(&self, id: &str) -> Option<JobStatus> {
+        let mut entries = self.entries.write().await;
+        let entry = entries.get_mut(id)?;
+        if !entry.status.is_finished() {
+            if let Some(handle) = entry.handle.take() {
+                handle.abort();
+            }
+            entry.status = JobStatus::Cancelled;
+            entry.finished_at = Some(Instant::now());
+        }
+        Some(entry.status)
+    }
+
+    async fn purge_expired(&self) {
+        let retention = Duration::from_secs(self.options.retention_seconds);
+        let mut entries = self.entries.write().await;
+        entries.retain(|_, entry| match entry.finished_at {
+            Some(finished) => finished.elapsed() < retention,
+            None => true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::oneshot;
+
+    fn status(registry: &JobRegistry<u32>, id: &str) -> impl Future<Output = Option<JobStatus>> {
+        let (registry, id) = (registry.clone(), id.to_string());
+        async move { registry.get(&id, |entry, _| entry.status).await }
+    }
+
+    async fn wait_finished(registry: &JobRegistry<u32>, id: &str) -> JobStatus {
+        for _ in 0..100 {
+            match status(registry, id).await {
+                Some(status) if status.is_finished() => return status,
+                _ => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[tokio::test]
+    async fn test_job_lifecycle() {
+        let registry = JobRegistry::<u32>::new(JobOptions::default());
+        let (release, released) = oneshot::channel::<()>();
+        let updater = registry.clone();
+        let id = registry
+            .submit(0, move |id| async move {
+                updater.update(&id, |value| *value = 7).await;
+                released.await.map_err(|e| e.to_string())
+            })
+            .await;
+
+        for _ in 0..100 {
+            if status(&registry, &id).await == Some(JobStatus::Running) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(status(&registry, &id).await, Some(JobStatus::Running));
+        release.send(()).unwrap();
+        assert_eq!(wait_finished(&registry, &id).await, JobStatus::Completed);
+        assert_eq!(registry.get(&id, |entry, _| entry.data).await, Some(7));
+
+        let failed = registry.submit(0, |_| async { Err("boom".to_string()) }).await;
+        assert_eq!(wait_finished(&registry, &failed).await, JobStatus::Failed);
+        assert_eq!(registry.get(&failed, |entry, _| entry.error.clone()).await, Some(Some("boom".into())));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_queue_position() {
+        // 작업자가 하나뿐이라 두 번째 작업은 대기열에 남는다
+        let registry = JobRegistry::<u32>::new(JobOptions { max_concurrent_jobs: 1, retention_seconds: 3600 });
+        let (release, released) = oneshot::channel::<()>();
+        let first = registry.submit(0, |_| async move { released.await.map_err(|e| e.to_string()) }).await;
+        let second = registry.submit(0, |_| async { Ok(()) }).await;
+        let third = registry.submit(0, |_| async { Ok(()) }).await;
+
+        assert_eq!(registry.get(&third, |_, position| position).await, Some(Some(2)));
+        assert_eq!(registry.cancel(&second).await, Some(JobStatus::Cancelled));
+        assert_eq!(registry.get(&third, |_, position| position).await, Some(Some(1)));
+        assert_eq!(registry.cancel("missing").await, None);
+
+        // 취소한 작업은 작업자가 비어도 실행되지 않는다
+        release.send(()).unwrap();
+        assert_eq!(wait_finished(&registry, &first).await, JobStatus::Completed);
+        assert_eq!(wait_finished(&registry, &third).await, JobStatus::Completed);
+        assert_eq!(status(&registry, &second).await, Some(JobStatus::Cancelled));
+        // 끝난 작업은 취소해도 상태가 바뀌지 않는다
+        assert_eq!(registry.cancel(&first).await, Some(JobStatus::Completed));
+    }
+
+    #[tokio::test]
+    async fn test_purge_finished_jobs() {
+        let registry = JobRegistry::<u32>::new(JobOptions { max_concurrent_jobs: 1, retention_seconds: 0 });
+        let (release, released) = oneshot::channel::<()>();
+        let running = registry.submit(0, |_| async move { released.await.map_err(|e| e.to_string()) }).await;
+        let queued = registry.submit(0, |_| async { Ok(()) }).await;
+        assert_eq!(registry.cancel(&queued).await, Some(JobStatus::Cancelled));
+
+        // 끝난 작업은 보존 기간이 지나면 사라지고 끝나지 않은 작업은 남는다
+        assert_eq!(status(&registry, &queued).await, None);
+        assert!(status(&registry, &running).await.is_some());
+        release.send(()).unwrap();
+        for _ in 0..100 {
+            if status(&registry, &running).await.is_none() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("finished job was not purged");
+    }
+}
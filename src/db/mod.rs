@@ -1,4 +1,22 @@
 pub mod connection;
 pub mod connection_manager;
+pub mod constraints;
+pub mod copy;
+pub mod copy_manager;
+pub mod ddl;
+pub mod dump;
+pub mod explain;
+pub mod import;
 pub mod implementations;
-pub mod types; 
\ No newline at end of file
+pub mod job_manager;
+pub mod job_registry;
+pub mod policy;
+pub mod procedure;
+pub mod profile;
+pub mod schema;
+pub mod schema_diff;
+pub mod snapshot;
+pub mod source;
+pub mod statement;
+pub mod types; 
+pub mod values;
\ No newline at end of file
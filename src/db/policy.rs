@@ -0,0 +1,341 @@
+use std::fmt;
+use std::ops::ControlFlow;
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{visit_expressions, visit_relations, Expr, Ident, ObjectName, ObjectType, Statement};
+use sqlparser::dialect::{Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::parser::Parser;
+use crate::db::types::DatabaseType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementType {
+    // SELECT, SHOW, EXPLAIN (ANALYZE 제외)
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Merge,
+    Create,
+    Alter,
+    Drop,
+    Truncate,
+    // CALL, EXEC
+    Call,
+    // BEGIN, COMMIT, ROLLBACK, SAVEPOINT
+    Transaction,
+    // SET, GRANT 등 나머지
+    Other,
+}
+
+impl StatementType {
+    fn of(statement: &Statement) -> Self {
+        match statement {
+            Statement::Query(_)
+            | Statement::ShowTables { .. }
+            | Statement::ShowColumns { .. }
+            | Statement::ShowCreate { .. }
+            | Statement::ShowVariable { .. }
+            | Statement::ShowVariables { .. }
+            | Statement::ShowFunctions { .. }
+            | Statement::ShowDatabases { .. }
+            | Statement::ShowSchemas { .. }
+            | Statement::ShowViews { .. }
+            | Statement::ExplainTable { .. } => Self::Select,
+            // EXPLAIN ANALYZE 는 문장을 실제로 실행한다
+            Statement::Explain { analyze: true, statement, .. } => Self::of(statement),
+            Statement::Explain { .. } => Self::Select,
+            Statement::Insert(_) => Self::Insert,
+            Statement::Update { .. } => Self::Update,
+            Statement::Delete(_) => Self::Delete,
+            Statement::Merge { .. } => Self::Merge,
+            Statement::CreateTable(_)
+            | Statement::CreateView { .. }
+            | Statement::CreateIndex(_)
+            | Statement::CreateSchema { .. }
+            | Statement::CreateDatabase { .. }
+            | Statement::CreateFunction(_)
+            | Statement::CreateProcedure { .. }
+            | Statement::CreateSequence { .. }
+            | Statement::CreateTrigger { .. }
+            | Statement::CreateRole { .. } => Self::Create,
+            Statement::AlterTable { .. }
+            | Statement::AlterView { .. }
+            | Statement::AlterIndex { .. }
+            | Statement::AlterRole { .. } => Self::Alter,
+            Statement::Drop { .. }
+            | Statement::DropFunction { .. }
+            | Statement::DropProcedure { .. }
+            | Statement::DropTrigger { .. } => Self::Drop,
+            Statement::Truncate { .. } => Self::Truncate,
+            Statement::Call(_) | Statement::Execute { .. } => Self::Call,
+            Statement::StartTransaction { .. }
+            | Statement::Commit { .. }
+            | Statement::Rollback { .. }
+            | Statement::Savepoint { .. } => Self::Transaction,
+            _ => Self::Other,
+        }
+    }
+}
+
+// 연결별 SQL 정책. 비어 있는 항목은 검사하지 않는다
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqlPolicy {
+    // 없으면 모든 문장 종류를 허용
+    pub allowed_statements: Option<Vec<StatementType>>,
+    // "table" 은 스키마와 상관없이, "schema.table" 은 그 스키마의 테이블만
+    pub denied_tables: Vec<String>,
+    pub denied_schemas: Vec<String>,
+    // pg_read_file, xp_cmdshell 처럼 호출하면 안 되는 함수와 프로시저
+    pub denied_functions: Vec<String>,
+    // WHERE 없는 UPDATE, DELETE 를 거부
+    pub require_where: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRule {
+    // 정책이 있는 연결에서 해석할 수 없는 SQL 은 거부한다
+    Parse,
+    AllowedStatements,
+    DeniedTables,
+    DeniedSchemas,
+    DeniedFunctions,
+    RequireWhere,
+}
+
+// 어떤 규칙이 몇 번째 문장(0부터)의 무엇 때문에 걸렸는지
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyViolation {
+    pub rule: PolicyRule,
+    pub statement: usize,
+    pub target: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rejected by SQL policy: {}", self.message)
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+// Oracle 방언은 sqlparser 에 없어서 일반 방언으로 해석한다
+fn dialect(db_type: DatabaseType) -> Box<dyn Dialect> {
+    match db_type {
+        DatabaseType::PostgreSQL => Box::new(PostgreSqlDialect {}),
+        DatabaseType::MySQL => Box::new(MySqlDialect {}),
+        DatabaseType::MSSQL => Box::new(MsSqlDialect {}),
+        _ => Box::new(GenericDialect {}),
+    }
+}
+
+fn name_parts(name: &ObjectName) -> Vec<String> {
+    name.0.iter().map(|ident| ident.value.to_lowercase()).collect()
+}
+
+fn rule_parts(rule: &str) -> Vec<String> {
+    rule.split('.').map(|part| part.trim().trim_matches(|c| matches!(c, '"' | '`' | '[' | ']')).to_lowercase()).collect()
+}
+
+// 검증된 이름(schema.table, schema.routine)을 마침표로 나눈다
+fn object_name(name: &str) -> ObjectName {
+    ObjectName(name.split('.').map(Ident::new).collect())
+}
+
+fn violation((rule, target, message): (PolicyRule, Option<String>, String)) -> PolicyViolation {
+    PolicyViolation { rule, statement: 0, target, message }
+}
+
+impl SqlPolicy {
+    // /call 처럼 SQL 없이 부르는 루틴. CALL 문장과 같은 규칙으로 검사한다
+    pub fn check_routine(&self, name: &str) -> Result<(), PolicyViolation> {
+        self.check_kind(StatementType::Call).map_err(violation)?;
+        self.check_function(&object_name(name)).map_err(violation)
+    }
+
+    // 가져오기와 복사처럼 SQL 없이 행을 넣는 테이블. INSERT (create 면 CREATE TABLE 도) 와 같은 규칙으로 검사한다
+    pub fn check_table_write(&self, table: &str, create: bool) -> Result<(), PolicyViolation> {
+        self.check_kind(StatementType::Insert).map_err(violation)?;
+        if create {
+            self.check_kind(StatementType::Create).map_err(violation)?;
+        }
+        self.check_relation(&object_name(table)).map_err(violation)
+    }
+
+    pub fn check(&self, db_type: DatabaseType, sql: &str) -> Result<(), PolicyViolation> {
+        let statements = Parser::parse_sql(dialect(db_type).as_ref(), sql).map_err(|e| PolicyViolation {
+            rule: PolicyRule::Parse,
+            statement: 0,
+            target: None,
+            message: format!("Could not parse SQL: {}", e),
+        })?;
+        for (index, statement) in statements.iter().enumerate() {
+            self.check_statement(statement).map_err(|(rule, target, message)| PolicyViolation {
+                rule,
+                statement: index,
+                target,
+                message,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn check_kind(&self, kind: StatementType) -> Result<(), (PolicyRule, Option<String>, String)> {
+        if let Some(allowed) = &self.allowed_statements {
+            if !allowed.contains(&kind) {
+                let name = serde_json::to_value(kind).ok().and_then(|v| v.as_str().map(str::to_string));
+                return Err((
+                    PolicyRule::AllowedStatements,
+                    name.clone(),
+                    format!("{} statements are not allowed", name.unwrap_or_default().to_uppercase()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_statement(&self, statement: &Statement) -> Result<(), (PolicyRule, Option<String>, String)> {
+        self.check_kind(StatementType::of(statement))?;
+
+        if self.require_where {
+            let missing = match statement {
+                Statement::Update { selection, .. } => selection.is_none(),
+                Statement::Delete(delete) => delete.selection.is_none(),
+                _ => false,
+            };
+            if missing {
+                return Err((
+                    PolicyRule::RequireWhere,
+                    None,
+                    "UPDATE and DELETE statements must have a WHERE clause".into(),
+                ));
+            }
+        }
+
+        // DROP 의 대상은 relation 으로 방문되지 않는다
+        let mut relations = Vec::new();
+        let _ = visit_relations(statement, |name: &ObjectName| {
+            relations.push(name.clone());
+            ControlFlow::<()>::Continue(())
+        });
+        if let Statement::Drop { object_type: ObjectType::Table | ObjectType::View, names, .. } = statement {
+            relations.extend(names.iter().cloned());
+        }
+        for relation in &relations {
+            self.check_relation(relation)?;
+        }
+
+        let mut functions = Vec::new();
+        let _ = visit_expressions(statement, |expr: &Expr| {
+            if let Expr::Function(function) = expr {
+                functions.push(function.name.clone());
+            }
+            ControlFlow::<()>::Continue(())
+        });
+        match statement {
+            Statement::Call(function) => functions.push(function.name.clone()),
+            Statement::Execute { name, .. } => functions.push(name.clone()),
+            _ => {}
+        }
+        for function in &functions {
+            self.check_function(function)?;
+        }
+        Ok(())
+    }
+
+    fn check_function(&self, function: &ObjectName) -> Result<(), (PolicyRule, Option<String>, String)> {
+        let name = name_parts(function).pop().unwrap_or_default();
+        if self.denied_functions.iter().any(|denied| rule_parts(denied).last() == Some(&name)) {
+            return Err((
+                PolicyRule::DeniedFunctions,
+                Some(function.to_string()),
+                format!("Function {} is not allowed", function),
+            ));
+        }
+        Ok(())
+    }
+
+    // 스키마 없이 쓴 테이블은 denied_schemas 로 걸러지지 않는다
+    fn check_relation(&self, relation: &ObjectName) -> Result<(), (PolicyRule, Option<String>, String)> {
+        let parts = name_parts(relation);
+        if parts.len() >= 2 {
+            let schema = &parts[parts.len() - 2];
+            if self.denied_schemas.iter().any(|denied| rule_parts(denied).last() == Some(schema)) {
+                return Err((
+                    PolicyRule::DeniedSchemas,
+                    Some(relation.to_string()),
+                    format!("Schema {} is not allowed", schema),
+                ));
+            }
+        }
+        if self.denied_tables.iter().any(|denied| parts.ends_with(&rule_parts(denied))) {
+            return Err((
+                PolicyRule::DeniedTables,
+                Some(relation.to_string()),
+                format!("Table {} is not allowed", relation),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_rules() {
+        let policy = SqlPolicy {
+            allowed_statements: Some(vec![StatementType::Select, StatementType::Update, StatementType::Delete, StatementType::Call]),
+            denied_tables: vec!["hr.salaries".into(), "secrets".into()],
+            denied_schemas: vec!["pg_catalog".into()],
+            denied_functions: vec!["pg_read_file".into(), "xp_cmdshell".into()],
+            require_where: true,
+        };
+        let rule = |db_type, sql| policy.check(db_type, sql).err().map(|violation| violation.rule);
+
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELECT * FROM orders o JOIN hr.employees e ON o.id = e.id"), None);
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELECT 1; DROP TABLE orders"), Some(PolicyRule::AllowedStatements));
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELECT * FROM HR.Salaries"), Some(PolicyRule::DeniedTables));
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELECT * FROM public.secrets"), Some(PolicyRule::DeniedTables));
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELECT * FROM salaries"), None);
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELECT * FROM pg_catalog.pg_authid"), Some(PolicyRule::DeniedSchemas));
+        assert_eq!(
+            rule(DatabaseType::PostgreSQL, "SELECT * FROM t WHERE id IN (SELECT pg_read_file('/etc/passwd'))"),
+            Some(PolicyRule::DeniedFunctions)
+        );
+        assert_eq!(rule(DatabaseType::MSSQL, "EXEC xp_cmdshell 'dir'"), Some(PolicyRule::DeniedFunctions));
+        assert_eq!(rule(DatabaseType::MySQL, "DELETE FROM orders"), Some(PolicyRule::RequireWhere));
+        assert_eq!(rule(DatabaseType::MySQL, "UPDATE orders SET paid = 1 WHERE id = 3"), None);
+        assert_eq!(rule(DatabaseType::PostgreSQL, "SELEC 1"), Some(PolicyRule::Parse));
+
+        let violation = policy.check(DatabaseType::PostgreSQL, "SELECT 1; SELECT * FROM secrets").unwrap_err();
+        assert_eq!(violation.statement, 1);
+        assert_eq!(violation.target.as_deref(), Some("secrets"));
+    }
+
+    #[test]
+    fn test_routine_and_table_write_rules() {
+        let policy = SqlPolicy {
+            allowed_statements: Some(vec![StatementType::Select, StatementType::Insert, StatementType::Call]),
+            denied_tables: vec!["hr.salaries".into()],
+            denied_schemas: vec!["sys".into()],
+            denied_functions: vec!["xp_cmdshell".into()],
+            ..Default::default()
+        };
+        let rule = |result: Result<(), PolicyViolation>| result.err().map(|violation| violation.rule);
+
+        assert_eq!(rule(policy.check_routine("billing.close_month")), None);
+        assert_eq!(rule(policy.check_routine("master.dbo.XP_CMDSHELL")), Some(PolicyRule::DeniedFunctions));
+        assert_eq!(rule(policy.check_table_write("orders", false)), None);
+        assert_eq!(rule(policy.check_table_write("orders", true)), Some(PolicyRule::AllowedStatements));
+        assert_eq!(rule(policy.check_table_write("HR.salaries", false)), Some(PolicyRule::DeniedTables));
+        assert_eq!(rule(policy.check_table_write("sys.users", false)), Some(PolicyRule::DeniedSchemas));
+
+        let read_only = SqlPolicy { allowed_statements: Some(vec![StatementType::Select]), ..Default::default() };
+        assert_eq!(rule(read_only.check_routine("refresh_totals")), Some(PolicyRule::AllowedStatements));
+        assert_eq!(rule(read_only.check_table_write("orders", false)), Some(PolicyRule::AllowedStatements));
+    }
+}
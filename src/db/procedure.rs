@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use crate::db::source::is_valid_identifier;
+use crate::db::types::{DatabaseType, ResultSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ParamMode {
+    In,
+    Out,
+    InOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    Integer,
+    Float,
+    String,
+    Boolean,
+    Cursor,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProcedureParam {
+    pub name: String,
+    #[serde(default = "default_mode")]
+    pub mode: ParamMode,
+    #[serde(rename = "type")]
+    pub param_type: ParamType,
+    // 드라이버에 전달할 실제 SQL 타입 (예: "integer", "NUMBER(10)")
+    pub sql_type: Option<String>,
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+fn default_mode() -> ParamMode {
+    ParamMode::In
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProcedureCall {
+    pub name: String,
+    #[serde(default)]
+    pub params: Vec<ProcedureParam>,
+    // 값이 있으면 함수 호출, 없으면 프로시저 호출
+    pub returns: Option<ParamType>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProcedureResult {
+    pub return_value: Option<serde_json::Value>,
+    pub out_params: serde_json::Map<String, serde_json::Value>,
+    pub result_sets: Vec<ResultSet>,
+}
+
+impl ProcedureParam {
+    pub fn is_input(&self) -> bool {
+        self.mode != ParamMode::Out
+    }
+
+    pub fn is_output(&self) -> bool {
+        self.mode != ParamMode::In
+    }
+
+    pub fn sql_type_for(&self, db_type: DatabaseType) -> String {
+        match &self.sql_type {
+            Some(sql_type) => sql_type.clone(),
+            None => default_sql_type(self.param_type, db_type).to_string(),
+        }
+    }
+}
+
+pub fn default_sql_type(param_type: ParamType, db_type: DatabaseType) -> &'static str {
+    match (db_type, param_type) {
+        (DatabaseType::PostgreSQL, ParamType::Integer) => "bigint",
+        (DatabaseType::PostgreSQL, ParamType::Float) => "double precision",
+        (DatabaseType::PostgreSQL, ParamType::String) => "text",
+        (DatabaseType::PostgreSQL, ParamType::Boolean) => "boolean",
+        (DatabaseType::PostgreSQL, ParamType::Cursor) => "refcursor",
+        (DatabaseType::MSSQL, ParamType::Integer) => "BIGINT",
+        (DatabaseType::MSSQL, ParamType::Float) => "FLOAT",
+        (DatabaseType::MSSQL, ParamType::Boolean) => "BIT",
+        (DatabaseType::MSSQL, _) => "NVARCHAR(MAX)",
+        (_, ParamType::Integer) => "BIGINT",
+        (_, ParamType::Float) => "DOUBLE",
+        (_, ParamType::Boolean) => "BOOLEAN",
+        (_, _) => "VARCHAR(4000)",
+    }
+}
+
+impl ProcedureCall {
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if !is_valid_identifier(&self.name) {
+            return Err(format!("Invalid routine name: {}", self.name).into());
+        }
+        for param in &self.params {
+            if let Some(sql_type) = &param.sql_type {
+                if !is_valid_sql_type(sql_type) {
+                    return Err(format!("Invalid SQL type for parameter {}: {}", param.name, sql_type).into());
+                }
+            }
+            if param.param_type == ParamType::Cursor && param.mode == ParamMode::In {
+                return Err(format!("Cursor parameter {} must be OUT or INOUT", param.name).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn is_valid_sql_type(sql_type: &str) -> bool {
+    !sql_type.is_empty()
+        && sql_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '(' | ')' | ',' | '_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_param_must_be_output() {
+        let call: ProcedureCall = serde_json::from_value(serde_json::json!({
+            "name": "open_books",
+            "params": [{"name": "cur", "type": "cursor"}]
+        }))
+        .unwrap();
+        assert!(call.validate().is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+use crate::db::connection::{Connection, DatabaseConnection};
+use crate::db::source::SourceSpec;
+use crate::db::schema::int_field;
+use crate::db::types::{CompactResultSet, DatabaseType};
+use crate::formats::typed::{column_kind, ColumnKind};
+
+const MAX_TOP_N: usize = 100;
+const MAX_BUCKETS: usize = 100;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileRequest {
+    pub connection_id: String,
+    #[serde(flatten)]
+    pub source: SourceSpec,
+    // 비어 있으면 모든 컬럼
+    #[serde(default)]
+    pub columns: Vec<String>,
+    #[serde(default = "default_top_n")]
+    pub top_n: usize,
+    #[serde(default = "default_buckets")]
+    pub histogram_buckets: usize,
+    // APPROX_COUNT_DISTINCT 를 지원하는 드라이버 (MSSQL, Oracle) 에서만 쓴다
+    #[serde(default)]
+    pub approximate: bool,
+}
+
+fn default_top_n() -> usize {
+    10
+}
+
+fn default_buckets() -> usize {
+    10
+}
+
+impl ProfileRequest {
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.source.validate()?;
+        if self.top_n > MAX_TOP_N {
+            return Err(format!("top_n must be at most {}", MAX_TOP_N).into());
+        }
+        if self.histogram_buckets == 0 || self.histogram_buckets > MAX_BUCKETS {
+            return Err(format!("histogram_buckets must be between 1 and {}", MAX_BUCKETS).into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ValueFrequency {
+    pub value: serde_json::Value,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub data_type: String,
+    pub null_count: u64,
+    pub null_ratio: f64,
+    pub distinct_count: Option<u64>,
+    pub distinct_approximate: bool,
+    pub min: serde_json::Value,
+    pub max: serde_json::Value,
+    // 문자열 컬럼만
+    pub avg_length: Option<f64>,
+    pub top_values: Vec<ValueFrequency>,
+    // 숫자 컬럼만
+    pub histogram: Option<Vec<HistogramBucket>>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TableProfile {
+    pub row_count: u64,
+    pub columns: Vec<ColumnProfile>,
+}
+
+// 집계에 쓸 수 있는 정도에 따라 컬럼을 나눈다
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfiledColumn {
+    pub name: String,
+    pub data_type: String,
+    pub kind: ColumnKind,
+    // DISTINCT, GROUP BY 가 되는지 (LOB, XML 등은 안 된다)
+    pub comparable: bool,
+}
+
+impl ProfiledColumn {
+    pub fn new(db_type: DatabaseType, name: &str, data_type: &str) -> Self {
+        let kind = column_kind(db_type, data_type);
+        let upper = data_type.to_ascii_uppercase();
+        let lob = match db_type {
+            DatabaseType::PostgreSQL => matches!(upper.as_str(), "JSON" | "XML"),
+            DatabaseType::MSSQL => matches!(upper.as_str(), "TEXT" | "NTEXT" | "IMAGE" | "XML" | "UDT"),
+            DatabaseType::Oracle => {
+                matches!(upper.as_str(), "CLOB" | "NCLOB" | "BLOB" | "BFILE" | "LONG" | "LONG RAW" | "XMLTYPE")
+            }
+            _ => false,
+        };
+        Self {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            kind,
+            comparable: !lob && kind != ColumnKind::Binary,
+        }
+    }
+
+    fn numeric(&self) -> bool {
+        matches!(self.kind, ColumnKind::Int64 | ColumnKind::UInt64 | ColumnKind::Float64 | ColumnKind::Decimal)
+    }
+
+    // MIN/MAX 는 BOOLEAN, BIT 에 정의되어 있지 않은 드라이버가 있다
+    fn orderable(&self) -> bool {
+        self.comparable && self.kind != ColumnKind::Boolean
+    }
+}
+
+// 결과 컬럼 이름을 그대로 참조하도록 항상 감싼다
+fn quoted(name: &str, db_type: DatabaseType) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("`{}`", name.replace('`', "``")),
+        DatabaseType::MSSQL => format!("[{}]", name.replace(']', "]]")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+fn limited(db_type: DatabaseType, select: &str, rest: &str, limit: usize) -> String {
+    match db_type {
+        DatabaseType::MSSQL => format!("SELECT TOP {} {} {}", limit, select, rest),
+        DatabaseType::Oracle => format!("SELECT {} {} FETCH FIRST {} ROWS ONLY", select, rest, limit),
+        _ => format!("SELECT {} {} LIMIT {}", select, rest, limit),
+    }
+}
+
+// 컬럼 이름과 타입을 알기 위해 한 행만 읽는다
+pub fn sample_sql(db_type: DatabaseType, source: &str) -> String {
+    limited(db_type, "*", &format!("FROM ({}) sub", source), 1)
+}
+
+fn length_expr(db_type: DatabaseType, column: &str) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!("LENGTH({}::text)", column),
+        DatabaseType::MySQL => format!("CHAR_LENGTH({})", column),
+        DatabaseType::MSSQL => format!("CAST(LEN({}) AS FLOAT)", column),
+        _ => format!("LENGTH({})", column),
+    }
+}
+
+// 모든 컬럼의 집계를 한 번의 스캔으로 구한다. 별칭은 c{i}_{항목}
+pub fn stats_sql(db_type: DatabaseType, source: &str, columns: &[ProfiledColumn], approximate: bool) -> String {
+    let mut select = vec![format!("COUNT(*) AS {}", quoted("row_count", db_type))];
+    for (i, column) in columns.iter().enumerate() {
+        let name = quoted(&column.name, db_type);
+        let alias = |suffix: &str| quoted(&format!("c{}_{}", i, suffix), db_type);
+        select.push(format!("COUNT({}) AS {}", name, alias("count")));
+        if column.comparable {
+            let distinct = match db_type {
+                DatabaseType::MSSQL | DatabaseType::Oracle if approximate => format!("APPROX_COUNT_DISTINCT({})", name),
+                _ => format!("COUNT(DISTINCT {})", name),
+            };
+            select.push(format!("{} AS {}", distinct, alias("distinct")));
+        }
+        if column.orderable() {
+            select.push(format!("MIN({}) AS {}", name, alias("min")));
+            select.push(format!("MAX({}) AS {}", name, alias("max")));
+        }
+        if column.kind == ColumnKind::Utf8 {
+            select.push(format!("AVG({}) AS {}", length_expr(db_type, &name), alias("avg_length")));
+        }
+    }
+    format!("SELECT {} FROM ({}) sub", select.join(", "), source)
+}
+
+pub fn top_values_sql(db_type: DatabaseType, source: &str, column: &str, limit: usize) -> String {
+    let name = quoted(column, db_type);
+    limited(
+        db_type,
+        &format!("{} AS {}, COUNT(*) AS {}", name, quoted("value", db_type), quoted("frequency", db_type)),
+        &format!(
+            "FROM ({}) sub WHERE {} IS NOT NULL GROUP BY {} ORDER BY COUNT(*) DESC",
+            source, name, name
+        ),
+        limit,
+    )
+}
+
+// 리터럴에 항상 소수점을 붙여 정수 나눗셈이 되지 않게 한다
+fn number_literal(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+// 마지막 구간은 최댓값을 포함한다
+pub fn histogram_sql(db_type: DatabaseType, source: &str, column: &str, min: f64, width: f64, buckets: usize) -> String {
+    let name = quoted(column, db_type);
+    let bucket = quoted("bucket", db_type);
+    format!(
+        "SELECT {bucket}, COUNT(*) AS {frequency} FROM (SELECT CASE WHEN {name} >= {max} THEN {last} \
+         ELSE FLOOR(({name} - {min}) / {width}) END AS {bucket} FROM ({source}) sub WHERE {name} IS NOT NULL) b \
+         GROUP BY {bucket} ORDER BY {bucket}",
+        frequency = quoted("frequency", db_type),
+        max = number_literal(min + width * buckets as f64),
+        last = buckets - 1,
+        min = number_literal(min),
+        width = number_literal(width),
+    )
+}
+
+fn as_f64(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub fn histogram_from_rows(rows: &[serde_json::Value], min: f64, width: f64, buckets: usize) -> Vec<HistogramBucket> {
+    let mut histogram = (0..buckets)
+        .map(|i| HistogramBucket {
+            lower: min + width * i as f64,
+            upper: min + width * (i + 1) as f64,
+            count: 0,
+        })
+        .collect::<Vec<_>>();
+    for row in rows {
+        let bucket = as_f64(&row["bucket"]).map(|b| (b.max(0.0) as usize).min(buckets - 1));
+        if let (Some(bucket), Some(count)) = (bucket, int_field(row, "frequency")) {
+            histogram[bucket].count += count.max(0) as u64;
+        }
+    }
+    histogram
+}
+
+fn count(row: &serde_json::Value, key: &str) -> u64 {
+    int_field(row, key).unwrap_or(0).max(0) as u64
+}
+
+pub async fn profile(
+    connection: &DatabaseConnection,
+    request: &ProfileRequest,
+) -> Result<TableProfile, Box<dyn std::error::Error + Send + Sync>> {
+    let db_type = connection.db_type();
+    let source = request.source.query();
+
+    let sample: CompactResultSet = connection
+        .execute_result_sets(&sample_sql(db_type, &source))
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+        .into();
+    let columns = sample
+        .columns
+        .iter()
+        .filter(|column| {
+            request.columns.is_empty() || request.columns.iter().any(|c| c.eq_ignore_ascii_case(&column.name))
+        })
+        .map(|column| ProfiledColumn::new(db_type, &column.name, &column.data_type))
+        .collect::<Vec<_>>();
+    if let Some(missing) = request
+        .columns
+        .iter()
+        .find(|c| !columns.iter().any(|column| column.name.eq_ignore_ascii_case(c)))
+    {
+        // 빈 결과면 컬럼 정보가 없으므로 지정한 컬럼을 찾지 못해도 오류로 보지 않는다
+        if !sample.rows.is_empty() {
+            return Err(format!("Column not found: {}", missing).into());
+        }
+    }
+
+    let stats = connection
+        .execute_query(&stats_sql(db_type, &source, &columns, request.approximate))
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let row_count = count(&stats, "row_count");
+
+    let mut profiles = Vec::new();
+    for (i, column) in columns.iter().enumerate() {
+        let key = |suffix: &str| format!("c{}_{}", i, suffix);
+        let non_null = count(&stats, &key("count"));
+        let null_count = row_count.saturating_sub(non_null);
+        let mut profile = ColumnProfile {
+            name: column.name.clone(),
+            data_type: column.data_type.clone(),
+            null_count,
+            null_ratio: if row_count == 0 { 0.0 } else { null_count as f64 / row_count as f64 },
+            distinct_count: int_field(&stats, &key("distinct")).map(|v| v.max(0) as u64),
+            distinct_approximate: column.comparable
+                && request.approximate
+                && matches!(db_type, DatabaseType::MSSQL | DatabaseType::Oracle),
+            min: stats[key("min")].clone(),
+            max: stats[key("max")].clone(),
+            avg_length: as_f64(&stats[key("avg_length")]),
+            top_values: Vec::new(),
+            histogram: None,
+        };
+
+        if column.comparable && request.top_n > 0 && non_null > 0 {
+            let rows = connection
+                .execute_query(&top_values_sql(db_type, &source, &column.name, request.top_n))
+                .await?;
+            profile.top_values = rows
+                .iter()
+                .map(|row| ValueFrequency { value: row["value"].clone(), count: count(row, "frequency") })
+                .collect();
+        }
+
+        if column.numeric() {
+            if let (Some(min), Some(max)) = (as_f64(&profile.min), as_f64(&profile.max)) {
+                let buckets = if max > min { request.histogram_buckets } else { 1 };
+                // 모든 값이 같으면 폭 1 인 구간 하나
+                let width = if max > min { (max - min) / buckets as f64 } else { 1.0 };
+                let rows = connection
+                    .execute_query(&histogram_sql(db_type, &source, &column.name, min, width, buckets))
+                    .await?;
+                profile.histogram = Some(histogram_from_rows(&rows, min, width, buckets));
+            }
+        }
+        profiles.push(profile);
+    }
+
+    Ok(TableProfile { row_count, columns: profiles })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stats_sql_skips_lobs() {
+        let columns = vec![
+            ProfiledColumn::new(DatabaseType::Oracle, "NAME", "VARCHAR2(100)"),
+            ProfiledColumn::new(DatabaseType::Oracle, "NOTES", "CLOB"),
+        ];
+        assert_eq!(
+            stats_sql(DatabaseType::Oracle, "SELECT * FROM t", &columns, true),
+            "SELECT COUNT(*) AS \"row_count\", \
+             COUNT(\"NAME\") AS \"c0_count\", APPROX_COUNT_DISTINCT(\"NAME\") AS \"c0_distinct\", \
+             MIN(\"NAME\") AS \"c0_min\", MAX(\"NAME\") AS \"c0_max\", AVG(LENGTH(\"NAME\")) AS \"c0_avg_length\", \
+             COUNT(\"NOTES\") AS \"c1_count\", AVG(LENGTH(\"NOTES\")) AS \"c1_avg_length\" \
+             FROM (SELECT * FROM t) sub"
+        );
+    }
+
+    #[test]
+    fn test_top_values_and_histogram() {
+        assert_eq!(
+            top_values_sql(DatabaseType::MSSQL, "SELECT * FROM t", "city", 5),
+            "SELECT TOP 5 [city] AS [value], COUNT(*) AS [frequency] \
+             FROM (SELECT * FROM t) sub WHERE [city] IS NOT NULL GROUP BY [city] ORDER BY COUNT(*) DESC"
+        );
+
+        let rows = vec![json!({"bucket": 0, "frequency": 3}), json!({"bucket": "3", "frequency": "1"})];
+        let histogram = histogram_from_rows(&rows, 0.0, 2.5, 4);
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram[0].count, 3);
+        assert_eq!(histogram[3].count, 1);
+        assert_eq!(histogram[3].upper, 10.0);
+    }
+
+    #[test]
+    fn test_query_cannot_break_out_of_the_wrapper() {
+        let request = |query: &str| {
+            serde_json::from_value::<ProfileRequest>(json!({ "connection_id": "c1", "query": query })).unwrap()
+        };
+        assert!(request("SELECT city FROM t").validate().is_ok());
+        assert!(request("SELECT 1 a) x; DROP TABLE t; SELECT * FROM (SELECT 1").validate().is_err());
+        assert!(request("UPDATE t SET city = NULL").validate().is_err());
+    }
+}
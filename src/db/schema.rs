@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use crate::db::connection::{Connection, DatabaseConnection};
+use crate::db::dump::string_literal;
+use crate::db::types::DatabaseType;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SchemaInfo {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableKind {
+    Table,
+    View,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TableInfo {
+    pub schema: String,
+    pub name: String,
+    pub kind: TableKind,
+    pub comment: Option<String>,
+}
+
+// 드라이버별 카탈로그 정보를 하나의 형태로 정규화한 컬럼
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub ordinal: i64,
+    pub data_type: String,
+    // 문자/바이너리 타입의 최대 길이. -1 은 MAX
+    pub max_length: Option<i64>,
+    pub numeric_precision: Option<i64>,
+    pub numeric_scale: Option<i64>,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub comment: Option<String>,
+}
+
+// schema 를 지정하지 않으면 접속 사용자의 기본 스키마
+pub fn schema_filter(db_type: DatabaseType, column: &str, schema: Option<&str>) -> String {
+    match (schema, db_type) {
+        // 따옴표 없이 만든 Oracle 이름은 대문자로 저장된다
+        (Some(schema), DatabaseType::Oracle) => {
+            let literal = string_literal(schema, db_type);
+            format!("{} IN ({}, UPPER({}))", column, literal, literal)
+        }
+        (Some(schema), _) => format!("{} = {}", column, string_literal(schema, db_type)),
+        (None, DatabaseType::PostgreSQL) => format!("{} = current_schema()", column),
+        (None, DatabaseType::MySQL) => format!("{} = DATABASE()", column),
+        (None, DatabaseType::MSSQL) => format!("{} = SCHEMA_NAME()", column),
+        (None, _) => format!("{} = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')", column),
+    }
+}
+
+// table 이 없으면 스키마 전체
+pub fn table_filter(db_type: DatabaseType, column: &str, table: Option<&str>) -> String {
+    match table {
+        Some(table) => schema_filter(db_type, column, Some(table)),
+        None => "1 = 1".to_string(),
+    }
+}
+
+pub fn schemas_sql(db_type: DatabaseType) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => "SELECT schema_name::text AS name FROM information_schema.schemata \
+            WHERE schema_name NOT LIKE 'pg\\_%' AND schema_name <> 'information_schema' ORDER BY 1"
+            .to_string(),
+        DatabaseType::MySQL => "SELECT schema_name AS name FROM information_schema.schemata \
+            WHERE schema_name NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys') ORDER BY 1"
+            .to_string(),
+        // 16384 이상은 고정 데이터베이스 역할의 스키마
+        DatabaseType::MSSQL => "SELECT name FROM sys.schemas \
+            WHERE schema_id < 16384 AND name NOT IN ('sys', 'INFORMATION_SCHEMA', 'guest') ORDER BY name"
+            .to_string(),
+        _ => "SELECT username AS \"name\" FROM all_users WHERE oracle_maintained = 'N' ORDER BY username"
+            .to_string(),
+    }
+}
+
+pub fn tables_sql(db_type: DatabaseType, schema: Option<&str>, kind: TableKind) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!(
+            "SELECT t.table_schema::text AS schema_name, t.table_name::text AS name, \
+             obj_description(format('%I.%I', t.table_schema, t.table_name)::regclass, 'pg_class') AS comment \
+             FROM information_schema.tables t WHERE {} AND t.table_type {} ORDER BY 2",
+            schema_filter(db_type, "t.table_schema", schema),
+            if kind == TableKind::View { "= 'VIEW'" } else { "IN ('BASE TABLE', 'FOREIGN')" }
+        ),
+        DatabaseType::MySQL => format!(
+            "SELECT table_schema AS schema_name, table_name AS name, NULLIF(table_comment, '') AS comment \
+             FROM information_schema.tables WHERE {} AND table_type {} ORDER BY 2",
+            schema_filter(db_type, "table_schema", schema),
+            if kind == TableKind::View { "= 'VIEW'" } else { "= 'BASE TABLE'" }
+        ),
+        DatabaseType::MSSQL => format!(
+            "SELECT s.name AS schema_name, o.name AS name, CAST(ep.value AS NVARCHAR(4000)) AS comment \
+             FROM sys.objects o JOIN sys.schemas s ON s.schema_id = o.schema_id \
+             LEFT JOIN sys.extended_properties ep ON ep.class = 1 AND ep.major_id = o.object_id \
+             AND ep.minor_id = 0 AND ep.name = 'MS_Description' \
+             WHERE {} AND o.type = '{}' ORDER BY o.name",
+            schema_filter(db_type, "s.name", schema),
+            if kind == TableKind::View { "V" } else { "U" }
+        ),
+        _ => format!(
+            "SELECT o.owner AS \"schema_name\", o.object_name AS \"name\", c.comments AS \"comment\" \
+             FROM all_objects o LEFT JOIN all_tab_comments c ON c.owner = o.owner AND c.table_name = o.object_name \
+             WHERE {} AND o.object_type = '{}' ORDER BY o.object_name",
+            schema_filter(db_type, "o.owner", schema),
+            if kind == TableKind::View { "VIEW" } else { "TABLE" }
+        ),
+    }
+}
+
+pub fn columns_sql(db_type: DatabaseType, schema: Option<&str>, table: Option<&str>) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!(
+            "SELECT c.table_name::text AS table_name, c.column_name::text AS name, c.ordinal_position::int AS ordinal, \
+             CASE WHEN c.data_type IN ('USER-DEFINED', 'ARRAY') THEN c.udt_name ELSE c.data_type END::text AS data_type, \
+             c.character_maximum_length::int AS max_length, c.numeric_precision::int AS numeric_precision, \
+             c.numeric_scale::int AS numeric_scale, c.is_nullable::text AS nullable, \
+             c.column_default::text AS default_value, \
+             col_description(a.attrelid, a.attnum) AS comment \
+             FROM information_schema.columns c \
+             JOIN pg_attribute a ON a.attrelid = format('%I.%I', c.table_schema, c.table_name)::regclass \
+             AND a.attname = c.column_name \
+             WHERE {} AND {} ORDER BY c.table_name, c.ordinal_position",
+            schema_filter(db_type, "c.table_schema", schema),
+            table_filter(db_type, "c.table_name", table)
+        ),
+        DatabaseType::MySQL => format!(
+            "SELECT table_name AS table_name, column_name AS name, ordinal_position AS ordinal, data_type AS data_type, \
+             character_maximum_length AS max_length, numeric_precision AS numeric_precision, \
+             numeric_scale AS numeric_scale, is_nullable AS nullable, column_default AS default_value, \
+             NULLIF(column_comment, '') AS comment \
+             FROM information_schema.columns WHERE {} AND {} ORDER BY table_name, ordinal_position",
+            schema_filter(db_type, "table_schema", schema),
+            table_filter(db_type, "table_name", table)
+        ),
+        // sys.columns.max_length 는 바이트 단위라 nchar/nvarchar 는 2로 나눈다
+        DatabaseType::MSSQL => format!(
+            "SELECT o.name AS table_name, c.name AS name, c.column_id AS ordinal, t.name AS data_type, \
+             CAST(CASE WHEN t.name IN ('char', 'varchar', 'binary', 'varbinary') THEN c.max_length \
+             WHEN t.name IN ('nchar', 'nvarchar') THEN IIF(c.max_length = -1, -1, c.max_length / 2) END AS INT) AS max_length, \
+             CAST(c.precision AS INT) AS numeric_precision, CAST(c.scale AS INT) AS numeric_scale, \
+             c.is_nullable AS nullable, dc.definition AS default_value, CAST(ep.value AS NVARCHAR(4000)) AS comment \
+             FROM sys.columns c \
+             JOIN sys.objects o ON o.object_id = c.object_id \
+             JOIN sys.schemas s ON s.schema_id = o.schema_id \
+             JOIN sys.types t ON t.user_type_id = c.user_type_id \
+             LEFT JOIN sys.default_constraints dc ON dc.object_id = c.default_object_id \
+             LEFT JOIN sys.extended_properties ep ON ep.class = 1 AND ep.major_id = c.object_id \
+             AND ep.minor_id = c.column_id AND ep.name = 'MS_Description' \
+             WHERE o.type IN ('U', 'V') AND {} AND {} ORDER BY o.name, c.column_id",
+            schema_filter(db_type, "s.name", schema),
+            table_filter(db_type, "o.name", table)
+        ),
+        _ => format!(
+            "SELECT c.table_name AS \"table_name\", c.column_name AS \"name\", c.column_id AS \"ordinal\", c.data_type AS \"data_type\", \
+             CASE WHEN c.data_type IN ('CHAR', 'NCHAR', 'VARCHAR2', 'NVARCHAR2') THEN c.char_length \
+             WHEN c.data_type = 'RAW' THEN c.data_length END AS \"max_length\", \
+             c.data_precision AS \"numeric_precision\", c.data_scale AS \"numeric_scale\", \
+             c.nullable AS \"nullable\", c.data_default AS \"default_value\", cc.comments AS \"comment\" \
+             FROM all_tab_columns c LEFT JOIN all_col_comments cc ON cc.owner = c.owner \
+             AND cc.table_name = c.table_name AND cc.column_name = c.column_name \
+             WHERE {} AND {} ORDER BY c.table_name, c.column_id",
+            schema_filter(db_type, "c.owner", schema),
+            table_filter(db_type, "c.table_name", table)
+        ),
+    }
+}
+
+pub fn text_field(row: &serde_json::Value, key: &str) -> Option<String> {
+    match &row[key] {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+pub fn int_field(row: &serde_json::Value, key: &str) -> Option<i64> {
+    match &row[key] {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+// information_schema 는 YES/NO, Oracle 은 Y/N, sys.columns 는 bit
+pub fn bool_field(row: &serde_json::Value, key: &str) -> bool {
+    match &row[key] {
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::Number(n) => n.as_i64() == Some(1),
+        serde_json::Value::String(s) => matches!(s.as_str(), "YES" | "Y" | "1" | "true"),
+        _ => false,
+    }
+}
+
+pub fn column_from_row(row: &serde_json::Value) -> ColumnInfo {
+    ColumnInfo {
+        name: text_field(row, "name").unwrap_or_default(),
+        ordinal: int_field(row, "ordinal").unwrap_or_default(),
+        data_type: text_field(row, "data_type").unwrap_or_default(),
+        max_length: int_field(row, "max_length"),
+        numeric_precision: int_field(row, "numeric_precision"),
+        numeric_scale: int_field(row, "numeric_scale"),
+        nullable: bool_field(row, "nullable"),
+        // Oracle 의 data_default 는 끝에 공백이나 줄바꿈이 붙어 온다
+        default: text_field(row, "default_value").map(|v| v.trim_end().to_string()),
+        comment: text_field(row, "comment"),
+    }
+}
+
+pub async fn list_schemas(
+    connection: &DatabaseConnection,
+) -> Result<Vec<SchemaInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection.execute_query(&schemas_sql(connection.db_type())).await?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| text_field(row, "name"))
+        .map(|name| SchemaInfo { name })
+        .collect())
+}
+
+pub async fn list_tables(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+    kind: TableKind,
+) -> Result<Vec<TableInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection
+        .execute_query(&tables_sql(connection.db_type(), schema, kind))
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| TableInfo {
+            schema: text_field(row, "schema_name").unwrap_or_default(),
+            name: text_field(row, "name").unwrap_or_default(),
+            kind,
+            comment: text_field(row, "comment"),
+        })
+        .collect())
+}
+
+pub async fn list_columns(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+    table: &str,
+) -> Result<Vec<ColumnInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection
+        .execute_query(&columns_sql(connection.db_type(), schema, Some(table)))
+        .await?;
+    Ok(rows.iter().map(column_from_row).collect())
+}
+
+// 스키마의 모든 테이블 컬럼을 한 번에 읽는다. (테이블 이름, 컬럼) 순서쌍
+pub async fn list_schema_columns(
+    connection: &DatabaseConnection,
+    schema: Option<&str>,
+) -> Result<Vec<(String, ColumnInfo)>, Box<dyn std::error::Error + Send + Sync>> {
+    let rows = connection
+        .execute_query(&columns_sql(connection.db_type(), schema, None))
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| (text_field(row, "table_name").unwrap_or_default(), column_from_row(row)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filters_escape_names() {
+        assert_eq!(
+            schema_filter(DatabaseType::MySQL, "table_schema", Some("a'b\\")),
+            "table_schema = 'a''b\\\\'"
+        );
+        assert_eq!(
+            schema_filter(DatabaseType::Oracle, "c.owner", Some("hr")),
+            "c.owner IN ('hr', UPPER('hr'))"
+        );
+        assert_eq!(schema_filter(DatabaseType::PostgreSQL, "s", None), "s = current_schema()");
+    }
+
+    #[test]
+    fn test_column_rows_normalize() {
+        let oracle = column_from_row(&json!({
+            "name": "PRICE", "ordinal": "2", "data_type": "NUMBER", "max_length": null,
+            "numeric_precision": 10, "numeric_scale": 2, "nullable": "N",
+            "default_value": "0 \n", "comment": null
+        }));
+        assert_eq!(oracle.ordinal, 2);
+        assert!(!oracle.nullable);
+        assert_eq!(oracle.default.as_deref(), Some("0"));
+
+        let mssql = column_from_row(&json!({
+            "name": "title", "ordinal": 1, "data_type": "nvarchar", "max_length": -1, "nullable": true
+        }));
+        assert_eq!(mssql.max_length, Some(-1));
+        assert!(mssql.nullable);
+        assert_eq!(mssql.numeric_precision, None);
+    }
+}
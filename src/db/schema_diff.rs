@@ -0,0 +1,514 @@
+use serde::{Deserialize, Serialize};
+use crate::db::constraints::{ConstraintInfo, ConstraintKind, ForeignKeyInfo, IndexInfo};
+use crate::db::ddl::{
+    column_definition, column_kind_of, column_type, constraint_definition, default_clause, foreign_key_statement,
+    index_statement, is_constraint_index, native_type, quote_ident, render_ddl, TableDefinition,
+};
+use crate::db::schema::ColumnInfo;
+use crate::db::types::DatabaseType;
+use crate::formats::typed::ColumnKind;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaDiffRequest {
+    pub source_connection_id: String,
+    pub target_connection_id: String,
+    // 없으면 각 연결의 기본 스키마
+    pub source_schema: Option<String>,
+    pub target_schema: Option<String>,
+    // false 면 DROP TABLE / DROP COLUMN 을 주석으로 남긴다
+    #[serde(default)]
+    pub include_drops: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ColumnChange {
+    pub name: String,
+    // 달라진 항목: "type", "nullable", "default"
+    pub fields: Vec<String>,
+    pub source: ColumnInfo,
+    pub target: ColumnInfo,
+}
+
+// 대상 테이블을 원본과 같게 만들려면 무엇을 더하고 빼야 하는지
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TableDiff {
+    pub name: String,
+    pub added_columns: Vec<ColumnInfo>,
+    pub removed_columns: Vec<ColumnInfo>,
+    pub changed_columns: Vec<ColumnChange>,
+    pub added_constraints: Vec<ConstraintInfo>,
+    pub removed_constraints: Vec<ConstraintInfo>,
+    pub added_indexes: Vec<IndexInfo>,
+    pub removed_indexes: Vec<IndexInfo>,
+    pub added_foreign_keys: Vec<ForeignKeyInfo>,
+    pub removed_foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+            && self.added_constraints.is_empty()
+            && self.removed_constraints.is_empty()
+            && self.added_indexes.is_empty()
+            && self.removed_indexes.is_empty()
+            && self.added_foreign_keys.is_empty()
+            && self.removed_foreign_keys.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct SchemaDiff {
+    // 원본에만 있는 테이블
+    pub added_tables: Vec<String>,
+    // 대상에만 있는 테이블
+    pub removed_tables: Vec<String>,
+    pub changed_tables: Vec<TableDiff>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty() && self.removed_tables.is_empty() && self.changed_tables.is_empty()
+    }
+}
+
+// 방언이 다르면 타입 이름 대신 분류와 길이, 자릿수를 비교한다
+fn same_type(source: &ColumnInfo, source_db: DatabaseType, target: &ColumnInfo, target_db: DatabaseType) -> bool {
+    if source_db == target_db {
+        return native_type(source, source_db).eq_ignore_ascii_case(&native_type(target, target_db));
+    }
+    let kind = column_kind_of(source, source_db);
+    if kind != column_kind_of(target, target_db) {
+        return false;
+    }
+    match kind {
+        ColumnKind::Utf8 | ColumnKind::Binary => source.max_length == target.max_length,
+        ColumnKind::Decimal => {
+            source.numeric_precision == target.numeric_precision && source.numeric_scale == target.numeric_scale
+        }
+        _ => true,
+    }
+}
+
+fn column_change(
+    source: &ColumnInfo,
+    source_db: DatabaseType,
+    target: &ColumnInfo,
+    target_db: DatabaseType,
+) -> Option<ColumnChange> {
+    let mut fields = Vec::new();
+    if !same_type(source, source_db, target, target_db) {
+        fields.push("type".to_string());
+    }
+    if source.nullable != target.nullable {
+        fields.push("nullable".to_string());
+    }
+    // 방언이 다르면 옮길 수 있는 형태로 바꿔서 비교한다
+    if default_clause(source, source_db, target_db) != default_clause(target, target_db, source_db) {
+        fields.push("default".to_string());
+    }
+    if fields.is_empty() {
+        return None;
+    }
+    Some(ColumnChange {
+        name: target.name.clone(),
+        fields,
+        source: source.clone(),
+        target: target.clone(),
+    })
+}
+
+fn lower_list(items: &[String]) -> String {
+    items.iter().map(|item| item.to_lowercase()).collect::<Vec<_>>().join(",")
+}
+
+// 이름은 환경마다 자동으로 붙는 경우가 많아 구성으로 비교한다
+fn constraint_key(constraint: &ConstraintInfo) -> String {
+    match constraint.kind {
+        ConstraintKind::PrimaryKey => format!("pk:{}", lower_list(&constraint.columns)),
+        ConstraintKind::Unique => format!("uq:{}", lower_list(&constraint.columns)),
+        ConstraintKind::Check => format!(
+            "ck:{}",
+            constraint
+                .definition
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<String>()
+                .to_lowercase()
+        ),
+    }
+}
+
+fn index_key(index: &IndexInfo) -> String {
+    format!("{}:{}", index.unique, lower_list(&index.columns))
+}
+
+fn foreign_key_key(fk: &ForeignKeyInfo) -> String {
+    format!(
+        "{}->{}({})",
+        lower_list(&fk.columns),
+        fk.referenced_table.to_lowercase(),
+        lower_list(&fk.referenced_columns)
+    )
+}
+
+// 키가 같은 항목끼리 짝을 짓고 남은 것을 (추가, 삭제) 로 돌려준다
+fn unmatched<T: Clone>(source: &[T], target: &[T], key: impl Fn(&T) -> String) -> (Vec<T>, Vec<T>) {
+    let source_keys = source.iter().map(&key).collect::<Vec<_>>();
+    let target_keys = target.iter().map(&key).collect::<Vec<_>>();
+    let added = source
+        .iter()
+        .zip(&source_keys)
+        .filter(|(_, k)| !target_keys.contains(k))
+        .map(|(item, _)| item.clone())
+        .collect();
+    let removed = target
+        .iter()
+        .zip(&target_keys)
+        .filter(|(_, k)| !source_keys.contains(k))
+        .map(|(item, _)| item.clone())
+        .collect();
+    (added, removed)
+}
+
+fn compare_table(
+    source: &TableDefinition,
+    source_db: DatabaseType,
+    target: &TableDefinition,
+    target_db: DatabaseType,
+) -> TableDiff {
+    let mut diff = TableDiff { name: target.name.clone(), ..Default::default() };
+
+    for column in &source.columns {
+        match target.columns.iter().find(|c| c.name.eq_ignore_ascii_case(&column.name)) {
+            Some(existing) => diff.changed_columns.extend(column_change(column, source_db, existing, target_db)),
+            None => diff.added_columns.push(column.clone()),
+        }
+    }
+    diff.removed_columns = target
+        .columns
+        .iter()
+        .filter(|c| !source.columns.iter().any(|s| s.name.eq_ignore_ascii_case(&c.name)))
+        .cloned()
+        .collect();
+
+    // CHECK 조건식은 방언이 다르면 비교할 수 없다
+    let comparable = |constraints: &[ConstraintInfo]| {
+        constraints
+            .iter()
+            .filter(|c| source_db == target_db || c.kind != ConstraintKind::Check)
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    (diff.added_constraints, diff.removed_constraints) =
+        unmatched(&comparable(&source.constraints), &comparable(&target.constraints), constraint_key);
+
+    let plain_indexes = |definition: &TableDefinition| {
+        definition
+            .indexes
+            .iter()
+            .filter(|index| !is_constraint_index(index, &definition.constraints))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    (diff.added_indexes, diff.removed_indexes) = unmatched(&plain_indexes(source), &plain_indexes(target), index_key);
+    (diff.added_foreign_keys, diff.removed_foreign_keys) =
+        unmatched(&source.foreign_keys, &target.foreign_keys, foreign_key_key);
+
+    diff
+}
+
+pub fn compare_schemas(
+    source: &[TableDefinition],
+    source_db: DatabaseType,
+    target: &[TableDefinition],
+    target_db: DatabaseType,
+) -> SchemaDiff {
+    let find = |tables: &[TableDefinition], name: &str| {
+        tables.iter().position(|t| t.name.eq_ignore_ascii_case(name))
+    };
+    let mut diff = SchemaDiff::default();
+    for table in source {
+        match find(target, &table.name) {
+            Some(i) => {
+                let table_diff = compare_table(table, source_db, &target[i], target_db);
+                if !table_diff.is_empty() {
+                    diff.changed_tables.push(table_diff);
+                }
+            }
+            None => diff.added_tables.push(table.name.clone()),
+        }
+    }
+    diff.removed_tables = target
+        .iter()
+        .filter(|t| find(source, &t.name).is_none())
+        .map(|t| t.name.clone())
+        .collect();
+    diff
+}
+
+fn drop_constraint(table: &str, constraint: &ConstraintInfo, dialect: DatabaseType) -> String {
+    let name = quote_ident(&constraint.name, dialect);
+    match (dialect, constraint.kind) {
+        (DatabaseType::MySQL, ConstraintKind::PrimaryKey) => format!("ALTER TABLE {} DROP PRIMARY KEY;\n", table),
+        (DatabaseType::MySQL, ConstraintKind::Unique) => format!("ALTER TABLE {} DROP INDEX {};\n", table, name),
+        (DatabaseType::MySQL, ConstraintKind::Check) => format!("ALTER TABLE {} DROP CHECK {};\n", table, name),
+        _ => format!("ALTER TABLE {} DROP CONSTRAINT {};\n", table, name),
+    }
+}
+
+fn drop_foreign_key(table: &str, fk: &ForeignKeyInfo, dialect: DatabaseType) -> String {
+    let name = quote_ident(&fk.name, dialect);
+    match dialect {
+        DatabaseType::MySQL => format!("ALTER TABLE {} DROP FOREIGN KEY {};\n", table, name),
+        _ => format!("ALTER TABLE {} DROP CONSTRAINT {};\n", table, name),
+    }
+}
+
+fn drop_index(table: &str, index: &IndexInfo, dialect: DatabaseType) -> String {
+    let name = quote_ident(&index.name, dialect);
+    match dialect {
+        DatabaseType::MySQL | DatabaseType::MSSQL => format!("DROP INDEX {} ON {};\n", name, table),
+        _ => format!("DROP INDEX {};\n", name),
+    }
+}
+
+fn add_column(table: &str, definition: &str, dialect: DatabaseType) -> String {
+    match dialect {
+        DatabaseType::MSSQL => format!("ALTER TABLE {} ADD {};\n", table, definition),
+        DatabaseType::Oracle => format!("ALTER TABLE {} ADD ({});\n", table, definition),
+        _ => format!("ALTER TABLE {} ADD COLUMN {};\n", table, definition),
+    }
+}
+
+fn alter_column(table: &str, change: &ColumnChange, source_db: DatabaseType, target_db: DatabaseType) -> String {
+    let column = quote_ident(&change.name, target_db);
+    let changed = |field: &str| change.fields.iter().any(|f| f == field);
+    let new_type = column_type(&change.source, source_db, target_db);
+    let default = default_clause(&change.source, source_db, target_db);
+    let not_null = if change.source.nullable { "NULL" } else { "NOT NULL" };
+
+    match target_db {
+        DatabaseType::PostgreSQL => {
+            let mut script = String::new();
+            if changed("type") {
+                script.push_str(&format!("ALTER TABLE {} ALTER COLUMN {} TYPE {};\n", table, column, new_type));
+            }
+            if changed("nullable") {
+                let action = if change.source.nullable { "DROP NOT NULL" } else { "SET NOT NULL" };
+                script.push_str(&format!("ALTER TABLE {} ALTER COLUMN {} {};\n", table, column, action));
+            }
+            if changed("default") {
+                let action = match &default {
+                    Some(default) => format!("SET DEFAULT {}", default),
+                    None => "DROP DEFAULT".to_string(),
+                };
+                script.push_str(&format!("ALTER TABLE {} ALTER COLUMN {} {};\n", table, column, action));
+            }
+            script
+        }
+        // MODIFY 는 컬럼 정의 전체를 다시 쓴다
+        DatabaseType::MySQL => format!(
+            "ALTER TABLE {} MODIFY COLUMN {};\n",
+            table,
+            column_definition(&change.source, source_db, target_db)
+        ),
+        // MSSQL 의 기본값은 이름 있는 제약이라 ALTER COLUMN 으로 바꿀 수 없다
+        DatabaseType::MSSQL => {
+            let mut script = String::new();
+            if changed("type") || changed("nullable") {
+                script.push_str(&format!("ALTER TABLE {} ALTER COLUMN {} {} {};\n", table, column, new_type, not_null));
+            }
+            if changed("default") {
+                script.push_str(&format!("-- Default of {}.{} differs and must be changed by hand\n", table, column));
+            }
+            script
+        }
+        // 같은 NULL 여부를 다시 지정하면 ORA-01451/01442 가 나므로 바뀐 것만 쓴다
+        _ => {
+            let mut parts = vec![column];
+            if changed("type") {
+                parts.push(new_type);
+            }
+            if changed("default") {
+                parts.push(format!("DEFAULT {}", default.unwrap_or_else(|| "NULL".to_string())));
+            }
+            if changed("nullable") {
+                parts.push(not_null.to_string());
+            }
+            format!("ALTER TABLE {} MODIFY ({});\n", table, parts.join(" "))
+        }
+    }
+}
+
+// 대상 연결에서 실행할 스크립트. 외래 키는 테이블을 모두 만든 뒤에 건다
+pub fn migration_script(
+    diff: &SchemaDiff,
+    source: &[TableDefinition],
+    source_db: DatabaseType,
+    target_db: DatabaseType,
+    include_drops: bool,
+) -> String {
+    let drop_prefix = if include_drops { "" } else { "-- " };
+    let mut drops = String::new();
+    let mut creates = String::new();
+    let mut foreign_keys = String::new();
+
+    for table_diff in &diff.changed_tables {
+        let table = quote_ident(&table_diff.name, target_db);
+        for fk in &table_diff.removed_foreign_keys {
+            drops.push_str(&drop_foreign_key(&table, fk, target_db));
+        }
+        for index in &table_diff.removed_indexes {
+            drops.push_str(&drop_index(&table, index, target_db));
+        }
+        for constraint in &table_diff.removed_constraints {
+            drops.push_str(&drop_constraint(&table, constraint, target_db));
+        }
+        for column in &table_diff.removed_columns {
+            drops.push_str(&format!(
+                "{}ALTER TABLE {} DROP COLUMN {};\n",
+                drop_prefix,
+                table,
+                quote_ident(&column.name, target_db)
+            ));
+        }
+    }
+    for name in &diff.removed_tables {
+        drops.push_str(&format!("{}DROP TABLE {};\n", drop_prefix, quote_ident(name, target_db)));
+    }
+
+    for name in &diff.added_tables {
+        let Some(definition) = source.iter().find(|t| &t.name == name) else {
+            continue;
+        };
+        let mut without_keys = definition.clone();
+        without_keys.foreign_keys.clear();
+        creates.push_str(&render_ddl(&without_keys, source_db, target_db));
+        for fk in &definition.foreign_keys {
+            foreign_keys.push_str(&foreign_key_statement(&definition.name, fk, target_db));
+        }
+    }
+
+    for table_diff in &diff.changed_tables {
+        let table = quote_ident(&table_diff.name, target_db);
+        for column in &table_diff.added_columns {
+            creates.push_str(&add_column(&table, &column_definition(column, source_db, target_db), target_db));
+        }
+        for change in &table_diff.changed_columns {
+            creates.push_str(&alter_column(&table, change, source_db, target_db));
+        }
+        for constraint in &table_diff.added_constraints {
+            if let Some(definition) = constraint_definition(constraint, source_db, target_db) {
+                creates.push_str(&format!("ALTER TABLE {} ADD {};\n", table, definition));
+            }
+        }
+        let column_names = source
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(&table_diff.name))
+            .map(|t| t.columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for index in &table_diff.added_indexes {
+            match index_statement(&table_diff.name, index, &column_names, source_db, target_db) {
+                Some(statement) => creates.push_str(&statement),
+                None => creates.push_str(&format!("-- Expression index {} was not translated\n", index.name)),
+            }
+        }
+        for fk in &table_diff.added_foreign_keys {
+            foreign_keys.push_str(&foreign_key_statement(&table_diff.name, fk, target_db));
+        }
+    }
+
+    format!("{}{}{}", drops, creates, foreign_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, max_length: Option<i64>, nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.into(),
+            ordinal: 1,
+            data_type: data_type.into(),
+            max_length,
+            numeric_precision: None,
+            numeric_scale: None,
+            nullable,
+            default: None,
+            comment: None,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnInfo>) -> TableDefinition {
+        TableDefinition {
+            schema: Some("public".into()),
+            name: name.into(),
+            columns,
+            constraints: Vec::new(),
+            indexes: Vec::new(),
+            foreign_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare_and_migrate() {
+        let mut source_books = table(
+            "books",
+            vec![
+                column("id", "integer", None, false),
+                column("title", "character varying", Some(200), false),
+                column("isbn", "character varying", Some(13), true),
+            ],
+        );
+        source_books.indexes.push(IndexInfo {
+            table: "books".into(),
+            name: "books_isbn_idx".into(),
+            columns: vec!["isbn".into()],
+            unique: true,
+            primary: false,
+        });
+        let source = vec![source_books, table("authors", vec![column("id", "integer", None, false)])];
+        let target = vec![
+            table(
+                "books",
+                vec![
+                    column("id", "integer", None, false),
+                    column("title", "character varying", Some(100), true),
+                    column("legacy", "text", None, true),
+                ],
+            ),
+            table("old_log", vec![column("id", "integer", None, false)]),
+        ];
+
+        let diff = compare_schemas(&source, DatabaseType::PostgreSQL, &target, DatabaseType::PostgreSQL);
+        assert_eq!(diff.added_tables, vec!["authors"]);
+        assert_eq!(diff.removed_tables, vec!["old_log"]);
+        let books = &diff.changed_tables[0];
+        assert_eq!(books.added_columns[0].name, "isbn");
+        assert_eq!(books.removed_columns[0].name, "legacy");
+        assert_eq!(books.changed_columns[0].fields, vec!["type", "nullable"]);
+
+        let script = migration_script(&diff, &source, DatabaseType::PostgreSQL, DatabaseType::PostgreSQL, false);
+        assert_eq!(
+            script,
+            "-- ALTER TABLE books DROP COLUMN legacy;\n\
+             -- DROP TABLE old_log;\n\
+             CREATE TABLE authors (\n    id integer NOT NULL\n);\n\
+             ALTER TABLE books ADD COLUMN isbn character varying(13);\n\
+             ALTER TABLE books ALTER COLUMN title TYPE character varying(200);\n\
+             ALTER TABLE books ALTER COLUMN title SET NOT NULL;\n\
+             CREATE UNIQUE INDEX books_isbn_idx ON books (isbn);\n"
+        );
+    }
+
+    #[test]
+    fn test_cross_dialect_types_compare_by_kind() {
+        let source = vec![table("t", vec![column("name", "character varying", Some(50), true)])];
+        let target = vec![table("T", vec![column("NAME", "VARCHAR2", Some(50), true)])];
+        let diff = compare_schemas(&source, DatabaseType::PostgreSQL, &target, DatabaseType::Oracle);
+        assert!(diff.is_empty());
+    }
+}
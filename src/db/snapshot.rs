@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::db::ddl::TableDefinition;
+use crate::db::types::DatabaseType;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CreateSnapshotRequest {
+    pub schema: Option<String>,
+    pub label: Option<String>,
+}
+
+// 어느 시점에 읽은 스키마 전체
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaSnapshot {
+    pub id: String,
+    pub connection_id: String,
+    pub db_type: DatabaseType,
+    pub schema: Option<String>,
+    pub label: Option<String>,
+    // RFC 3339 (UTC). 문자열 그대로 정렬해도 시간 순서가 된다
+    pub created_at: String,
+    pub tables: Vec<TableDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub connection_id: String,
+    pub db_type: DatabaseType,
+    pub schema: Option<String>,
+    pub label: Option<String>,
+    pub created_at: String,
+    pub table_count: usize,
+}
+
+impl SchemaSnapshot {
+    pub fn new(
+        connection_id: &str,
+        db_type: DatabaseType,
+        request: CreateSnapshotRequest,
+        tables: Vec<TableDefinition>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            connection_id: connection_id.to_string(),
+            db_type,
+            schema: request.schema,
+            label: request.label,
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            tables,
+        }
+    }
+
+    pub fn summary(&self) -> SnapshotSummary {
+        SnapshotSummary {
+            id: self.id.clone(),
+            connection_id: self.connection_id.clone(),
+            db_type: self.db_type,
+            schema: self.schema.clone(),
+            label: self.label.clone(),
+            created_at: self.created_at.clone(),
+            table_count: self.tables.len(),
+        }
+    }
+}
+
+// 스냅샷은 {dir}/{connection_id}/{snapshot_id}.json 으로 저장한다
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        Self::new("./snapshots")
+    }
+}
+
+// 경로에 들어가는 ID 는 영문자, 숫자, -, _ 만 허용한다
+fn is_safe_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl SnapshotStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        match std::env::var("SNAPSHOT_DIR") {
+            Ok(dir) if !dir.is_empty() => Self::new(dir),
+            _ => Self::default(),
+        }
+    }
+
+    fn connection_dir(&self, connection_id: &str) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+        if !is_safe_id(connection_id) {
+            return Err(format!("Invalid connection ID: {}", connection_id).into());
+        }
+        Ok(self.dir.join(connection_id))
+    }
+
+    fn snapshot_path(
+        &self,
+        connection_id: &str,
+        id: &str,
+    ) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+        if !is_safe_id(id) {
+            return Err(format!("Invalid snapshot ID: {}", id).into());
+        }
+        Ok(self.connection_dir(connection_id)?.join(format!("{}.json", id)))
+    }
+
+    // 임시 파일에 쓴 뒤 이름을 바꿔 읽는 쪽이 반쯤 쓴 파일을 보지 않게 한다
+    pub async fn save(&self, snapshot: &SchemaSnapshot) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.snapshot_path(&snapshot.connection_id, &snapshot.id)?;
+        tokio::fs::create_dir_all(self.connection_dir(&snapshot.connection_id)?).await?;
+        let temp = path.with_extension("json.tmp");
+        tokio::fs::write(&temp, serde_json::to_vec(snapshot)?).await?;
+        tokio::fs::rename(&temp, &path).await?;
+        Ok(())
+    }
+
+    pub async fn get(
+        &self,
+        connection_id: &str,
+        id: &str,
+    ) -> Result<Option<SchemaSnapshot>, Box<dyn std::error::Error + Send + Sync>> {
+        match tokio::fs::read(self.snapshot_path(connection_id, id)?).await {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // 오래된 것부터
+    pub async fn list(
+        &self,
+        connection_id: &str,
+    ) -> Result<Vec<SnapshotSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut entries = match tokio::fs::read_dir(self.connection_dir(connection_id)?).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut summaries = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let snapshot: SchemaSnapshot = serde_json::from_slice(&tokio::fs::read(&path).await?)?;
+                summaries.push(snapshot.summary());
+            }
+        }
+        summaries.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(summaries)
+    }
+
+    pub async fn latest(
+        &self,
+        connection_id: &str,
+    ) -> Result<Option<SchemaSnapshot>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.list(connection_id).await?.pop() {
+            Some(summary) => self.get(connection_id, &summary.id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn delete(&self, connection_id: &str, id: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        match tokio::fs::remove_file(self.snapshot_path(connection_id, id)?).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", Uuid::new_v4()));
+        let store = SnapshotStore::new(&dir);
+        let request = CreateSnapshotRequest { schema: None, label: Some("before release".into()) };
+        let snapshot = SchemaSnapshot::new("conn-1", DatabaseType::PostgreSQL, request, Vec::new());
+
+        store.save(&snapshot).await.unwrap();
+        assert_eq!(store.list("conn-1").await.unwrap(), vec![snapshot.summary()]);
+        assert_eq!(store.get("conn-1", &snapshot.id).await.unwrap(), Some(snapshot.clone()));
+        assert!(store.get("conn-1", "../conn-2").await.is_err());
+        assert!(store.delete("conn-1", &snapshot.id).await.unwrap());
+        assert!(store.list("conn-1").await.unwrap().is_empty());
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Deserialize;
+use crate::db::statement::{classify, statement_words, StatementKind};
+
+// 복사, 덤프, 프로파일이 읽을 원본. query 와 table 중 하나를 지정한다.
+// query 는 드라이버가 여러 문장을 받아 주거나 프로파일처럼 서브쿼리로 감싸므로 조회 문장 하나만 허용한다
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceSpec {
+    pub query: Option<String>,
+    pub table: Option<String>,
+}
+
+impl SourceSpec {
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match (&self.query, &self.table) {
+            (Some(query), None) if statement_words(query).len() != 1 => Err("query must be a single statement".into()),
+            (Some(query), None) if classify(query) != StatementKind::Read => Err("query must be a read statement".into()),
+            (Some(_), None) => Ok(()),
+            (None, Some(table)) if is_valid_identifier(table) => Ok(()),
+            (None, Some(table)) => Err(format!("Invalid table name: {}", table).into()),
+            _ => Err("Specify exactly one of query or table".into()),
+        }
+    }
+
+    pub fn query(&self) -> String {
+        match (&self.query, &self.table) {
+            (Some(query), _) => query.clone(),
+            (None, Some(table)) => format!("SELECT * FROM {}", table),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+// 테이블, 루틴 이름. 스키마/패키지 한정 이름 허용 (예: hr.pkg_emp.get_salary)
+pub fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('.').all(|part| {
+            let mut chars = part.chars();
+            matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '#')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_validation() {
+        assert!(is_valid_identifier("get_salary"));
+        assert!(is_valid_identifier("hr.pkg_emp.get_salary"));
+        assert!(!is_valid_identifier(""));
+        assert!(!is_valid_identifier("proc; DROP TABLE users"));
+        assert!(!is_valid_identifier("hr..proc"));
+    }
+
+    #[test]
+    fn test_source_spec() {
+        let source = |value: serde_json::Value| serde_json::from_value::<SourceSpec>(value).unwrap();
+        assert_eq!(source(serde_json::json!({ "table": "hr.books" })).query(), "SELECT * FROM hr.books");
+        assert_eq!(source(serde_json::json!({ "query": "SELECT 1" })).query(), "SELECT 1");
+        assert!(source(serde_json::json!({ "table": "books; DROP TABLE t" })).validate().is_err());
+        assert!(source(serde_json::json!({ "query": "SELECT 1", "table": "books" })).validate().is_err());
+        assert!(source(serde_json::json!({})).validate().is_err());
+        assert!(source(serde_json::json!({ "query": "WITH a AS (SELECT 1) SELECT * FROM a" })).validate().is_ok());
+        assert!(source(serde_json::json!({ "query": "SELECT 1; SELECT 2" })).validate().is_err());
+        assert!(source(serde_json::json!({ "query": "DELETE FROM books" })).validate().is_err());
+        assert!(source(serde_json::json!({ "query": "SELECT 1 DELETE FROM books" })).validate().is_err());
+        assert!(source(serde_json::json!({ "query": "" })).validate().is_err());
+    }
+}
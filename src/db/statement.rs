@@ -0,0 +1,148 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementKind {
+    Read,
+    Write,
+}
+
+// 문장 하나의 키워드(대문자). 문자열, 따옴표 식별자, 주석은 빼고 ; 로 문장을 나눈다
+pub fn statement_words(sql: &str) -> Vec<Vec<String>> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut statements = vec![Vec::new()];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == close {
+                        // '' 처럼 두 번 쓰면 이스케이프
+                        if chars.get(i + 1) == Some(&close) && close != ']' {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            // PostgreSQL 달러 인용 문자열 ($$...$$, $tag$...$tag$)
+            '$' if !chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => {
+                let start = i;
+                let mut end = i + 1;
+                while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+                    end += 1;
+                }
+                if chars.get(end) == Some(&'$') {
+                    let tag = &chars[start..=end];
+                    i = end + 1;
+                    while i < chars.len() && !chars[i..].starts_with(tag) {
+                        i += 1;
+                    }
+                    i += tag.len();
+                } else {
+                    i += 1;
+                }
+            }
+            ';' => {
+                statements.push(Vec::new());
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                    i += 1;
+                }
+                let word = chars[start..i].iter().collect::<String>().to_uppercase();
+                if let Some(statement) = statements.last_mut() {
+                    statement.push(word);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    statements.retain(|words| !words.is_empty());
+    statements
+}
+
+// 조회 문장 안에 나오면 쓰기로 보는 키워드. T-SQL 은 ; 없이 여러 문장을 이어 쓸 수 있어서
+// 첫 단어만으로는 판단할 수 없다
+const WRITE_KEYWORDS: &[&str] = &[
+    "INSERT", "UPDATE", "DELETE", "MERGE", "UPSERT", "INTO", "CREATE", "ALTER", "DROP", "TRUNCATE", "RENAME",
+    "GRANT", "REVOKE", "COMMIT", "ROLLBACK", "SAVEPOINT", "BEGIN", "DECLARE", "SET", "EXEC", "EXECUTE", "CALL",
+    "COPY", "LOAD", "LOCK", "HANDLER", "PREPARE", "DO", "VACUUM", "ANALYZE", "REINDEX", "CLUSTER", "REFRESH",
+];
+
+fn classify_words(words: &[String]) -> StatementKind {
+    if words.first().map(String::as_str) == Some("EXPLAIN") {
+        // EXPLAIN 은 계획만 만든다. EXPLAIN ANALYZE 는 문장을 실제로 실행하므로 그 문장으로 판단한다
+        if !words.iter().any(|word| word == "ANALYZE") {
+            return StatementKind::Read;
+        }
+        let inner = words
+            .iter()
+            .position(|word| !matches!(word.as_str(), "EXPLAIN" | "ANALYZE" | "VERBOSE" | "BUFFERS" | "FORMAT" | "JSON" | "TEXT"))
+            .unwrap_or(words.len());
+        return classify_words(&words[inner..]);
+    }
+    let readable = matches!(
+        words.first().map(String::as_str),
+        Some("SELECT" | "WITH" | "SHOW" | "DESCRIBE" | "DESC" | "VALUES" | "TABLE")
+    );
+    // SELECT ... INTO 는 테이블을 만든다
+    if readable && !words.iter().any(|word| WRITE_KEYWORDS.contains(&word.as_str())) {
+        StatementKind::Read
+    } else {
+        StatementKind::Write
+    }
+}
+
+// 문장 중 하나라도 쓰기면 Write. 함수 안에서 일어나는 쓰기는 알 수 없다
+pub fn classify(sql: &str) -> StatementKind {
+    let statements = statement_words(sql);
+    if statements.iter().all(|words| classify_words(words) == StatementKind::Read) {
+        StatementKind::Read
+    } else {
+        StatementKind::Write
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("select * from t where note = 'drop table x; delete'"), StatementKind::Read);
+        assert_eq!(classify("-- delete\nSELECT 1; /* update */ SHOW tables"), StatementKind::Read);
+        assert_eq!(classify("WITH a AS (SELECT 1) SELECT * FROM a"), StatementKind::Read);
+        assert_eq!(classify("WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d"), StatementKind::Write);
+        assert_eq!(classify("SELECT * INTO backup FROM t"), StatementKind::Write);
+        assert_eq!(classify("SELECT 1; DROP TABLE t"), StatementKind::Write);
+        assert_eq!(classify("EXPLAIN ANALYZE DELETE FROM t"), StatementKind::Write);
+        assert_eq!(classify("EXPLAIN DELETE FROM t"), StatementKind::Read);
+        assert_eq!(classify("EXPLAIN ANALYZE SELECT * FROM t"), StatementKind::Read);
+        assert_eq!(classify("EXPLAIN (ANALYZE, BUFFERS) UPDATE t SET a = 1"), StatementKind::Write);
+        assert_eq!(classify("SELECT 1 DELETE FROM t"), StatementKind::Write);
+        assert_eq!(classify("SELECT set_config('default_transaction_read_only', 'off', false)"), StatementKind::Read);
+        assert_eq!(classify("SELECT $$; DROP TABLE t$$"), StatementKind::Read);
+        assert_eq!(classify("SELECT \"update\" FROM [delete]"), StatementKind::Read);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::db::policy::SqlPolicy;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum DatabaseType {
@@ -9,6 +10,67 @@ pub enum DatabaseType {
     Redis,
 }
 
+// 결과 집합의 컬럼 이름과 드라이버가 보고한 타입 이름
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMeta {
+    pub name: String,
+    pub data_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultSet {
+    pub columns: Vec<ColumnMeta>,
+    pub rows: Vec<serde_json::Value>,
+    pub affected_rows: Option<u64>,
+}
+
+// 컬럼 이름을 한 번만 싣는 형태. rows 의 각 값 배열은 columns 순서를 따른다
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompactResultSet {
+    pub columns: Vec<ColumnMeta>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub affected_rows: Option<u64>,
+}
+
+impl From<ResultSet> for CompactResultSet {
+    fn from(result_set: ResultSet) -> Self {
+        // 컬럼 메타데이터가 없으면 첫 행의 키를 사용한다
+        let columns = if result_set.columns.is_empty() {
+            result_set
+                .rows
+                .first()
+                .and_then(|row| row.as_object())
+                .map(|row| {
+                    row.keys()
+                        .map(|name| ColumnMeta { name: name.clone(), data_type: String::new() })
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            result_set.columns
+        };
+        let rows = result_set
+            .rows
+            .into_iter()
+            .map(|mut row| {
+                columns
+                    .iter()
+                    .map(|column| match row.as_object_mut() {
+                        Some(object) => object.remove(&column.name).unwrap_or_default(),
+                        None => serde_json::Value::Null,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            columns,
+            rows,
+            affected_rows: result_set.affected_rows,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ConnectionInfo {
     pub db_type: DatabaseType,
@@ -17,6 +79,12 @@ pub struct ConnectionInfo {
     pub password: Option<String>,
     #[serde(default)]
     pub pool_options: PoolOptions,
+    // 게이트웨이의 문장 분류와 드라이버 세션 설정 양쪽에서 쓰기를 막는다
+    #[serde(default)]
+    pub read_only: bool,
+    // 실행 전에 방언별로 해석해서 검사하는 SQL 정책
+    #[serde(default)]
+    pub policy: Option<SqlPolicy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +116,8 @@ impl ConnectionInfo {
             username: None,
             password: None,
             pool_options,
+            read_only: false,
+            policy: None,
         }
     }
 
@@ -56,4 +126,26 @@ impl ConnectionInfo {
         self.password = Some(password);
         self
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compact_result_set() {
+        let result_set = ResultSet {
+            columns: vec![
+                ColumnMeta { name: "id".into(), data_type: "INT4".into() },
+                ColumnMeta { name: "title".into(), data_type: "TEXT".into() },
+            ],
+            rows: vec![json!({"title": "Rust", "id": 1}), json!({"id": 2})],
+            affected_rows: None,
+        };
+
+        let compact = CompactResultSet::from(result_set);
+        assert_eq!(compact.rows, vec![vec![json!(1), json!("Rust")], vec![json!(2), json!(null)]]);
+        assert_eq!(compact.columns[1].name, "title");
+    }
+}
@@ -0,0 +1,34 @@
+use base64ct::{Base64, Encoding};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+
+// JSON 에 담기 어려운 타입은 손실 없는 문자열로 표현한다.
+// 내보내기(Arrow/Parquet 등) 시 컬럼 타입 정보와 함께 다시 해석된다.
+
+pub fn decimal_value(v: Decimal) -> serde_json::Value {
+    serde_json::Value::String(v.to_string())
+}
+
+pub fn datetime_value(v: NaiveDateTime) -> serde_json::Value {
+    serde_json::Value::String(v.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+}
+
+pub fn datetime_tz_value(v: DateTime<Utc>) -> serde_json::Value {
+    serde_json::Value::String(v.to_rfc3339())
+}
+
+pub fn date_value(v: NaiveDate) -> serde_json::Value {
+    serde_json::Value::String(v.format("%Y-%m-%d").to_string())
+}
+
+pub fn time_value(v: NaiveTime) -> serde_json::Value {
+    serde_json::Value::String(v.format("%H:%M:%S%.f").to_string())
+}
+
+pub fn binary_value(v: &[u8]) -> serde_json::Value {
+    serde_json::Value::String(Base64::encode_string(v))
+}
+
+pub fn float_value(v: f64) -> serde_json::Value {
+    serde_json::Value::Number(serde_json::Number::from_f64(v).unwrap_or(0.into()))
+}
@@ -1,12 +1,17 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
+    Json,
 };
+use crate::db::import::ImportError;
+use crate::db::policy::PolicyViolation;
 
 #[derive(Debug)]
 pub struct AppError {
     pub message: String,
     pub status_code: StatusCode,
+    // 있으면 {"error": message, "details": details} JSON 으로 응답한다
+    pub details: Option<serde_json::Value>,
 }
 
 impl AppError {
@@ -14,6 +19,39 @@ impl AppError {
         Self {
             message,
             status_code: StatusCode::BAD_REQUEST,
+            details: None,
+        }
+    }
+
+    pub fn not_found(message: String) -> Self {
+        Self {
+            message,
+            status_code: StatusCode::NOT_FOUND,
+            details: None,
+        }
+    }
+
+    pub fn unauthorized(message: String) -> Self {
+        Self {
+            message,
+            status_code: StatusCode::UNAUTHORIZED,
+            details: None,
+        }
+    }
+
+    pub fn forbidden(message: String) -> Self {
+        Self {
+            message,
+            status_code: StatusCode::FORBIDDEN,
+            details: None,
+        }
+    }
+
+    pub fn locked(message: String) -> Self {
+        Self {
+            message,
+            status_code: StatusCode::LOCKED,
+            details: None,
         }
     }
 
@@ -21,12 +59,28 @@ impl AppError {
         Self {
             message,
             status_code: StatusCode::INTERNAL_SERVER_ERROR,
+            details: None,
         }
     }
 }
 
+impl AppError {
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        if let Some(details) = self.details {
+            let body = serde_json::json!({ "error": self.message, "details": details });
+            return (self.status_code, Json(body)).into_response();
+        }
+        if self.status_code == StatusCode::UNAUTHORIZED {
+            let challenge = [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))];
+            return (self.status_code, challenge, self.message).into_response();
+        }
         (self.status_code, self.message).into_response()
     }
 }
@@ -42,3 +96,18 @@ impl From<Box<dyn std::error::Error + Send + Sync>> for AppError {
         Self::database_error(error.to_string())
     }
 }
+
+impl From<PolicyViolation> for AppError {
+    fn from(violation: PolicyViolation) -> Self {
+        let details = serde_json::to_value(&violation).unwrap_or_default();
+        Self::forbidden(violation.to_string()).with_details(details)
+    }
+}
+
+// 중단되기 전까지 적재/거부된 행을 details 로 돌려준다
+impl From<ImportError> for AppError {
+    fn from(error: ImportError) -> Self {
+        let details = serde_json::to_value(&error.report).unwrap_or_default();
+        Self::database_error(error.to_string()).with_details(details)
+    }
+}
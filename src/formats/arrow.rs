@@ -0,0 +1,202 @@
+use std::sync::Arc;
+use arrow::array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Float64Array, Int64Array,
+    StringArray, Time64MicrosecondArray, TimestampMicrosecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use base64ct::{Base64, Encoding};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use crate::db::types::{CompactResultSet, DatabaseType};
+use crate::formats::typed::{
+    column_kind, parse_bool, parse_date_days, parse_decimal, parse_time_micros, parse_timestamp_micros, text,
+    ColumnKind,
+};
+use crate::formats::OutputFormat;
+
+// Decimal128 최대 정밀도
+const DECIMAL_PRECISION: u8 = 38;
+
+fn parse_all<T>(
+    values: &[&serde_json::Value],
+    parse: impl Fn(&serde_json::Value) -> Option<T>,
+) -> Option<Vec<Option<T>>> {
+    values
+        .iter()
+        .map(|value| if value.is_null() { Some(None) } else { parse(value).map(Some) })
+        .collect()
+}
+
+// 값 하나라도 해석할 수 없으면 None 을 돌려주고, 호출 측은 문자열 컬럼으로 내린다
+fn typed_array(kind: ColumnKind, values: &[&serde_json::Value]) -> Option<(DataType, ArrayRef)> {
+    let array: ArrayRef = match kind {
+        ColumnKind::Boolean => Arc::new(BooleanArray::from(parse_all(values, parse_bool)?)),
+        ColumnKind::Int64 => Arc::new(Int64Array::from(parse_all(values, |v| {
+            v.as_i64().or_else(|| v.as_str()?.parse().ok())
+        })?)),
+        ColumnKind::UInt64 => Arc::new(UInt64Array::from(parse_all(values, |v| {
+            v.as_u64().or_else(|| v.as_str()?.parse().ok())
+        })?)),
+        ColumnKind::Float64 => Arc::new(Float64Array::from(parse_all(values, |v| {
+            v.as_f64().or_else(|| v.as_str()?.parse().ok())
+        })?)),
+        ColumnKind::Decimal => {
+            let decimals = parse_all(values, parse_decimal)?;
+            // 타입 이름에 스케일이 없는 드라이버가 많아 값에서 스케일을 정한다
+            let scale = decimals.iter().flatten().map(|d| d.scale()).max().unwrap_or(0);
+            let mantissas = decimals
+                .into_iter()
+                .map(|d| {
+                    d.map(|mut d| {
+                        d.rescale(scale);
+                        d.mantissa()
+                    })
+                })
+                .collect::<Vec<_>>();
+            Arc::new(
+                Decimal128Array::from(mantissas)
+                    .with_precision_and_scale(DECIMAL_PRECISION, i8::try_from(scale).ok()?)
+                    .ok()?,
+            )
+        }
+        ColumnKind::Timestamp => Arc::new(TimestampMicrosecondArray::from(parse_all(values, parse_timestamp_micros)?)),
+        ColumnKind::TimestampTz => Arc::new(
+            TimestampMicrosecondArray::from(parse_all(values, parse_timestamp_micros)?).with_timezone("UTC"),
+        ),
+        ColumnKind::Date => Arc::new(Date32Array::from(parse_all(values, parse_date_days)?)),
+        ColumnKind::Time => Arc::new(Time64MicrosecondArray::from(parse_all(values, parse_time_micros)?)),
+        ColumnKind::Binary => {
+            let bytes = parse_all(values, |v| Base64::decode_vec(v.as_str()?).ok())?;
+            Arc::new(BinaryArray::from_iter(bytes))
+        }
+        ColumnKind::Utf8 => Arc::new(StringArray::from(values.iter().map(|v| text(v)).collect::<Vec<_>>())),
+    };
+    Some((array.data_type().clone(), array))
+}
+
+// 결과 집합을 컬럼 메타데이터에 맞춘 RecordBatch 로 변환한다
+pub fn to_record_batch(result_set: &CompactResultSet, db_type: DatabaseType) -> Result<RecordBatch, String> {
+    let mut fields = Vec::with_capacity(result_set.columns.len());
+    let mut arrays = Vec::with_capacity(result_set.columns.len());
+
+    for (i, column) in result_set.columns.iter().enumerate() {
+        let kind = column_kind(db_type, &column.data_type);
+        let values = result_set
+            .rows
+            .iter()
+            .map(|row| row.get(i).unwrap_or(&serde_json::Value::Null))
+            .collect::<Vec<_>>();
+        let (data_type, array) = typed_array(kind, &values)
+            .or_else(|| typed_array(ColumnKind::Utf8, &values))
+            .ok_or_else(|| format!("Failed to convert column {}", column.name))?;
+        fields.push(Field::new(&column.name, data_type, true));
+        arrays.push(array);
+    }
+
+    let options = RecordBatchOptions::new().with_row_count(Some(result_set.rows.len()));
+    RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options).map_err(|e| e.to_string())
+}
+
+fn encode_ipc(batch: &RecordBatch) -> Result<Vec<u8>, String> {
+    let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema()).map_err(|e| e.to_string())?;
+    writer.write(batch).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn encode_parquet(batch: &RecordBatch) -> Result<Vec<u8>, String> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(properties)).map_err(|e| e.to_string())?;
+    writer.write(batch).map_err(|e| e.to_string())?;
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+// Arrow IPC 스트림 또는 Parquet 파일 하나로 응답한다
+pub fn arrow_response(
+    result_set: &CompactResultSet,
+    db_type: DatabaseType,
+    format: OutputFormat,
+    mut headers: HeaderMap,
+) -> Result<Response, String> {
+    let batch = to_record_batch(result_set, db_type)?;
+    let (body, content_type) = match format {
+        OutputFormat::Parquet => (encode_parquet(&batch)?, "application/vnd.apache.parquet"),
+        _ => (encode_ipc(&batch)?, "application/vnd.apache.arrow.stream"),
+    };
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    Ok((headers, body).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{ColumnMeta, ResultSet};
+    use arrow::array::Array;
+    use arrow::datatypes::TimeUnit;
+    use serde_json::json;
+
+    fn column(name: &str, data_type: &str) -> ColumnMeta {
+        ColumnMeta { name: name.into(), data_type: data_type.into() }
+    }
+
+    #[test]
+    fn test_record_batch_types() {
+        let result_set = ResultSet {
+            columns: vec![
+                column("id", "INT8"),
+                column("price", "NUMERIC"),
+                column("created_at", "TIMESTAMPTZ"),
+                column("cover", "BYTEA"),
+                column("note", "INT4"),
+            ],
+            rows: vec![
+                json!({"id": 1, "price": "12.5", "created_at": "2024-01-02T03:04:05+00:00", "cover": "AQID", "note": 7}),
+                json!({"id": 2, "price": "3.25", "created_at": null, "cover": null, "note": "n/a"}),
+            ],
+            affected_rows: None,
+        };
+
+        let batch = to_record_batch(&result_set.into(), DatabaseType::PostgreSQL).unwrap();
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Decimal128(38, 2));
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+        assert_eq!(schema.field(3).data_type(), &DataType::Binary);
+        // 해석할 수 없는 값이 섞이면 문자열 컬럼으로 내린다
+        assert_eq!(schema.field(4).data_type(), &DataType::Utf8);
+
+        let prices = batch.column(1).as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(prices.value(0), 1250);
+        let covers = batch.column(3).as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(covers.value(0), &[1, 2, 3]);
+        assert!(covers.is_null(1));
+    }
+
+    #[test]
+    fn test_encode_ipc_and_parquet() {
+        let result_set = ResultSet {
+            columns: vec![column("id", "INT4")],
+            rows: vec![json!({"id": 1}), json!({"id": 2})],
+            affected_rows: None,
+        };
+        let batch = to_record_batch(&result_set.into(), DatabaseType::MySQL).unwrap();
+
+        let ipc = encode_ipc(&batch).unwrap();
+        let mut reader = arrow::ipc::reader::StreamReader::try_new(ipc.as_slice(), None).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().num_rows(), 2);
+
+        let parquet = encode_parquet(&batch).unwrap();
+        assert_eq!(&parquet[..4], b"PAR1");
+    }
+}
@@ -0,0 +1,144 @@
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use crate::db::types::CompactResultSet;
+use crate::formats::{value_to_text, OutputFormat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    #[default]
+    Necessary,
+    Always,
+    NonNumeric,
+    Never,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CsvOptions {
+    pub delimiter: Option<char>,
+    #[serde(default)]
+    pub quote_style: QuoteStyle,
+    #[serde(default)]
+    pub null_value: String,
+    #[serde(default = "default_header")]
+    pub header: bool,
+}
+
+fn default_header() -> bool {
+    true
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote_style: QuoteStyle::default(),
+            null_value: String::new(),
+            header: default_header(),
+        }
+    }
+}
+
+impl CsvOptions {
+    fn delimiter_for(&self, format: OutputFormat) -> Result<u8, String> {
+        let delimiter = self
+            .delimiter
+            .unwrap_or(if format == OutputFormat::Tsv { '\t' } else { ',' });
+        // 128 이상의 문자는 UTF-8 로 한 바이트가 아니다
+        if !delimiter.is_ascii() {
+            return Err(format!("Delimiter must be a single-byte ASCII character: {:?}", delimiter));
+        }
+        Ok(delimiter as u8)
+    }
+
+    fn writer_builder(&self, delimiter: u8) -> csv::WriterBuilder {
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(delimiter)
+            .terminator(csv::Terminator::CRLF)
+            .quote_style(match self.quote_style {
+                QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+                QuoteStyle::Always => csv::QuoteStyle::Always,
+                QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+                QuoteStyle::Never => csv::QuoteStyle::Never,
+            });
+        builder
+    }
+}
+
+// 결과 집합을 RFC 4180 CSV (또는 TSV) 로 인코딩한다
+pub fn csv_response(
+    result_set: CompactResultSet,
+    format: OutputFormat,
+    options: CsvOptions,
+    mut headers: HeaderMap,
+) -> Result<Response, String> {
+    let delimiter = options.delimiter_for(format)?;
+    let mut writer = options.writer_builder(delimiter).from_writer(Vec::new());
+    if options.header {
+        writer
+            .write_record(result_set.columns.iter().map(|c| c.name.as_str()))
+            .map_err(|e| e.to_string())?;
+    }
+    for row in &result_set.rows {
+        writer
+            .write_record(row.iter().map(|value| value_to_text(value, &options.null_value)))
+            .map_err(|e| e.to_string())?;
+    }
+    let body = writer.into_inner().map_err(|e| e.into_error().to_string())?;
+
+    let content_type = match format {
+        OutputFormat::Tsv => "text/tab-separated-values; charset=utf-8",
+        _ => "text/csv; charset=utf-8",
+    };
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    Ok((headers, body).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{ColumnMeta, ResultSet};
+    use serde_json::json;
+
+    fn sample() -> ResultSet {
+        ResultSet {
+            columns: vec![
+                ColumnMeta { name: "id".into(), data_type: "INT4".into() },
+                ColumnMeta { name: "title".into(), data_type: "TEXT".into() },
+            ],
+            rows: vec![
+                json!({"id": 1, "title": "Hello, \"world\""}),
+                json!({"id": 2, "title": null}),
+            ],
+            affected_rows: None,
+        }
+    }
+
+    async fn body_text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_csv_quotes_and_nulls() {
+        let options = CsvOptions { null_value: "NULL".into(), ..Default::default() };
+        let response = csv_response(sample().into(), OutputFormat::Csv, options, HeaderMap::new()).unwrap();
+        assert_eq!(
+            body_text(response).await,
+            "id,title\r\n1,\"Hello, \"\"world\"\"\"\r\n2,NULL\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tsv_without_header() {
+        let options = CsvOptions { header: false, ..Default::default() };
+        let response = csv_response(sample().into(), OutputFormat::Tsv, options, HeaderMap::new()).unwrap();
+        assert_eq!(body_text(response).await, "1\t\"Hello, \"\"world\"\"\"\r\n2\t\r\n");
+
+        let options = CsvOptions { delimiter: Some('\u{a7}'), ..Default::default() };
+        assert!(csv_response(sample().into(), OutputFormat::Csv, options, HeaderMap::new()).is_err());
+    }
+}
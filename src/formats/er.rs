@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use crate::db::constraints::{ConstraintInfo, ConstraintKind, ForeignKeyInfo};
+use crate::db::schema::{ColumnInfo, TableInfo};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErFormat {
+    #[default]
+    Json,
+    Mermaid,
+    Dot,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ErColumn {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    pub foreign_key: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ErNode {
+    pub id: String,
+    pub columns: Vec<ErColumn>,
+}
+
+// 참조하는 테이블(from) 에서 참조되는 테이블(to) 로
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ErEdge {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub columns: Vec<String>,
+    pub referenced_columns: Vec<String>,
+    // 외래 키 컬럼이 NULL 을 허용하면 부모 없이도 존재할 수 있다
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ErGraph {
+    pub nodes: Vec<ErNode>,
+    pub edges: Vec<ErEdge>,
+}
+
+impl ErGraph {
+    pub fn new(
+        tables: &[TableInfo],
+        columns: &[(String, ColumnInfo)],
+        constraints: &[ConstraintInfo],
+        foreign_keys: &[ForeignKeyInfo],
+    ) -> Self {
+        let primary = constraints
+            .iter()
+            .filter(|c| c.kind == ConstraintKind::PrimaryKey)
+            .flat_map(|c| c.columns.iter().map(move |column| (c.table.as_str(), column.as_str())))
+            .collect::<HashSet<_>>();
+        let foreign = foreign_keys
+            .iter()
+            .flat_map(|fk| fk.columns.iter().map(move |column| (fk.table.as_str(), column.as_str())))
+            .collect::<HashSet<_>>();
+
+        let mut nodes = tables
+            .iter()
+            .map(|table| ErNode {
+                id: table.name.clone(),
+                columns: columns
+                    .iter()
+                    .filter(|(owner, _)| *owner == table.name)
+                    .map(|(_, column)| ErColumn {
+                        name: column.name.clone(),
+                        data_type: column.data_type.clone(),
+                        nullable: column.nullable,
+                        primary_key: primary.contains(&(table.name.as_str(), column.name.as_str())),
+                        foreign_key: foreign.contains(&(table.name.as_str(), column.name.as_str())),
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut edges = Vec::new();
+        for fk in foreign_keys {
+            // 다른 스키마의 테이블은 스키마 이름을 붙여 컬럼 없는 노드로 둔다
+            let to = if fk.referenced_schema == fk.schema {
+                fk.referenced_table.clone()
+            } else {
+                format!("{}.{}", fk.referenced_schema, fk.referenced_table)
+            };
+            if !nodes.iter().any(|node| node.id == to) {
+                nodes.push(ErNode { id: to.clone(), columns: Vec::new() });
+            }
+            let optional = columns
+                .iter()
+                .filter(|(owner, column)| *owner == fk.table && fk.columns.contains(&column.name))
+                .any(|(_, column)| column.nullable);
+            edges.push(ErEdge {
+                name: fk.name.clone(),
+                from: fk.table.clone(),
+                to,
+                columns: fk.columns.clone(),
+                referenced_columns: fk.referenced_columns.clone(),
+                optional,
+            });
+        }
+
+        Self { nodes, edges }
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("erDiagram\n");
+        for node in &self.nodes {
+            out.push_str(&format!("    {} {{\n", mermaid_name(&node.id)));
+            for column in &node.columns {
+                let keys = match (column.primary_key, column.foreign_key) {
+                    (true, true) => " PK, FK",
+                    (true, false) => " PK",
+                    (false, true) => " FK",
+                    (false, false) => "",
+                };
+                out.push_str(&format!(
+                    "        {} {}{}\n",
+                    mermaid_name(&column.data_type),
+                    mermaid_name(&column.name),
+                    keys
+                ));
+            }
+            out.push_str("    }\n");
+        }
+        for edge in &self.edges {
+            let parent = if edge.optional { "|o" } else { "||" };
+            out.push_str(&format!(
+                "    {} {}--o{{ {} : \"{}\"\n",
+                mermaid_name(&edge.to),
+                parent,
+                mermaid_name(&edge.from),
+                edge.name.replace('"', "'")
+            ));
+        }
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph er {\n    rankdir=LR;\n    node [shape=record];\n");
+        for node in &self.nodes {
+            let fields = node
+                .columns
+                .iter()
+                .map(|column| {
+                    let key = match (column.primary_key, column.foreign_key) {
+                        (true, true) => " (PK, FK)",
+                        (true, false) => " (PK)",
+                        (false, true) => " (FK)",
+                        (false, false) => "",
+                    };
+                    format!("{} : {}{}\\l", record_text(&column.name), record_text(&column.data_type), key)
+                })
+                .collect::<String>();
+            out.push_str(&format!(
+                "    {} [label=\"{{{}|{}}}\"];\n",
+                dot_id(&node.id),
+                record_text(&node.id),
+                fields
+            ));
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "    {} -> {} [label={}];\n",
+                dot_id(&edge.from),
+                dot_id(&edge.to),
+                dot_id(&edge.name)
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+// Mermaid 의 엔티티/속성 이름은 영문자, 숫자, _, - 만 쓸 수 있다
+fn mermaid_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// record 라벨에서 구조를 나타내는 문자는 이스케이프해야 한다
+fn record_text(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '{' | '}' | '|' | '<' | '>' | '"' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+pub fn er_response(graph: ErGraph, format: ErFormat) -> Response {
+    match format {
+        ErFormat::Json => Json(graph).into_response(),
+        ErFormat::Mermaid => (
+            [(header::CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"))],
+            graph.to_mermaid(),
+        )
+            .into_response(),
+        ErFormat::Dot => (
+            [(header::CONTENT_TYPE, HeaderValue::from_static("text/vnd.graphviz; charset=utf-8"))],
+            graph.to_dot(),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::TableKind;
+
+    fn column(name: &str, nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.into(),
+            ordinal: 1,
+            data_type: "character varying".into(),
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            nullable,
+            default: None,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn test_graph_renders_foreign_keys() {
+        let table = |name: &str| TableInfo { schema: "public".into(), name: name.into(), kind: TableKind::Table, comment: None };
+        let tables = vec![table("customers"), table("orders")];
+        let columns = vec![
+            ("customers".to_string(), column("id", false)),
+            ("orders".to_string(), column("id", false)),
+            ("orders".to_string(), column("customer_id", true)),
+        ];
+        let constraints = vec![ConstraintInfo {
+            table: "customers".into(),
+            name: "customers_pk".into(),
+            kind: ConstraintKind::PrimaryKey,
+            columns: vec!["id".into()],
+            definition: None,
+        }];
+        let foreign_keys = vec![ForeignKeyInfo {
+            name: "orders_customer_fk".into(),
+            schema: "public".into(),
+            table: "orders".into(),
+            columns: vec!["customer_id".into()],
+            referenced_schema: "public".into(),
+            referenced_table: "customers".into(),
+            referenced_columns: vec!["id".into()],
+            on_delete: None,
+            on_update: None,
+        }];
+
+        let graph = ErGraph::new(&tables, &columns, &constraints, &foreign_keys);
+        assert!(graph.edges[0].optional);
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("        character_varying id PK\n"));
+        assert!(mermaid.contains("        character_varying customer_id FK\n"));
+        assert!(mermaid.contains("    customers |o--o{ orders : \"orders_customer_fk\"\n"));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"orders\" -> \"customers\" [label=\"orders_customer_fk\"];"));
+        assert!(dot.contains("label=\"{customers|id : character varying (PK)\\l}\""));
+    }
+}
@@ -0,0 +1,143 @@
+pub mod arrow;
+pub mod csv;
+pub mod er;
+pub mod typed;
+pub mod xlsx;
+
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use crate::db::types::{CompactResultSet, DatabaseType, ResultSet};
+use crate::error::AppError;
+use crate::formats::arrow::arrow_response;
+use crate::formats::csv::{csv_response, CsvOptions};
+use crate::formats::xlsx::xlsx_response;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Json,
+    // columns 배열과 값 배열 rows 로 이루어진 JSON
+    Compact,
+    Csv,
+    Tsv,
+    Arrow,
+    Parquet,
+    Xlsx,
+}
+
+impl OutputFormat {
+    // 요청 본문의 format 이 Accept 헤더보다 우선한다
+    pub fn negotiate(requested: Option<OutputFormat>, headers: &HeaderMap) -> Self {
+        if let Some(format) = requested {
+            return format;
+        }
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        // q 값이 가장 큰 형식, 같으면 먼저 나온 형식. q=0 은 받지 않는다는 뜻이다
+        let mut best: Option<(Self, f32)> = None;
+        for part in accept.split(',') {
+            let mut params = part.split(';');
+            let format = match params.next().unwrap_or_default().trim() {
+                "text/csv" => Self::Csv,
+                "text/tab-separated-values" => Self::Tsv,
+                "application/vnd.apache.arrow.stream" => Self::Arrow,
+                "application/vnd.apache.parquet" => Self::Parquet,
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => Self::Xlsx,
+                "application/json" => Self::Json,
+                _ => continue,
+            };
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((format, quality));
+            }
+        }
+        best.map_or(Self::Json, |(format, _)| format)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompactResults {
+    pub result_sets: Vec<CompactResultSet>,
+}
+
+// 결과 집합 목록을 요청한 형식으로 내보낸다.
+// xlsx 는 결과 집합마다 시트를 만들고, CSV/TSV/Arrow/Parquet 는 result_set 하나만 담는다.
+pub fn export_response(
+    result_sets: Vec<ResultSet>,
+    db_type: DatabaseType,
+    format: OutputFormat,
+    result_set: usize,
+    csv: CsvOptions,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    if format == OutputFormat::Json {
+        return Ok((headers, Json(result_sets)).into_response());
+    }
+    // 나머지 인코더는 모두 열 지향 형태를 기반으로 한다
+    let mut result_sets = result_sets.into_iter().map(CompactResultSet::from).collect::<Vec<_>>();
+    match format {
+        OutputFormat::Compact => Ok((headers, Json(CompactResults { result_sets })).into_response()),
+        OutputFormat::Xlsx => xlsx_response(&result_sets, db_type, headers).map_err(AppError::validation_error),
+        format => {
+            if result_set >= result_sets.len() {
+                return Err(AppError::validation_error(format!(
+                    "Result set {} does not exist",
+                    result_set
+                )));
+            }
+            let selected = result_sets.swap_remove(result_set);
+            match format {
+                OutputFormat::Arrow | OutputFormat::Parquet => {
+                    arrow_response(&selected, db_type, format, headers).map_err(AppError::database_error)
+                }
+                _ => csv_response(selected, format, csv, headers).map_err(AppError::validation_error),
+            }
+        }
+    }
+}
+
+// 셀 하나를 텍스트로 표현한다 (객체/배열은 JSON 텍스트)
+pub fn value_to_text(value: &serde_json::Value, null_value: &str) -> String {
+    match value {
+        serde_json::Value::Null => null_value.to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_negotiate() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(OutputFormat::negotiate(None, &headers), OutputFormat::Json);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("text/tab-separated-values;q=0.9, */*"));
+        assert_eq!(OutputFormat::negotiate(None, &headers), OutputFormat::Tsv);
+        assert_eq!(OutputFormat::negotiate(Some(OutputFormat::Csv), &headers), OutputFormat::Csv);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/vnd.apache.parquet"));
+        assert_eq!(OutputFormat::negotiate(None, &headers), OutputFormat::Parquet);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("text/csv;q=0.1, application/json"));
+        assert_eq!(OutputFormat::negotiate(None, &headers), OutputFormat::Json);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json;q=0.5, text/csv; q=0.8"));
+        assert_eq!(OutputFormat::negotiate(None, &headers), OutputFormat::Csv);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("text/csv;q=0"));
+        assert_eq!(OutputFormat::negotiate(None, &headers), OutputFormat::Json);
+    }
+}
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use rust_decimal::Decimal;
+use crate::db::types::DatabaseType;
+use crate::formats::value_to_text;
+
+// 드라이버가 보고한 타입 이름을 분류한 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Boolean,
+    Int64,
+    UInt64,
+    Float64,
+    Decimal,
+    Timestamp,
+    TimestampTz,
+    Date,
+    Time,
+    Binary,
+    Utf8,
+}
+
+pub fn column_kind(db_type: DatabaseType, data_type: &str) -> ColumnKind {
+    let upper = data_type.to_ascii_uppercase();
+    let base = upper.split('(').next().unwrap_or_default().trim();
+
+    match (db_type, base) {
+        // Oracle DATE 는 시각까지 담는다
+        (DatabaseType::Oracle, "DATE") => ColumnKind::Timestamp,
+        (DatabaseType::Oracle, "TIMESTAMP") if upper.contains("TIME ZONE") => ColumnKind::TimestampTz,
+        // NUMBER(p) 는 정수, NUMBER(p,s) 와 NUMBER 는 소수
+        (DatabaseType::Oracle, "NUMBER") => match oracle_number_scale(&upper) {
+            Some(0) => ColumnKind::Int64,
+            _ => ColumnKind::Decimal,
+        },
+        // MySQL TIMESTAMP 는 UTC 로 저장된다
+        (DatabaseType::MySQL, "TIMESTAMP") => ColumnKind::TimestampTz,
+        (DatabaseType::MySQL, "BIGINT UNSIGNED") => ColumnKind::UInt64,
+        (DatabaseType::MSSQL, "BIT" | "BITN") => ColumnKind::Boolean,
+        (_, "BOOL" | "BOOLEAN") => ColumnKind::Boolean,
+        (
+            _,
+            "INT1" | "INT2" | "INT4" | "INT8" | "INTN" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT"
+            | "INTEGER" | "BIGINT" | "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED"
+            | "INT UNSIGNED",
+        ) => ColumnKind::Int64,
+        (
+            _,
+            "FLOAT4" | "FLOAT8" | "FLOATN" | "FLOAT" | "REAL" | "DOUBLE" | "DOUBLE PRECISION"
+            | "BINARY_FLOAT" | "BINARY_DOUBLE",
+        ) => ColumnKind::Float64,
+        (_, "NUMERIC" | "DECIMAL" | "NUMERICN" | "DECIMALN" | "MONEY" | "MONEY4" | "SMALLMONEY") => {
+            ColumnKind::Decimal
+        }
+        // information_schema 는 PostgreSQL 타입을 긴 이름으로 보여준다
+        (_, "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" | "DATETIMEOFFSET" | "DATETIMEOFFSETN") => {
+            ColumnKind::TimestampTz
+        }
+        (_, "TIMESTAMP WITHOUT TIME ZONE") => ColumnKind::Timestamp,
+        (_, "TIME WITHOUT TIME ZONE" | "TIME WITH TIME ZONE" | "TIMETZ") => ColumnKind::Time,
+        (_, "TIMESTAMP" | "DATETIME" | "DATETIME2" | "DATETIMEN" | "DATETIME4" | "SMALLDATETIME") => {
+            ColumnKind::Timestamp
+        }
+        (_, "DATE" | "DATEN") => ColumnKind::Date,
+        (_, "TIME" | "TIMEN") => ColumnKind::Time,
+        (
+            _,
+            "BYTEA" | "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BINARY" | "VARBINARY" | "BIGBINARY"
+            | "BIGVARBIN" | "IMAGE" | "RAW" | "LONG RAW",
+        ) => ColumnKind::Binary,
+        _ => ColumnKind::Utf8,
+    }
+}
+
+// "NUMBER(10,2)" -> Some(2), "NUMBER(10)" -> Some(0), "NUMBER" -> None
+fn oracle_number_scale(data_type: &str) -> Option<i64> {
+    let args = data_type.split_once('(')?.1.trim_end_matches(')');
+    match args.split_once(',') {
+        Some((_, scale)) => scale.trim().parse().ok(),
+        None => Some(0),
+    }
+}
+
+pub fn text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        other => Some(value_to_text(other, "")),
+    }
+}
+
+pub fn parse_bool(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(b) => Some(*b),
+        serde_json::Value::Number(n) => n.as_i64().map(|n| n != 0),
+        serde_json::Value::String(s) => match s.as_str() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn parse_decimal(value: &serde_json::Value) -> Option<Decimal> {
+    let text = text(value)?;
+    Decimal::from_str(&text).or_else(|_| Decimal::from_scientific(&text)).ok()
+}
+
+pub fn parse_timestamp_micros(value: &serde_json::Value) -> Option<i64> {
+    let text = value.as_str()?;
+    if let Ok(v) = DateTime::parse_from_rfc3339(text) {
+        return Some(v.timestamp_micros());
+    }
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+        .map(|v| v.and_utc().timestamp_micros())
+}
+
+pub fn parse_date_days(value: &serde_json::Value) -> Option<i32> {
+    let date = NaiveDate::parse_from_str(value.as_str()?, "%Y-%m-%d").ok()?;
+    i32::try_from((date - NaiveDate::from_ymd_opt(1970, 1, 1)?).num_days()).ok()
+}
+
+pub fn parse_time_micros(value: &serde_json::Value) -> Option<i64> {
+    let time = NaiveTime::parse_from_str(value.as_str()?, "%H:%M:%S%.f").ok()?;
+    Some(i64::from(time.num_seconds_from_midnight()) * 1_000_000 + i64::from(time.nanosecond() / 1_000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oracle_column_kinds() {
+        assert_eq!(column_kind(DatabaseType::Oracle, "NUMBER(10)"), ColumnKind::Int64);
+        assert_eq!(column_kind(DatabaseType::Oracle, "NUMBER(10,2)"), ColumnKind::Decimal);
+        assert_eq!(column_kind(DatabaseType::Oracle, "DATE"), ColumnKind::Timestamp);
+        assert_eq!(
+            column_kind(DatabaseType::Oracle, "TIMESTAMP(6) WITH TIME ZONE"),
+            ColumnKind::TimestampTz
+        );
+    }
+}
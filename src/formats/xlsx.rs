@@ -0,0 +1,171 @@
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use crate::db::types::{CompactResultSet, DatabaseType};
+use crate::formats::typed::{
+    column_kind, parse_bool, parse_date_days, parse_decimal, parse_time_micros, parse_timestamp_micros, text,
+    ColumnKind,
+};
+
+// 엑셀 시트 한 장의 최대 행 수 (헤더 포함)
+const MAX_ROWS: usize = 1_048_576;
+// 1970-01-01 의 엑셀 일련번호
+const UNIX_EPOCH_SERIAL: f64 = 25569.0;
+const MICROS_PER_DAY: f64 = 86_400_000_000.0;
+
+struct CellFormats {
+    header: Format,
+    datetime: Format,
+    date: Format,
+    time: Format,
+}
+
+impl CellFormats {
+    fn new() -> Self {
+        Self {
+            header: Format::new().set_bold(),
+            datetime: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+            date: Format::new().set_num_format("yyyy-mm-dd"),
+            time: Format::new().set_num_format("hh:mm:ss"),
+        }
+    }
+}
+
+// 타입에 맞는 셀을 쓰고, 해석할 수 없는 값은 문자열로 남긴다
+fn write_cell(
+    sheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    kind: ColumnKind,
+    value: &serde_json::Value,
+    formats: &CellFormats,
+) -> Result<(), XlsxError> {
+    if value.is_null() {
+        return Ok(());
+    }
+    let written = match kind {
+        ColumnKind::Boolean => parse_bool(value).map(|v| sheet.write_boolean(row, col, v)),
+        ColumnKind::Int64 | ColumnKind::UInt64 | ColumnKind::Float64 => value
+            .as_f64()
+            .or_else(|| value.as_str()?.parse().ok())
+            .map(|v| sheet.write_number(row, col, v)),
+        ColumnKind::Decimal => parse_decimal(value)
+            .and_then(|v| v.to_string().parse::<f64>().ok())
+            .map(|v| sheet.write_number(row, col, v)),
+        ColumnKind::Timestamp | ColumnKind::TimestampTz => parse_timestamp_micros(value).map(|v| {
+            let serial = UNIX_EPOCH_SERIAL + v as f64 / MICROS_PER_DAY;
+            sheet.write_number_with_format(row, col, serial, &formats.datetime)
+        }),
+        ColumnKind::Date => parse_date_days(value).map(|v| {
+            let serial = UNIX_EPOCH_SERIAL + f64::from(v);
+            sheet.write_number_with_format(row, col, serial, &formats.date)
+        }),
+        ColumnKind::Time => parse_time_micros(value)
+            .map(|v| sheet.write_number_with_format(row, col, v as f64 / MICROS_PER_DAY, &formats.time)),
+        ColumnKind::Binary | ColumnKind::Utf8 => None,
+    };
+    match written {
+        Some(result) => result.map(|_| ()),
+        None => sheet
+            .write_string(row, col, text(value).unwrap_or_default())
+            .map(|_| ()),
+    }
+}
+
+fn write_sheet(
+    sheet: &mut Worksheet,
+    result_set: &CompactResultSet,
+    db_type: DatabaseType,
+    formats: &CellFormats,
+) -> Result<(), XlsxError> {
+    let kinds = result_set
+        .columns
+        .iter()
+        .map(|column| column_kind(db_type, &column.data_type))
+        .collect::<Vec<_>>();
+
+    for (col, column) in result_set.columns.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, &column.name, &formats.header)?;
+    }
+    for (i, row) in result_set.rows.iter().enumerate() {
+        for (col, (value, kind)) in row.iter().zip(&kinds).enumerate() {
+            write_cell(sheet, i as u32 + 1, col as u16, *kind, value, formats)?;
+        }
+    }
+
+    // 헤더 행 고정
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+    Ok(())
+}
+
+// 결과 집합마다 시트 한 장씩 만든다
+pub fn to_workbook(result_sets: &[CompactResultSet], db_type: DatabaseType) -> Result<Vec<u8>, String> {
+    let formats = CellFormats::new();
+    let mut workbook = Workbook::new();
+
+    for (i, result_set) in result_sets.iter().enumerate() {
+        if result_set.rows.len() >= MAX_ROWS {
+            return Err(format!(
+                "Result set {} has {} rows, more than an xlsx sheet can hold",
+                i,
+                result_set.rows.len()
+            ));
+        }
+        let sheet = workbook.add_worksheet();
+        sheet
+            .set_name(format!("Result {}", i + 1))
+            .map_err(|e| e.to_string())?;
+        write_sheet(sheet, result_set, db_type, &formats).map_err(|e| e.to_string())?;
+    }
+    // 결과 집합이 없어도 빈 시트 하나는 있어야 한다
+    if result_sets.is_empty() {
+        workbook.add_worksheet();
+    }
+
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
+
+pub fn xlsx_response(
+    result_sets: &[CompactResultSet],
+    db_type: DatabaseType,
+    mut headers: HeaderMap,
+) -> Result<Response, String> {
+    let body = to_workbook(result_sets, db_type)?;
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_static("attachment; filename=\"result.xlsx\""),
+    );
+
+    Ok((headers, body).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{ColumnMeta, ResultSet};
+    use serde_json::json;
+
+    #[test]
+    fn test_workbook_has_sheet_per_result_set() {
+        let result_set = ResultSet {
+            columns: vec![
+                ColumnMeta { name: "id".into(), data_type: "INT4".into() },
+                ColumnMeta { name: "published".into(), data_type: "DATE".into() },
+                ColumnMeta { name: "available".into(), data_type: "BOOL".into() },
+            ],
+            rows: vec![json!({"id": 1, "published": "2024-03-01", "available": true})],
+            affected_rows: None,
+        };
+
+        let bytes = to_workbook(&[result_set.clone().into(), result_set.into()], DatabaseType::PostgreSQL).unwrap();
+        // xlsx 는 zip 컨테이너다
+        assert_eq!(&bytes[..2], b"PK");
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("xl/worksheets/sheet2.xml"));
+    }
+}
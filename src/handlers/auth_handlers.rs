@@ -0,0 +1,203 @@
+use std::net::SocketAddr;
+use crate::auth::login::{
+    dummy_passwd, find_user, record_attempt, register_failure, reset_failures, store_passwd, LoginAttempt, LoginRequest, RefreshRequest,
+};
+use crate::auth::password::{
+    consume_reset_token, create_reset_token, validate_new_passwd, ChangePasswordRequest, ResetConfirmRequest,
+    ResetRequest,
+};
+use crate::auth::rbac::AccessControl;
+use crate::auth::{AuthMethod, Authenticator, Principal, TokenIssuer, TokenPair};
+use crate::error::AppError;
+use crate::mail::{MailMessage, Mailer};
+use crate::utils::passwd::{passwd_hash, passwd_needs_rehash, passwd_verify, Passwd};
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::{Extension, Json};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+fn token_issuer(authenticator: &Authenticator) -> Result<&TokenIssuer, AppError> {
+    authenticator
+        .issuer()
+        .ok_or_else(|| AppError::database_error("Token issuance is not configured".into()))
+}
+
+// Argon2 는 CPU 를 오래 쓰므로 블로킹 스레드에서 돌린다
+async fn hash_passwd(plain: &str) -> Result<Passwd, AppError> {
+    let plain = plain.to_string();
+    tokio::task::spawn_blocking(move || passwd_hash(&plain))
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?
+        .map_err(|e| AppError::database_error(e.to_string()))
+}
+
+async fn verify_passwd(plain: &str, hash: &str, salt: &str) -> Result<bool, AppError> {
+    let (plain, hash, salt) = (plain.to_string(), hash.to_string(), salt.to_string());
+    tokio::task::spawn_blocking(move || passwd_verify(&plain, &hash, &salt))
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))
+}
+
+// 가입되지 않은 이메일도 가입된 경우와 같은 시간을 쓰도록 더미 해시로 확인한다
+async fn verify_dummy_passwd(plain: &str) -> Result<(), AppError> {
+    let plain = plain.to_string();
+    tokio::task::spawn_blocking(move || {
+        let dummy = dummy_passwd();
+        passwd_verify(&plain, &dummy.wd, &dummy.salt)
+    })
+    .await
+    .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(())
+}
+
+// 이메일과 비밀번호를 확인해 액세스/리프레시 토큰을 발급한다. 모든 시도는 login_log 에 남긴다.
+// 토큰의 roles 에는 사용자의 전역 권한을 넣는다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn login(
+    State(authenticator): State<Authenticator>,
+    State(access): State<AccessControl>,
+    Extension(pool): Extension<PgPool>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Json<TokenPair>, AppError> {
+    let issuer = token_issuer(&authenticator)?;
+    let mut attempt = LoginAttempt {
+        email: payload.email.clone(),
+        ip_address: Some(addr.ip().to_string()),
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(255).collect()),
+        ..Default::default()
+    };
+
+    let Some(user) = find_user(&pool, &payload.email).await? else {
+        verify_dummy_passwd(&payload.passwd).await?;
+        attempt.reason = Some("unknown_user");
+        record_attempt(&pool, &attempt).await?;
+        return Err(AppError::unauthorized("Invalid email or password".into()));
+    };
+    attempt.user_id = Some(user.id);
+
+    // 잠긴 계정은 비밀번호를 확인하지 않는다
+    if user.locked {
+        attempt.reason = Some("locked");
+        record_attempt(&pool, &attempt).await?;
+        return Err(AppError::locked("Account is locked".into()));
+    }
+
+    if !verify_passwd(&payload.passwd, &user.passwd, &user.salt_key).await? {
+        let locked = register_failure(&pool, user.id, authenticator.login_policy()).await?;
+        attempt.reason = Some(if locked { "locked_now" } else { "bad_password" });
+        record_attempt(&pool, &attempt).await?;
+        if locked {
+            warn!("Locked user {} after repeated login failures", user.id);
+        }
+        return Err(AppError::unauthorized("Invalid email or password".into()));
+    }
+
+    reset_failures(&pool, user.id).await?;
+    if passwd_needs_rehash(&user.passwd) {
+        match hash_passwd(&payload.passwd).await {
+            Ok(passwd) => {
+                store_passwd(&pool, user.id, &passwd).await?;
+                info!("Upgraded password hash for user {}", user.id);
+            }
+            // 다시 해시하지 못해도 로그인은 성공시키고 다음 로그인 때 다시 시도한다
+            Err(e) => warn!("Failed to rehash password for user {}: {}", user.id, e.message),
+        }
+    }
+    attempt.success = true;
+    record_attempt(&pool, &attempt).await?;
+    info!("User {} logged in", user.id);
+    Ok(Json(issuer.issue(&user.email, &access.global_roles(&user.email).await)?))
+}
+
+// 리프레시 토큰으로 새 토큰 쌍을 발급한다. 그 사이 잠기거나 삭제된 계정은 거부하고 역할은 다시 읽는다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn refresh_token(
+    State(authenticator): State<Authenticator>,
+    State(access): State<AccessControl>,
+    Extension(pool): Extension<PgPool>,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<Json<TokenPair>, AppError> {
+    let issuer = token_issuer(&authenticator)?;
+    let claims = authenticator
+        .jwt()
+        .verify(&payload.refresh_token)
+        .map_err(|e| AppError::unauthorized(format!("Invalid token: {}", e)))?;
+    if !claims.is_refresh() {
+        return Err(AppError::unauthorized("Expected a refresh token".into()));
+    }
+    match find_user(&pool, &claims.sub).await? {
+        Some(user) if !user.locked => Ok(Json(issuer.issue(&user.email, &access.global_roles(&user.email).await)?)),
+        Some(_) => Err(AppError::locked("Account is locked".into())),
+        None => Err(AppError::unauthorized("Invalid token".into())),
+    }
+}
+
+// 로그인한 사용자가 현재 비밀번호를 확인한 뒤 새 비밀번호로 바꾼다
+pub async fn change_password(
+    principal: Principal,
+    Extension(pool): Extension<PgPool>,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    if principal.method != AuthMethod::Jwt {
+        return Err(AppError::validation_error("Password change requires a user login".into()));
+    }
+    validate_new_passwd(&payload.new_passwd).map_err(AppError::validation_error)?;
+    let user = find_user(&pool, &principal.subject)
+        .await?
+        .ok_or_else(|| AppError::unauthorized("Unknown user".into()))?;
+    if !verify_passwd(&payload.old_passwd, &user.passwd, &user.salt_key).await? {
+        return Err(AppError::unauthorized("Current password is incorrect".into()));
+    }
+
+    store_passwd(&pool, user.id, &hash_passwd(&payload.new_passwd).await?).await?;
+    info!("User {} changed password", user.id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 재설정 토큰을 메일로 보낸다. 가입 여부가 드러나지 않도록 항상 202 를 돌려준다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn request_password_reset(
+    State(authenticator): State<Authenticator>,
+    State(mailer): State<Mailer>,
+    Extension(pool): Extension<PgPool>,
+    Json(payload): Json<ResetRequest>,
+) -> Result<StatusCode, AppError> {
+    if let Some(user) = find_user(&pool, &payload.email).await? {
+        let policy = authenticator.reset_policy();
+        let token = create_reset_token(&pool, user.id, policy).await?;
+        let message = MailMessage {
+            to: user.email.clone(),
+            subject: "Password reset".into(),
+            body: policy.mail_body(&token),
+        };
+        match mailer.send(&message).await {
+            Ok(()) => info!("Sent password reset mail to user {}", user.id),
+            Err(e) => warn!("Failed to send password reset mail to user {}: {}", user.id, e),
+        }
+    }
+    Ok(StatusCode::ACCEPTED)
+}
+
+// 재설정 토큰으로 비밀번호를 바꾸고 로그인 잠금도 푼다
+pub async fn confirm_password_reset(
+    Extension(pool): Extension<PgPool>,
+    Json(payload): Json<ResetConfirmRequest>,
+) -> Result<StatusCode, AppError> {
+    validate_new_passwd(&payload.new_passwd).map_err(AppError::validation_error)?;
+    // 해시를 먼저 만들어 두어 토큰을 쓴 뒤 실패하는 경우를 줄인다
+    let passwd = hash_passwd(&payload.new_passwd).await?;
+    let user_id = consume_reset_token(&pool, &payload.token)
+        .await?
+        .ok_or_else(|| AppError::validation_error("Invalid or expired reset token".into()))?;
+
+    store_passwd(&pool, user_id, &passwd).await?;
+    reset_failures(&pool, user_id).await?;
+    info!("User {} reset password", user_id);
+    Ok(StatusCode::NO_CONTENT)
+}
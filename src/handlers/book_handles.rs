@@ -1,14 +1,18 @@
+use crate::auth::{AccessControl, Permission, Principal};
 use crate::error::AppError;
 use crate::models::book_models::{Book, CreateBook};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::{extract::Path, Extension, Json};
+use axum::{extract::{Path, State}, Extension, Json};
 use sqlx::{query_as, PgPool};
 
 pub async fn create_book(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Json(payload): Json<CreateBook>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::Write, None).await?;
     if payload.title.is_empty() || payload.author.is_empty() {
         return Err(AppError::validation_error(
             "제목과 저자는 필수 항목입니다.".into(),
@@ -26,7 +30,12 @@ pub async fn create_book(
     Ok((StatusCode::CREATED, Json(rec)))
 }
 
-pub async fn get_books(Extension(pool): Extension<PgPool>) -> Result<impl IntoResponse, AppError> {
+pub async fn get_books(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::Read, None).await?;
     let recs: Vec<_> = query_as::<_, Book>("SELECT id, title, author FROM books")
         .fetch_all(&pool)
         .await?;
@@ -34,9 +43,12 @@ pub async fn get_books(Extension(pool): Extension<PgPool>) -> Result<impl IntoRe
 }
 
 pub async fn get_book(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::Read, None).await?;
     let rec = sqlx::query_as::<_, Book>("SELECT id, title, author FROM books WHERE id = $1")
         .bind(id)
         .fetch_one(&pool)
@@ -46,10 +58,13 @@ pub async fn get_book(
 }
 
 pub async fn update_book(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
     Json(payload): Json<CreateBook>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::Write, None).await?;
     if payload.title.is_empty() || payload.author.is_empty() {
         return Err(AppError::validation_error(
             "제목과 저자는 필수 항목입니다.".into(),
@@ -68,9 +83,12 @@ pub async fn update_book(
 }
 
 pub async fn delete_book(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::Write, None).await?;
     let result = sqlx::query("DELETE FROM books WHERE id = $1")
         .bind(id)
         .execute(&pool)
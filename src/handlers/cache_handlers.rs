@@ -0,0 +1,51 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::cache::QueryCache;
+use crate::error::AppError;
+use axum::{Json, extract::{Path, State}};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct InvalidateResponse {
+    pub invalidated: u64,
+}
+
+// 모든 연결의 캐시를 지우므로 전역 쓰기 권한이 필요하다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn invalidate_all(
+    principal: Principal,
+    State(cache): State<QueryCache>,
+    State(access): State<AccessControl>,
+) -> Result<Json<InvalidateResponse>, AppError> {
+    access.authorize(&principal, Permission::Write, None).await?;
+    let invalidated = cache.invalidate_all().await?;
+    Ok(Json(InvalidateResponse { invalidated }))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn invalidate_connection(
+    principal: Principal,
+    State(cache): State<QueryCache>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<Json<InvalidateResponse>, AppError> {
+    access.authorize(&principal, Permission::Write, Some(&connection_id)).await?;
+    let invalidated = cache.invalidate_connection(&connection_id).await?;
+    Ok(Json(InvalidateResponse { invalidated }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_invalidation_requires_write() {
+        let access = owned_by_alice(&["c1"]).await;
+        let cache = QueryCache::default();
+        assert_forbidden(
+            invalidate_connection(user("mallory"), State(cache.clone()), State(access.clone()), Path("c1".into())).await,
+        );
+        // 소유자라도 전역 권한이 없으면 모든 캐시를 지울 수 없다
+        assert_forbidden(invalidate_all(user("alice"), State(cache), State(access)).await);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::auth::{AccessControl, Permission, Principal, Subject};
 use crate::db::connection_manager::ConnectionManager;
+use crate::db::policy::SqlPolicy;
 use crate::db::types::{ConnectionInfo, PoolOptions as DbPoolOptions, DatabaseType};
 use crate::error::AppError;
 use axum::response::IntoResponse;
-use axum::{Extension, Json, extract::State};
+use axum::http::StatusCode;
+use axum::{Json, extract::{Path, State}};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -59,6 +62,13 @@ pub struct CreateConnectionRequest {
     pub username: Option<String>,
     pub password: Option<String>,
     pub pool_options: DbPoolOptions,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub policy: Option<SqlPolicy>,
+    // 지정하면 등록한 사용자 대신 팀이 연결을 소유한다
+    #[serde(default)]
+    pub team: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,11 +77,20 @@ pub struct ConnectionResponse {
     pub connection_string: String,
 }
 
-#[axum::debug_handler]
+#[axum::debug_handler(state = crate::state::AppState)]
 pub async fn create_connection(
+    principal: Principal,
     State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
     Json(payload): Json<CreateConnectionRequest>,
 ) -> Result<Json<ConnectionResponse>, AppError> {
+    access.authorize(&principal, Permission::RegisterConnection, None).await?;
+    let owner = match &payload.team {
+        Some(team) if access.is_team_member(team, &principal.subject).await => Subject::Team(team.clone()),
+        Some(team) => return Err(AppError::forbidden(format!("{} is not a member of team {}", principal.subject, team))),
+        None => Subject::User(principal.subject.clone()),
+    };
+
     let db_type = match payload.db_type.to_uppercase().as_str() {
         "POSTGRESQL" => DatabaseType::PostgreSQL,
         "ORACLE" => DatabaseType::Oracle,
@@ -88,23 +107,89 @@ pub async fn create_connection(
         username: payload.username,
         password: payload.password,
         pool_options: payload.pool_options,
+        read_only: payload.read_only,
+        policy: payload.policy,
     };
 
     let id = manager.add_connection(connection_info).await?;
-    
+    access.set_owner(&id, owner).await;
+
     Ok(Json(ConnectionResponse {
         id,
         connection_string,
     }))
 }
 
+#[axum::debug_handler(state = crate::state::AppState)]
 pub async fn delete_connection(
-    Extension(manager): Extension<ConnectionManager>,
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
     Json(payload): Json<ConnectionIdRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    access
+        .authorize(&principal, Permission::ManageConnection, Some(&payload.connection_id))
+        .await?;
     manager.remove_connection(&payload.connection_id).await;
+    access.forget_connection(&payload.connection_id).await;
     Ok(Json(ConnectionResponse { 
         id: payload.connection_id,
         connection_string: String::new() 
     }))
 }
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn get_policy(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<Json<SqlPolicy>, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&connection_id)).await?;
+    manager
+        .get_connection(&connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    manager
+        .policy(&connection_id)
+        .await
+        .map(Json)
+        .ok_or_else(|| AppError::not_found("Connection has no SQL policy".into()))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn set_policy(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+    Json(policy): Json<SqlPolicy>,
+) -> Result<Json<SqlPolicy>, AppError> {
+    access
+        .authorize(&principal, Permission::ManageConnection, Some(&connection_id))
+        .await?;
+    manager
+        .get_connection(&connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    manager.set_policy(&connection_id, Some(policy.clone())).await;
+    Ok(Json(policy))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn delete_policy(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    access
+        .authorize(&principal, Permission::ManageConnection, Some(&connection_id))
+        .await?;
+    manager
+        .get_connection(&connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    manager.set_policy(&connection_id, None).await;
+    Ok(StatusCode::NO_CONTENT)
+}
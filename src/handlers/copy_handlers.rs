@@ -0,0 +1,239 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::copy::CopyRequest;
+use crate::db::copy_manager::{CopyInfo, CopyManager};
+use crate::db::job_manager::JobStatus;
+use crate::error::AppError;
+use crate::handlers::authorize_sql;
+use axum::http::StatusCode;
+use axum::{Json, extract::{Path, State}};
+use serde::Serialize;
+use tracing::info;
+
+#[derive(Debug, Serialize)]
+pub struct SubmitCopyResponse {
+    pub copy_id: String,
+    pub status: JobStatus,
+}
+
+// 원본은 읽기, 대상은 쓰기 권한이 필요하다
+async fn authorize_copy(
+    access: &AccessControl,
+    principal: &Principal,
+    source_connection_id: &str,
+    target_connection_id: &str,
+) -> Result<(), AppError> {
+    access.authorize(principal, Permission::Read, Some(source_connection_id)).await?;
+    access.authorize(principal, Permission::Write, Some(target_connection_id)).await
+}
+
+// 복사 작업은 제출한 사용자나 대상 연결의 관리자만 볼 수 있다
+async fn authorized_copy(
+    copies: &CopyManager,
+    access: &AccessControl,
+    principal: &Principal,
+    id: &str,
+) -> Result<CopyInfo, AppError> {
+    let copy = copies
+        .get_copy(id)
+        .await
+        .ok_or_else(|| AppError::not_found("Copy not found".into()))?;
+    authorize_copy(access, principal, &copy.source_connection_id, &copy.target_connection_id).await?;
+    if copy.submitted_by != principal.subject {
+        access
+            .authorize(principal, Permission::ManageConnection, Some(&copy.target_connection_id))
+            .await?;
+    }
+    Ok(copy)
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn submit_copy(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(copies): State<CopyManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<CopyRequest>,
+) -> Result<(StatusCode, Json<SubmitCopyResponse>), AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    access.authorize(&principal, Permission::Write, Some(&payload.target_connection_id)).await?;
+    // 원본 쿼리는 /sql 과 같이 분류하고 원본 연결의 읽기 전용 설정과 SQL 정책을 따른다
+    let (source, _) =
+        authorize_sql(&manager, &access, &principal, &payload.source_connection_id, &payload.source.query()).await?;
+    info!(
+        "Submitting copy from connection {} to {}.{}",
+        payload.source_connection_id, payload.target_connection_id, payload.target_table
+    );
+    let target = manager
+        .get_connection(&payload.target_connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid target connection ID".into()))?;
+    if target.read_only() {
+        return Err(AppError::forbidden("Target connection is read-only".into()));
+    }
+    payload
+        .validate_connections(source.db_type())
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    // 대상은 INSERT (create_table 이면 CREATE TABLE 도) 와 같은 규칙으로 검사한다
+    manager
+        .check_table_write_policy(&payload.target_connection_id, &payload.target_table, payload.create_table)
+        .await?;
+
+    let copy_id = copies.submit(payload, principal.subject, source, target).await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SubmitCopyResponse {
+            copy_id,
+            status: JobStatus::Queued,
+        }),
+    ))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn get_copy(
+    principal: Principal,
+    State(copies): State<CopyManager>,
+    State(access): State<AccessControl>,
+    Path(id): Path<String>,
+) -> Result<Json<CopyInfo>, AppError> {
+    authorized_copy(&copies, &access, &principal, &id).await.map(Json)
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn cancel_copy(
+    principal: Principal,
+    State(copies): State<CopyManager>,
+    State(access): State<AccessControl>,
+    Path(id): Path<String>,
+) -> Result<Json<CopyInfo>, AppError> {
+    authorized_copy(&copies, &access, &principal, &id).await?;
+    copies
+        .cancel(&id)
+        .await
+        .ok_or_else(|| AppError::not_found("Copy not found".into()))?;
+    copies
+        .get_copy(&id)
+        .await
+        .map(Json)
+        .ok_or_else(|| AppError::not_found("Copy not found".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::CreateGrantRequest;
+    use crate::auth::{Role, Subject};
+    use crate::db::connection::DatabaseConnection;
+    use crate::db::policy::{SqlPolicy, StatementType};
+    use crate::handlers::test_support::{assert_forbidden, assert_policy_rejected, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_copies_are_visible_to_submitter_only() {
+        let manager = ConnectionManager::new();
+        let source = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let target = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let access = owned_by_alice(&[&source, &target]).await;
+        let copies = CopyManager::default();
+        let submit = |principal| {
+            let payload = serde_json::from_value(serde_json::json!({
+                "source_connection_id": source,
+                "table": "books",
+                "target_connection_id": target,
+                "target_table": "books_copy",
+            }))
+            .unwrap();
+            submit_copy(principal, State(manager.clone()), State(copies.clone()), State(access.clone()), Json(payload))
+        };
+
+        assert_forbidden(submit(user("mallory")).await);
+        // 원본만 읽을 수 있으면 복사할 수 없다
+        let request = CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Viewer, connection_id: Some(source.clone()) };
+        access.grant(request, "test").await;
+        assert_forbidden(submit(user("bob")).await);
+
+        let (_, Json(submitted)) = submit(user("alice")).await.unwrap();
+        let copy_id = submitted.copy_id;
+        // 두 연결에 쓸 수 있어도 남이 제출한 복사는 볼 수 없다
+        access
+            .grant(CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Analyst, connection_id: None }, "test")
+            .await;
+        assert_forbidden(get_copy(user("bob"), State(copies.clone()), State(access.clone()), Path(copy_id.clone())).await);
+        assert_forbidden(cancel_copy(user("bob"), State(copies.clone()), State(access.clone()), Path(copy_id.clone())).await);
+
+        let Json(info) = get_copy(user("alice"), State(copies), State(access), Path(copy_id)).await.unwrap();
+        assert_eq!(info.submitted_by, "alice");
+    }
+
+    #[tokio::test]
+    async fn test_copy_refused_into_read_only_target() {
+        let manager = ConnectionManager::new();
+        let source = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let target = manager.insert_connection(DatabaseConnection::unreachable(true)).await;
+        let access = owned_by_alice(&[&source, &target]).await;
+        let payload = serde_json::from_value(serde_json::json!({
+            "source_connection_id": source,
+            "table": "books",
+            "target_connection_id": target,
+            "target_table": "books_copy",
+        }))
+        .unwrap();
+        assert_forbidden(
+            submit_copy(user("alice"), State(manager), State(CopyManager::default()), State(access), Json(payload)).await,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_source_must_be_a_read() {
+        let manager = ConnectionManager::new();
+        let source = manager.insert_connection(DatabaseConnection::unreachable(true)).await;
+        let target = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let access = owned_by_alice(&[&source, &target]).await;
+        for query in ["DELETE FROM books", "SELECT * FROM books; DROP TABLE books"] {
+            let payload = serde_json::from_value(serde_json::json!({
+                "source_connection_id": source,
+                "query": query,
+                "target_connection_id": target,
+                "target_table": "books_copy",
+            }))
+            .unwrap();
+            let result =
+                submit_copy(user("alice"), State(manager.clone()), State(CopyManager::default()), State(access.clone()), Json(payload)).await;
+            assert_eq!(result.err().map(|e| e.status_code), Some(StatusCode::BAD_REQUEST));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_refused_by_policy() {
+        let manager = ConnectionManager::new();
+        let source = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let target = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let access = owned_by_alice(&[&source, &target]).await;
+        manager
+            .set_policy(&source, Some(SqlPolicy { denied_tables: vec!["salaries".into()], ..Default::default() }))
+            .await;
+        let target_policy = SqlPolicy {
+            allowed_statements: Some(vec![StatementType::Select, StatementType::Insert]),
+            denied_tables: vec!["audit_log".into()],
+            ..Default::default()
+        };
+        manager.set_policy(&target, Some(target_policy)).await;
+        let submit = |request: serde_json::Value| {
+            let mut payload = serde_json::json!({ "source_connection_id": source, "target_connection_id": target });
+            payload.as_object_mut().unwrap().extend(request.as_object().unwrap().clone());
+            let payload = serde_json::from_value(payload).unwrap();
+            submit_copy(user("alice"), State(manager.clone()), State(CopyManager::default()), State(access.clone()), Json(payload))
+        };
+
+        assert_policy_rejected(submit(serde_json::json!({ "table": "salaries", "target_table": "books_copy" })).await, "denied_tables");
+        assert_policy_rejected(submit(serde_json::json!({ "table": "books", "target_table": "audit_log" })).await, "denied_tables");
+        // 대상 정책이 CREATE 를 허용하지 않으면 테이블을 만들 수 없다
+        assert_policy_rejected(
+            submit(serde_json::json!({ "table": "books", "target_table": "books_copy", "create_table": true })).await,
+            "allowed_statements",
+        );
+        assert!(submit(serde_json::json!({ "table": "books", "target_table": "books_copy" })).await.is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::ddl::{load_table, render_ddl};
+use crate::db::types::DatabaseType;
+use crate::error::AppError;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+pub struct DdlQuery {
+    pub schema: Option<String>,
+    // 없으면 원본 연결의 방언
+    pub dialect: Option<DatabaseType>,
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn table_ddl(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path((connection_id, table)): Path<(String, String)>,
+    Query(query): Query<DdlQuery>,
+) -> Result<Response, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&connection_id)).await?;
+    let connection = manager
+        .get_connection(&connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    let source = connection.db_type();
+    let target = query.dialect.unwrap_or(source);
+    if target == DatabaseType::Redis {
+        return Err(AppError::validation_error("Redis is not a SQL dialect".into()));
+    }
+    info!("Generating {:?} DDL for {} with connection ID: {}", target, table, connection_id);
+
+    let definition = load_table(&connection, query.schema.as_deref(), &table)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?
+        .ok_or_else(|| AppError::not_found(format!("Table not found: {}", table)))?;
+
+    Ok((
+        [(header::CONTENT_TYPE, HeaderValue::from_static("application/sql; charset=utf-8"))],
+        render_ddl(&definition, source, target),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_ddl_requires_read() {
+        let access = owned_by_alice(&["c1"]).await;
+        let query = DdlQuery { schema: None, dialect: None };
+        assert_forbidden(
+            table_ddl(user("mallory"), State(ConnectionManager::new()), State(access), Path(("c1".into(), "books".into())), Query(query))
+                .await,
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::auth::{AccessControl, Principal};
+use crate::db::connection::Connection;
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::dump::{render_dump, DumpRequest};
+use crate::db::types::CompactResultSet;
+use crate::error::AppError;
+use crate::handlers::authorize_sql;
+use axum::extract::State;
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use tracing::info;
+
+// 테이블이나 쿼리 결과를 대상 방언의 SQL 스크립트(CREATE TABLE + INSERT)로 내려준다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn dump_sql(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<DumpRequest>,
+) -> Result<Response, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    let query = payload.source.query();
+    let (connection, _) = authorize_sql(&manager, &access, &principal, &payload.connection_id, &query).await?;
+    info!(
+        "Dumping from connection {} as {:?} SQL",
+        payload.connection_id, payload.dialect
+    );
+
+    let result_set: CompactResultSet = connection
+        .execute_result_sets(&query)
+        .await?
+        .into_iter()
+        .find(|result_set| !result_set.columns.is_empty())
+        .ok_or_else(|| AppError::validation_error("Source query returned no result set".into()))?
+        .into();
+    let script = render_dump(&payload, &result_set, connection.db_type()).map_err(AppError::validation_error)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/sql; charset=utf-8")),
+            (header::CONTENT_DISPOSITION, HeaderValue::from_static("attachment; filename=\"dump.sql\"")),
+        ],
+        script,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::CreateGrantRequest;
+    use crate::auth::{Role, Subject};
+    use crate::db::connection::DatabaseConnection;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_dump_requires_read() {
+        let access = owned_by_alice(&["c1"]).await;
+        let payload: DumpRequest =
+            serde_json::from_value(serde_json::json!({ "connection_id": "c1", "table": "books", "dialect": "PostgreSQL" })).unwrap();
+        assert_forbidden(dump_sql(user("mallory"), State(ConnectionManager::new()), State(access), Json(payload)).await);
+    }
+
+    #[tokio::test]
+    async fn test_dump_refuses_viewer_write_query() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let access = owned_by_alice(&[&id]).await;
+        let request = CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Viewer, connection_id: Some(id.clone()) };
+        access.grant(request, "test").await;
+        for query in ["DELETE FROM books", "SELECT 1; DROP TABLE books", "SELECT * INTO backup FROM books"] {
+            let payload: DumpRequest = serde_json::from_value(serde_json::json!({
+                "connection_id": id,
+                "query": query,
+                "target_table": "books",
+                "dialect": "PostgreSQL",
+            }))
+            .unwrap();
+            let result = dump_sql(user("bob"), State(manager.clone()), State(access.clone()), Json(payload)).await;
+            assert_eq!(result.err().map(|e| e.status_code), Some(StatusCode::BAD_REQUEST));
+        }
+    }
+}
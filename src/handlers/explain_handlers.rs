@@ -0,0 +1,62 @@
+use crate::auth::{AccessControl, Principal};
+use crate::db::connection::Connection;
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::explain::PlanNode;
+use crate::db::types::DatabaseType;
+use crate::error::AppError;
+use crate::handlers::authorize_sql;
+use axum::response::IntoResponse;
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+pub struct ExplainRequest {
+    pub query: String,
+    pub connection_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplainResponse {
+    pub db_type: DatabaseType,
+    pub raw: serde_json::Value,
+    pub plan: Vec<PlanNode>,
+}
+
+// 실행 계획만 만들고 문장은 실행하지 않으므로 읽기 권한으로 충분하다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn explain_sql(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<ExplainRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("Explaining SQL query with connection ID: {}", payload.connection_id);
+    // 드라이버가 앞에 붙이는 EXPLAIN 까지 포함해 분류한다. 뒤에 이어 붙인 문장은 실제로 실행된다
+    let explained_sql = format!("EXPLAIN {}", payload.query);
+    let (connection, _) = authorize_sql(&manager, &access, &principal, &payload.connection_id, &explained_sql).await?;
+
+    let explained = connection
+        .explain(&payload.query)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+
+    Ok(Json(ExplainResponse {
+        db_type: connection.db_type(),
+        raw: explained.raw,
+        plan: explained.plan,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_explain_requires_read() {
+        let access = owned_by_alice(&["c1"]).await;
+        let payload = ExplainRequest { query: "SELECT 1".into(), connection_id: "c1".into() };
+        assert_forbidden(explain_sql(user("mallory"), State(ConnectionManager::new()), State(access), Json(payload)).await);
+    }
+}
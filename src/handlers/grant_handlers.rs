@@ -0,0 +1,101 @@
+use crate::auth::rbac::{CreateGrantRequest, Grant, Team};
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::error::AppError;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use tracing::info;
+
+// 전역 권한은 ManageUsers, 연결 권한은 그 연결의 ManageConnection 이 있어야 다룰 수 있다
+async fn authorize_scope(
+    access: &AccessControl,
+    principal: &Principal,
+    connection_id: Option<&str>,
+) -> Result<(), AppError> {
+    match connection_id {
+        Some(id) => access.authorize(principal, Permission::ManageConnection, Some(id)).await,
+        None => access.authorize(principal, Permission::ManageUsers, None).await,
+    }
+}
+
+pub async fn list_grants(
+    principal: Principal,
+    State(access): State<AccessControl>,
+) -> Result<Json<Vec<Grant>>, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
+    Ok(Json(access.list_grants(None).await))
+}
+
+pub async fn list_connection_grants(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<Json<Vec<Grant>>, AppError> {
+    access.authorize(&principal, Permission::ManageConnection, Some(&connection_id)).await?;
+    Ok(Json(access.list_grants(Some(&connection_id)).await))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn create_grant(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    State(manager): State<ConnectionManager>,
+    Json(payload): Json<CreateGrantRequest>,
+) -> Result<(StatusCode, Json<Grant>), AppError> {
+    authorize_scope(&access, &principal, payload.connection_id.as_deref()).await?;
+    if let Some(connection_id) = &payload.connection_id {
+        manager
+            .get_connection(connection_id)
+            .await
+            .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    }
+    let grant = access.grant(payload, &principal.subject).await;
+    info!("{} granted {:?} to {:?}", principal.subject, grant.role, grant.subject);
+    Ok((StatusCode::CREATED, Json(grant)))
+}
+
+pub async fn delete_grant(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    Path(grant_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let grant = access
+        .get_grant(&grant_id)
+        .await
+        .ok_or_else(|| AppError::not_found("Grant not found".into()))?;
+    authorize_scope(&access, &principal, grant.connection_id.as_deref()).await?;
+    access.revoke(&grant_id).await;
+    info!("{} revoked grant {}", principal.subject, grant_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_teams(
+    principal: Principal,
+    State(access): State<AccessControl>,
+) -> Result<Json<Vec<Team>>, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
+    Ok(Json(access.list_teams().await))
+}
+
+pub async fn add_team_member(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    Path((team, subject)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
+    access.add_team_member(&team, &subject).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_team_member(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    Path((team, subject)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
+    if !access.remove_team_member(&team, &subject).await {
+        return Err(AppError::not_found("Team member not found".into()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,116 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::import::{import_stream, ImportOptions, ImportReport};
+use crate::error::AppError;
+use axum::extract::{Multipart, Path, State};
+use axum::Json;
+use tracing::info;
+
+// multipart/form-data: "options" (JSON) 파트 다음에 "file" 파트를 보낸다.
+// file 파트는 메모리에 모으지 않고 읽는 대로 배치 단위로 적재한다.
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn import_data(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<ImportReport>, AppError> {
+    access.authorize(&principal, Permission::Write, Some(&connection_id)).await?;
+    let connection = manager
+        .get_connection(&connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    if connection.read_only() {
+        return Err(AppError::forbidden("Connection is read-only".into()));
+    }
+
+    let mut options: Option<ImportOptions> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::validation_error(e.body_text()))?
+    {
+        match field.name() {
+            Some("options") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::validation_error(e.body_text()))?;
+                let parsed: ImportOptions = serde_json::from_str(&text)
+                    .map_err(|e| AppError::validation_error(format!("Invalid import options: {}", e)))?;
+                parsed
+                    .validate()
+                    .map_err(|e| AppError::validation_error(e.to_string()))?;
+                // SQL 없이 INSERT 하므로 대상 테이블을 INSERT 와 같은 규칙으로 검사한다
+                manager
+                    .check_table_write_policy(&connection_id, &parsed.table, false)
+                    .await?;
+                options = Some(parsed);
+            }
+            Some("file") => {
+                let options = options
+                    .as_ref()
+                    .ok_or_else(|| AppError::validation_error("The options part must come before the file part".into()))?;
+                info!("Importing into {} with connection ID: {}", options.table, connection_id);
+                let report = import_stream(&connection, options, field).await?;
+                return Ok(Json(report));
+            }
+            _ => {}
+        }
+    }
+
+    Err(AppError::validation_error("Missing file part".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::DatabaseConnection;
+    use crate::db::policy::SqlPolicy;
+    use crate::handlers::test_support::{assert_forbidden, assert_policy_rejected, owned_by_alice, user};
+    use axum::body::Body;
+    use axum::extract::{FromRequest, Request};
+    use axum::http::header;
+
+    #[tokio::test]
+    async fn test_import_requires_write() {
+        let access = owned_by_alice(&["c1"]).await;
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=x")
+            .body(Body::empty())
+            .unwrap();
+        let multipart = Multipart::from_request(request, &()).await.unwrap();
+        assert_forbidden(
+            import_data(user("mallory"), State(ConnectionManager::new()), State(access), Path("c1".into()), multipart).await,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_refused_by_policy() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        manager
+            .set_policy(&id, Some(SqlPolicy { denied_schemas: vec!["hr".into()], ..Default::default() }))
+            .await;
+        let access = owned_by_alice(&[&id]).await;
+        let body = concat!(
+            "--x\r\n",
+            "Content-Disposition: form-data; name=\"options\"\r\n\r\n",
+            "{\"table\": \"hr.salaries\", \"format\": \"csv\"}\r\n",
+            "--x\r\n",
+            "Content-Disposition: form-data; name=\"file\"; filename=\"salaries.csv\"\r\n\r\n",
+            "id,amount\r\n1,100\r\n",
+            "--x--\r\n",
+        );
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=x")
+            .body(Body::from(body))
+            .unwrap();
+        let multipart = Multipart::from_request(request, &()).await.unwrap();
+        assert_policy_rejected(
+            import_data(user("alice"), State(manager), State(access), Path(id), multipart).await,
+            "denied_schemas",
+        );
+    }
+}
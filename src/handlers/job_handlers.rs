@@ -0,0 +1,206 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::job_manager::{JobInfo, JobManager, JobStatus, ResultPage};
+use crate::error::AppError;
+use crate::handlers::authorize_sql;
+use crate::formats::csv::CsvOptions;
+use crate::formats::{export_response, OutputFormat};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use axum::{Json, extract::{Path, Query, State}};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitJobRequest {
+    pub query: String,
+    pub connection_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmitJobResponse {
+    pub job_id: String,
+    pub status: JobStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResultPageQuery {
+    #[serde(default)]
+    pub result_set: usize,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    // 없으면 Accept 헤더로 결정한다
+    pub format: Option<OutputFormat>,
+    #[serde(default)]
+    pub result_set: usize,
+}
+
+fn default_limit() -> usize {
+    100
+}
+
+// 작업은 제출한 사용자나 그 연결의 관리자만 볼 수 있다. 제출한 뒤 연결 권한을 잃었으면 거부한다
+async fn authorized_job(
+    jobs: &JobManager,
+    access: &AccessControl,
+    principal: &Principal,
+    id: &str,
+) -> Result<JobInfo, AppError> {
+    let job = jobs
+        .get_job(id)
+        .await
+        .ok_or_else(|| AppError::not_found("Job not found".into()))?;
+    access.authorize(principal, Permission::Read, Some(&job.connection_id)).await?;
+    if job.submitted_by != principal.subject {
+        access
+            .authorize(principal, Permission::ManageConnection, Some(&job.connection_id))
+            .await?;
+    }
+    Ok(job)
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn submit_job(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(jobs): State<JobManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<SubmitJobRequest>,
+) -> Result<(StatusCode, Json<SubmitJobResponse>), AppError> {
+    info!("Submitting SQL job with connection ID: {}", payload.connection_id);
+    let (connection, _) = authorize_sql(&manager, &access, &principal, &payload.connection_id, &payload.query).await?;
+
+    let job_id = jobs
+        .submit(payload.connection_id, principal.subject, connection, payload.query)
+        .await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SubmitJobResponse {
+            job_id,
+            status: JobStatus::Queued,
+        }),
+    ))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn get_job(
+    principal: Principal,
+    State(jobs): State<JobManager>,
+    State(access): State<AccessControl>,
+    Path(id): Path<String>,
+) -> Result<Json<JobInfo>, AppError> {
+    authorized_job(&jobs, &access, &principal, &id).await.map(Json)
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn get_job_results(
+    principal: Principal,
+    State(jobs): State<JobManager>,
+    State(access): State<AccessControl>,
+    Path(id): Path<String>,
+    Query(page): Query<ResultPageQuery>,
+) -> Result<Json<ResultPage>, AppError> {
+    authorized_job(&jobs, &access, &principal, &id).await?;
+    let limit = page.limit.clamp(1, 10_000);
+    jobs.get_results(&id, page.result_set, page.offset, limit)
+        .await
+        .map(Json)
+        .map_err(|e| AppError::validation_error(e.to_string()))
+}
+
+// 완료된 작업의 결과를 파일로 내려받는다 (페이지 없이 전체)
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn export_job_results(
+    principal: Principal,
+    State(jobs): State<JobManager>,
+    State(access): State<AccessControl>,
+    Path(id): Path<String>,
+    Query(export): Query<ExportQuery>,
+    request_headers: HeaderMap,
+) -> Result<Response, AppError> {
+    authorized_job(&jobs, &access, &principal, &id).await?;
+    let (db_type, result_sets) = jobs
+        .get_result_sets(&id)
+        .await
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    export_response(
+        result_sets,
+        db_type,
+        OutputFormat::negotiate(export.format, &request_headers),
+        export.result_set,
+        CsvOptions::default(),
+        HeaderMap::new(),
+    )
+}
+
+// 결과를 더 기다리지 않는다. 서버에서 실행 중인 쿼리는 취소되지 않는다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn cancel_job(
+    principal: Principal,
+    State(jobs): State<JobManager>,
+    State(access): State<AccessControl>,
+    Path(id): Path<String>,
+) -> Result<Json<JobInfo>, AppError> {
+    authorized_job(&jobs, &access, &principal, &id).await?;
+    jobs.cancel(&id)
+        .await
+        .ok_or_else(|| AppError::not_found("Job not found".into()))?;
+    jobs.get_job(&id)
+        .await
+        .map(Json)
+        .ok_or_else(|| AppError::not_found("Job not found".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::CreateGrantRequest;
+    use crate::auth::{Role, Subject};
+    use crate::db::connection::DatabaseConnection;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_jobs_are_visible_to_submitter_only() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let access = owned_by_alice(&[&id]).await;
+        let jobs = JobManager::default();
+        let submit = |principal, query: &str| {
+            submit_job(
+                principal,
+                State(manager.clone()),
+                State(jobs.clone()),
+                State(access.clone()),
+                Json(SubmitJobRequest { query: query.into(), connection_id: id.clone() }),
+            )
+        };
+
+        assert_forbidden(submit(user("mallory"), "SELECT 1").await);
+        let (_, Json(submitted)) = submit(user("alice"), "SELECT 1").await.unwrap();
+        let job_id = submitted.job_id;
+
+        // 연결을 읽을 수 있어도 남이 제출한 작업은 볼 수 없다
+        let request = CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Viewer, connection_id: Some(id.clone()) };
+        access.grant(request, "test").await;
+        let bob = || user("bob");
+        assert_forbidden(get_job(bob(), State(jobs.clone()), State(access.clone()), Path(job_id.clone())).await);
+        let page = ResultPageQuery { result_set: 0, offset: 0, limit: 10 };
+        assert_forbidden(get_job_results(bob(), State(jobs.clone()), State(access.clone()), Path(job_id.clone()), Query(page)).await);
+        let export = ExportQuery { format: None, result_set: 0 };
+        assert_forbidden(
+            export_job_results(bob(), State(jobs.clone()), State(access.clone()), Path(job_id.clone()), Query(export), HeaderMap::new())
+                .await,
+        );
+        assert_forbidden(cancel_job(bob(), State(jobs.clone()), State(access.clone()), Path(job_id.clone())).await);
+
+        let Json(info) = get_job(user("alice"), State(jobs), State(access), Path(job_id)).await.unwrap();
+        assert_eq!(info.submitted_by, "alice");
+    }
+}
@@ -1,4 +1,113 @@
+pub mod auth_handlers;
 pub mod book_handles;
+pub mod cache_handlers;
 pub mod connection_handlers;
+pub mod copy_handlers;
+pub mod ddl_handlers;
+pub mod dump_handlers;
+pub mod explain_handlers;
+pub mod grant_handlers;
+pub mod import_handlers;
+pub mod job_handlers;
+pub mod procedure_handlers;
+pub mod profile_handlers;
+pub mod schema_handlers;
+pub mod schema_diff_handlers;
+pub mod snapshot_handlers;
 pub mod sql_handlers;
 pub mod user_handlers;
+
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection::DatabaseConnection;
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::statement::{classify, StatementKind};
+use crate::error::AppError;
+use std::sync::Arc;
+
+// 사용자 SQL 을 실행하는 핸들러가 같이 쓴다. 문장을 분류해 필요한 권한을 확인하고, 읽기 전용 연결에서는
+// 쓰기 문장을 거부한 뒤 SQL 정책을 적용한다. 드라이버의 읽기 전용 세션보다 먼저 걸러서
+// DDL 처럼 세션이 막지 못하는 문장 (MySQL, Oracle 의 암묵적 커밋) 도 거부한다
+pub(crate) async fn authorize_sql(
+    manager: &ConnectionManager,
+    access: &AccessControl,
+    principal: &Principal,
+    connection_id: &str,
+    sql: &str,
+) -> Result<(Arc<DatabaseConnection>, StatementKind), AppError> {
+    let kind = classify(sql);
+    let permission = match kind {
+        StatementKind::Read => Permission::Read,
+        StatementKind::Write => Permission::Write,
+    };
+    access.authorize(principal, permission, Some(connection_id)).await?;
+    let connection = manager
+        .get_connection(connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    if connection.read_only() && kind == StatementKind::Write {
+        return Err(AppError::forbidden("Connection is read-only; only read statements are allowed".into()));
+    }
+    manager.check_policy(connection_id, connection.db_type(), sql).await?;
+    Ok((connection, kind))
+}
+
+// 핸들러 권한 테스트에서 같이 쓰는 도우미
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::auth::{AccessControl, AuthMethod, Principal, Subject};
+    use crate::error::AppError;
+    use axum::http::StatusCode;
+
+    pub fn user(subject: &str) -> Principal {
+        Principal { subject: subject.into(), method: AuthMethod::Jwt, roles: Vec::new() }
+    }
+
+    // alice 가 connection_ids 를 모두 소유한다. 다른 사용자는 아무 권한도 없다
+    pub async fn owned_by_alice(connection_ids: &[&str]) -> AccessControl {
+        let access = AccessControl::new();
+        for id in connection_ids {
+            access.set_owner(id, Subject::User("alice".into())).await;
+        }
+        access
+    }
+
+    pub fn assert_forbidden<T>(result: Result<T, AppError>) {
+        assert_eq!(result.err().map(|e| e.status_code), Some(StatusCode::FORBIDDEN));
+    }
+
+    // 정책 위반은 403 과 함께 어떤 규칙에 걸렸는지 details 로 돌려준다
+    pub fn assert_policy_rejected<T>(result: Result<T, AppError>, rule: &str) {
+        let error = result.err().expect("expected a policy violation");
+        assert_eq!(error.status_code, StatusCode::FORBIDDEN);
+        assert_eq!(error.details.as_ref().and_then(|details| details["rule"].as_str()), Some(rule));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::CreateGrantRequest;
+    use crate::auth::{Role, Subject};
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_authorize_sql_guards_writes() {
+        let manager = ConnectionManager::new();
+        let writable = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let read_only = manager.insert_connection(DatabaseConnection::unreachable(true)).await;
+        let access = owned_by_alice(&[&writable, &read_only]).await;
+        let request = CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Viewer, connection_id: Some(writable.clone()) };
+        access.grant(request, "test").await;
+        let authorize = |principal, id: &str, sql: &str| {
+            let (manager, access, id, sql) = (manager.clone(), access.clone(), id.to_string(), sql.to_string());
+            async move { authorize_sql(&manager, &access, &principal, &id, &sql).await.map(|(_, kind)| kind) }
+        };
+
+        // 조회 뒤에 이어 붙인 쓰기 문장도 쓰기로 본다
+        assert_eq!(authorize(user("bob"), &writable, "SELECT 1").await.unwrap(), StatementKind::Read);
+        assert_forbidden(authorize(user("bob"), &writable, "SELECT 1; DROP TABLE t").await);
+        assert_eq!(authorize(user("alice"), &writable, "DELETE FROM t").await.unwrap(), StatementKind::Write);
+        assert_eq!(authorize(user("alice"), &read_only, "SELECT 1").await.unwrap(), StatementKind::Read);
+        assert_forbidden(authorize(user("alice"), &read_only, "DROP TABLE t").await);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection::Connection;
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::procedure::{ProcedureCall, ProcedureResult};
+use crate::error::AppError;
+use axum::{Json, extract::State};
+use serde::Deserialize;
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+pub struct CallRequest {
+    pub connection_id: String,
+    #[serde(flatten)]
+    pub call: ProcedureCall,
+}
+
+// 루틴이 무엇을 하는지 알 수 없으므로 쓰기 권한이 필요하다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn call_procedure(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<CallRequest>,
+) -> Result<Json<ProcedureResult>, AppError> {
+    info!("Calling routine {} with connection ID: {}", payload.call.name, payload.connection_id);
+    access.authorize(&principal, Permission::Write, Some(&payload.connection_id)).await?;
+    payload
+        .call
+        .validate()
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+
+    let connection = manager
+        .get_connection(&payload.connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    // 루틴 안에서 쓰기를 하는지 알 수 없으므로 읽기 전용 연결에서는 부르지 않는다
+    if connection.read_only() {
+        return Err(AppError::forbidden("Connection is read-only; routines cannot be called".into()));
+    }
+    // SQL 을 받지 않으므로 CALL 문장과 같은 규칙으로 루틴 이름을 검사한다
+    manager
+        .check_routine_policy(&payload.connection_id, &payload.call.name)
+        .await?;
+
+    let result = connection
+        .call_procedure(&payload.call)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::DatabaseConnection;
+    use crate::db::policy::SqlPolicy;
+    use crate::handlers::test_support::{assert_forbidden, assert_policy_rejected, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_call_requires_write() {
+        let access = owned_by_alice(&["c1"]).await;
+        let payload: CallRequest = serde_json::from_value(serde_json::json!({ "connection_id": "c1", "name": "refresh_totals" })).unwrap();
+        assert_forbidden(call_procedure(user("mallory"), State(ConnectionManager::new()), State(access), Json(payload)).await);
+    }
+
+    #[tokio::test]
+    async fn test_call_refused_on_read_only_connection() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(true)).await;
+        let access = owned_by_alice(&[&id]).await;
+        let payload: CallRequest = serde_json::from_value(serde_json::json!({ "connection_id": id, "name": "refresh_totals" })).unwrap();
+        assert_forbidden(call_procedure(user("alice"), State(manager), State(access), Json(payload)).await);
+    }
+
+    #[tokio::test]
+    async fn test_call_refused_by_policy() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let policy = SqlPolicy { denied_functions: vec!["xp_cmdshell".into()], ..Default::default() };
+        manager.set_policy(&id, Some(policy)).await;
+        let access = owned_by_alice(&[&id]).await;
+        let payload: CallRequest = serde_json::from_value(serde_json::json!({ "connection_id": id, "name": "dbo.xp_cmdshell" })).unwrap();
+        assert_policy_rejected(call_procedure(user("alice"), State(manager), State(access), Json(payload)).await, "denied_functions");
+    }
+}
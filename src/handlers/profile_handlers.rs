@@ -0,0 +1,53 @@
+use crate::auth::{AccessControl, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::profile::{profile, ProfileRequest, TableProfile};
+use crate::error::AppError;
+use crate::handlers::authorize_sql;
+use axum::extract::State;
+use axum::Json;
+use tracing::info;
+
+// 컬럼별 통계를 데이터베이스 안에서 집계해 돌려준다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn profile_columns(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<ProfileRequest>,
+) -> Result<Json<TableProfile>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    let (connection, _) =
+        authorize_sql(&manager, &access, &principal, &payload.connection_id, &payload.source.query()).await?;
+    info!("Profiling columns on connection {}", payload.connection_id);
+
+    Ok(Json(profile(&connection, &payload).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::DatabaseConnection;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_profile_requires_read() {
+        let access = owned_by_alice(&["c1"]).await;
+        let payload: ProfileRequest = serde_json::from_value(serde_json::json!({ "connection_id": "c1", "table": "books" })).unwrap();
+        assert_forbidden(profile_columns(user("mallory"), State(ConnectionManager::new()), State(access), Json(payload)).await);
+    }
+
+    #[tokio::test]
+    async fn test_profile_refuses_wrapper_breakout() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(true)).await;
+        let access = owned_by_alice(&[&id]).await;
+        let payload: ProfileRequest =
+            serde_json::from_value(serde_json::json!({ "connection_id": id, "query": "SELECT 1 a) x; DROP TABLE t; SELECT * FROM (SELECT 1" }))
+                .unwrap();
+        let result = profile_columns(user("alice"), State(manager), State(access), Json(payload)).await;
+        assert_eq!(result.err().map(|e| e.status_code), Some(StatusCode::BAD_REQUEST));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::ddl::load_schema;
+use crate::db::schema_diff::{self, migration_script, SchemaDiff, SchemaDiffRequest};
+use crate::db::types::DatabaseType;
+use crate::error::AppError;
+use axum::extract::State;
+use axum::Json;
+use serde::Serialize;
+use tracing::info;
+
+#[derive(Debug, Serialize)]
+pub struct SchemaDiffResponse {
+    pub source_db: DatabaseType,
+    pub target_db: DatabaseType,
+    pub diff: SchemaDiff,
+    // 대상 연결에서 실행하면 원본과 같아지는 스크립트
+    pub migration: String,
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn compare_schemas(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Json(payload): Json<SchemaDiffRequest>,
+) -> Result<Json<SchemaDiffResponse>, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&payload.source_connection_id)).await?;
+    access.authorize(&principal, Permission::Read, Some(&payload.target_connection_id)).await?;
+    info!(
+        "Comparing schema of connection {} with {}",
+        payload.source_connection_id, payload.target_connection_id
+    );
+    let source = manager
+        .get_connection(&payload.source_connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid source connection ID".into()))?;
+    let target = manager
+        .get_connection(&payload.target_connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid target connection ID".into()))?;
+
+    let source_tables = load_schema(&source, payload.source_schema.as_deref())
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    let target_tables = load_schema(&target, payload.target_schema.as_deref())
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+
+    let (source_db, target_db) = (source.db_type(), target.db_type());
+    let diff = schema_diff::compare_schemas(&source_tables, source_db, &target_tables, target_db);
+    let migration = migration_script(&diff, &source_tables, source_db, target_db, payload.include_drops);
+
+    Ok(Json(SchemaDiffResponse {
+        source_db,
+        target_db,
+        diff,
+        migration,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::CreateGrantRequest;
+    use crate::auth::{Role, Subject};
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_compare_requires_read_on_both() {
+        let access = owned_by_alice(&["c1", "c2"]).await;
+        // c1 만 읽을 수 있다
+        let request = CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Viewer, connection_id: Some("c1".into()) };
+        access.grant(request, "test").await;
+        let payload: SchemaDiffRequest =
+            serde_json::from_value(serde_json::json!({ "source_connection_id": "c1", "target_connection_id": "c2" })).unwrap();
+        assert_forbidden(compare_schemas(user("bob"), State(ConnectionManager::new()), State(access), Json(payload)).await);
+    }
+}
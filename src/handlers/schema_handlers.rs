@@ -0,0 +1,192 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection::DatabaseConnection;
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::constraints::{self, ConstraintInfo, ForeignKeyInfo, IndexInfo};
+use crate::db::schema::{self, ColumnInfo, SchemaInfo, TableInfo, TableKind};
+use crate::error::AppError;
+use crate::formats::er::{er_response, ErFormat, ErGraph};
+use axum::extract::{Path, Query, State};
+use axum::response::Response;
+use axum::Json;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaQuery {
+    // 없으면 접속 사용자의 기본 스키마
+    pub schema: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErQuery {
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub format: ErFormat,
+}
+
+// 스키마 조회는 모두 읽기 권한이 필요하다
+async fn connection_for(
+    manager: &ConnectionManager,
+    access: &AccessControl,
+    principal: &Principal,
+    connection_id: &str,
+) -> Result<Arc<DatabaseConnection>, AppError> {
+    access.authorize(principal, Permission::Read, Some(connection_id)).await?;
+    manager
+        .get_connection(connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_schemas(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<Json<Vec<SchemaInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let schemas = schema::list_schemas(&connection)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(Json(schemas))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_tables(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<Vec<TableInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let tables = schema::list_tables(&connection, query.schema.as_deref(), TableKind::Table)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(Json(tables))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_views(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<Vec<TableInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let views = schema::list_tables(&connection, query.schema.as_deref(), TableKind::View)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(Json(views))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_columns(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path((connection_id, table)): Path<(String, String)>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<Vec<ColumnInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let columns = schema::list_columns(&connection, query.schema.as_deref(), &table)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    if columns.is_empty() {
+        return Err(AppError::not_found(format!("Table not found: {}", table)));
+    }
+    Ok(Json(columns))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_constraints(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path((connection_id, table)): Path<(String, String)>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<Vec<ConstraintInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let constraints = constraints::list_constraints(&connection, query.schema.as_deref(), Some(&table))
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(Json(constraints))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_indexes(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path((connection_id, table)): Path<(String, String)>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<Vec<IndexInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let indexes = constraints::list_indexes(&connection, query.schema.as_deref(), Some(&table))
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(Json(indexes))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_foreign_keys(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path((connection_id, table)): Path<(String, String)>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<Vec<ForeignKeyInfo>>, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let foreign_keys = constraints::list_foreign_keys(&connection, query.schema.as_deref(), Some(&table))
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(Json(foreign_keys))
+}
+
+// 스키마 전체의 외래 키로 ER 다이어그램을 만든다
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn er_diagram(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+    Query(query): Query<ErQuery>,
+) -> Result<Response, AppError> {
+    let connection = connection_for(&manager, &access, &principal, &connection_id).await?;
+    let schema = query.schema.as_deref();
+    let graph = async {
+        let tables = schema::list_tables(&connection, schema, TableKind::Table).await?;
+        let columns = schema::list_schema_columns(&connection, schema).await?;
+        let constraints = constraints::list_constraints(&connection, schema, None).await?;
+        let foreign_keys = constraints::list_foreign_keys(&connection, schema, None).await?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(ErGraph::new(&tables, &columns, &constraints, &foreign_keys))
+    }
+    .await
+    .map_err(|e| AppError::database_error(e.to_string()))?;
+    Ok(er_response(graph, query.format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_schema_requires_read() {
+        let access = owned_by_alice(&["c1"]).await;
+        let manager = ConnectionManager::new();
+        assert_forbidden(list_schemas(user("mallory"), State(manager.clone()), State(access.clone()), Path("c1".into())).await);
+        assert_forbidden(
+            list_columns(
+                user("mallory"),
+                State(manager),
+                State(access),
+                Path(("c1".into(), "books".into())),
+                Query(SchemaQuery { schema: None }),
+            )
+            .await,
+        );
+    }
+}
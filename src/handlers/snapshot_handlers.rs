@@ -0,0 +1,199 @@
+use crate::auth::{AccessControl, Permission, Principal};
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::ddl::{load_schema, TableDefinition};
+use crate::db::schema_diff::{compare_schemas, SchemaDiff};
+use crate::db::snapshot::{CreateSnapshotRequest, SchemaSnapshot, SnapshotStore, SnapshotSummary};
+use crate::error::AppError;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotDiffQuery {
+    // 비교할 다른 스냅샷 ID. 없으면 현재 데이터베이스와 비교한다
+    pub against: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotDiffResponse {
+    pub from: SnapshotSummary,
+    // 비교 대상 스냅샷 ID, 또는 "live"
+    pub to: String,
+    pub drifted: bool,
+    // from 이후에 더해지거나 빠지거나 바뀐 것
+    pub diff: SchemaDiff,
+}
+
+async fn load_snapshot(store: &SnapshotStore, connection_id: &str, id: &str) -> Result<SchemaSnapshot, AppError> {
+    store
+        .get(connection_id, id)
+        .await
+        .map_err(|e| AppError::validation_error(e.to_string()))?
+        .ok_or_else(|| AppError::not_found("Snapshot not found".into()))
+}
+
+async fn load_live(
+    manager: &ConnectionManager,
+    connection_id: &str,
+    snapshot: &SchemaSnapshot,
+) -> Result<Vec<TableDefinition>, AppError> {
+    let connection = manager
+        .get_connection(connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    load_schema(&connection, snapshot.schema.as_deref())
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))
+}
+
+fn diff_response(from: &SchemaSnapshot, to: String, tables: &[TableDefinition]) -> SnapshotDiffResponse {
+    // 나중 상태를 원본으로 두면 added 가 스냅샷 이후에 생긴 것이 된다
+    let diff = compare_schemas(tables, from.db_type, &from.tables, from.db_type);
+    SnapshotDiffResponse {
+        from: from.summary(),
+        to,
+        drifted: !diff.is_empty(),
+        diff,
+    }
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn create_snapshot(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(store): State<SnapshotStore>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+    Json(payload): Json<CreateSnapshotRequest>,
+) -> Result<(StatusCode, Json<SnapshotSummary>), AppError> {
+    // 스냅샷 저장과 삭제는 쓰기, 조회와 비교는 읽기 권한
+    access.authorize(&principal, Permission::Write, Some(&connection_id)).await?;
+    info!("Taking schema snapshot of connection {}", connection_id);
+    let connection = manager
+        .get_connection(&connection_id)
+        .await
+        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    let tables = load_schema(&connection, payload.schema.as_deref())
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+
+    let snapshot = SchemaSnapshot::new(&connection_id, connection.db_type(), payload, tables);
+    store
+        .save(&snapshot)
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(snapshot.summary())))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn list_snapshots(
+    principal: Principal,
+    State(store): State<SnapshotStore>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<Json<Vec<SnapshotSummary>>, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&connection_id)).await?;
+    store
+        .list(&connection_id)
+        .await
+        .map(Json)
+        .map_err(|e| AppError::validation_error(e.to_string()))
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn get_snapshot(
+    principal: Principal,
+    State(store): State<SnapshotStore>,
+    State(access): State<AccessControl>,
+    Path((connection_id, snapshot_id)): Path<(String, String)>,
+) -> Result<Json<SchemaSnapshot>, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&connection_id)).await?;
+    load_snapshot(&store, &connection_id, &snapshot_id).await.map(Json)
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn delete_snapshot(
+    principal: Principal,
+    State(store): State<SnapshotStore>,
+    State(access): State<AccessControl>,
+    Path((connection_id, snapshot_id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    access.authorize(&principal, Permission::Write, Some(&connection_id)).await?;
+    let deleted = store
+        .delete(&connection_id, &snapshot_id)
+        .await
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::not_found("Snapshot not found".into()))
+    }
+}
+
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn diff_snapshot(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(store): State<SnapshotStore>,
+    State(access): State<AccessControl>,
+    Path((connection_id, snapshot_id)): Path<(String, String)>,
+    Query(query): Query<SnapshotDiffQuery>,
+) -> Result<Json<SnapshotDiffResponse>, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&connection_id)).await?;
+    let from = load_snapshot(&store, &connection_id, &snapshot_id).await?;
+    let response = match query.against.as_deref() {
+        None | Some("live") => {
+            let live = load_live(&manager, &connection_id, &from).await?;
+            diff_response(&from, "live".to_string(), &live)
+        }
+        Some(other) => {
+            let to = load_snapshot(&store, &connection_id, other).await?;
+            diff_response(&from, to.id.clone(), &to.tables)
+        }
+    };
+    Ok(Json(response))
+}
+
+// 가장 최근 스냅샷 이후에 스키마가 바뀌었는지
+#[axum::debug_handler(state = crate::state::AppState)]
+pub async fn detect_drift(
+    principal: Principal,
+    State(manager): State<ConnectionManager>,
+    State(store): State<SnapshotStore>,
+    State(access): State<AccessControl>,
+    Path(connection_id): Path<String>,
+) -> Result<Json<SnapshotDiffResponse>, AppError> {
+    access.authorize(&principal, Permission::Read, Some(&connection_id)).await?;
+    let latest = store
+        .latest(&connection_id)
+        .await
+        .map_err(|e| AppError::validation_error(e.to_string()))?
+        .ok_or_else(|| AppError::not_found("No snapshot recorded for this connection".into()))?;
+    let live = load_live(&manager, &connection_id, &latest).await?;
+    Ok(Json(diff_response(&latest, "live".to_string(), &live)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::rbac::CreateGrantRequest;
+    use crate::auth::{Role, Subject};
+    use crate::handlers::test_support::{assert_forbidden, owned_by_alice, user};
+
+    #[tokio::test]
+    async fn test_snapshots_require_access() {
+        let access = owned_by_alice(&["c1"]).await;
+        let store = SnapshotStore::default();
+        assert_forbidden(list_snapshots(user("mallory"), State(store.clone()), State(access.clone()), Path("c1".into())).await);
+
+        // 읽기만 할 수 있으면 스냅샷을 지울 수 없다
+        let request = CreateGrantRequest { subject: Subject::User("bob".into()), role: Role::Viewer, connection_id: Some("c1".into()) };
+        access.grant(request, "test").await;
+        assert_forbidden(
+            delete_snapshot(user("bob"), State(store), State(access), Path(("c1".into(), "s1".into()))).await,
+        );
+    }
+}
@@ -1,42 +1,213 @@
+use crate::auth::{AccessControl, Principal};
+use crate::cache::QueryCache;
 use crate::db::connection_manager::ConnectionManager;
 use crate::db::connection::Connection;
+use crate::db::statement::StatementKind;
+use crate::db::types::{DatabaseType, ResultSet};
 use crate::error::AppError;
-use axum::response::IntoResponse;
+use crate::handlers::authorize_sql;
+use crate::formats::csv::CsvOptions;
+use crate::formats::{export_response, OutputFormat};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
 use axum::{Json, extract::State};
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
+
+pub const CACHE_HEADER: &str = "x-cache";
 
 #[derive(Debug, Deserialize)]
 pub struct SqlQuery {
     pub query: String,
     pub connection_id: String,
+    // 지정하면 결과를 캐시한다 (opt-in)
+    pub cache: Option<CacheOptions>,
+    // 없으면 Accept 헤더로 결정한다
+    pub format: Option<OutputFormat>,
+    // CSV/TSV/Arrow/Parquet 로 내보낼 결과 집합 번호 (xlsx 는 전체)
+    #[serde(default)]
+    pub result_set: usize,
+    pub csv: Option<CsvOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CacheOptions {
+    pub ttl_seconds: u64,
 }
 
+// rows/affected_rows 는 첫 번째 결과 집합 (기존 클라이언트 호환용)
 #[derive(Debug, Serialize)]
 pub struct QueryResult {
     pub rows: Vec<serde_json::Value>,
     pub affected_rows: Option<u64>,
+    pub result_sets: Vec<ResultSet>,
+}
+
+impl QueryResult {
+    pub fn new(result_sets: Vec<ResultSet>) -> Self {
+        let (rows, affected_rows) = result_sets
+            .first()
+            .map(|first| (first.rows.clone(), first.affected_rows))
+            .unwrap_or_default();
+        Self {
+            rows,
+            affected_rows,
+            result_sets,
+        }
+    }
 }
 
-#[axum::debug_handler]
+#[axum::debug_handler(state = crate::state::AppState)]
 pub async fn execute_sql(
+    principal: Principal,
     State(manager): State<ConnectionManager>,
+    State(cache): State<QueryCache>,
+    State(access): State<AccessControl>,
+    request_headers: HeaderMap,
     Json(payload): Json<SqlQuery>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
     info!("Executing SQL query with connection ID: {}", payload.connection_id);
-    let connection = manager
-        .get_connection(&payload.connection_id)
-        .await
-        .ok_or_else(|| AppError::validation_error("Invalid connection ID".into()))?;
+    // 캐시된 결과를 돌려줄 때도 권한을 확인한다
+    let (connection, kind) = authorize_sql(&manager, &access, &principal, &payload.connection_id, &payload.query).await?;
 
-    // Execute the query
-    let results = connection
-        .execute_query(&payload.query)
-        .await
-        .map_err(|e| AppError::database_error(e.to_string()))?;
+    let mut headers = HeaderMap::new();
+    // 쓰기 문장은 캐시하지 않는다. 캐시에서 돌려주면 실행되지 않는다
+    let cache_key = payload
+        .cache
+        .as_ref()
+        .filter(|_| kind == StatementKind::Read)
+        .map(|_| QueryCache::key(&payload.connection_id, &payload.query, &[]));
+
+    // 캐시 저장소 오류는 쿼리 실패로 취급하지 않는다
+    let mut cached = None;
+    if let Some(key) = &cache_key {
+        match cache.get(key).await {
+            Ok(result_sets) => cached = result_sets,
+            Err(e) => warn!("Query cache lookup failed: {}", e),
+        }
+    }
 
-    Ok(Json(QueryResult { 
-        rows: results,
-        affected_rows: None, // This will be implemented when we add support for non-SELECT queries
-    }))
-} 
\ No newline at end of file
+    let result_sets = match cached {
+        Some(result_sets) => {
+            headers.insert(CACHE_HEADER, HeaderValue::from_static("HIT"));
+            result_sets
+        }
+        None => {
+            // Execute the query
+            let result_sets = connection
+                .execute_result_sets(&payload.query)
+                .await
+                .map_err(|e| AppError::database_error(e.to_string()))?;
+
+            match (&cache_key, &payload.cache) {
+                (Some(key), Some(options)) => {
+                    headers.insert(CACHE_HEADER, HeaderValue::from_static("MISS"));
+                    if let Err(e) = cache
+                        .set(key, &result_sets, Duration::from_secs(options.ttl_seconds))
+                        .await
+                    {
+                        warn!("Query cache store failed: {}", e);
+                    }
+                }
+                _ => {
+                    headers.insert(CACHE_HEADER, HeaderValue::from_static("BYPASS"));
+                }
+            }
+            result_sets
+        }
+    };
+
+    render(result_sets, connection.db_type(), headers, &request_headers, payload)
+}
+
+fn render(
+    result_sets: Vec<ResultSet>,
+    db_type: DatabaseType,
+    headers: HeaderMap,
+    request_headers: &HeaderMap,
+    payload: SqlQuery,
+) -> Result<Response, AppError> {
+    match OutputFormat::negotiate(payload.format, request_headers) {
+        OutputFormat::Json => Ok((headers, Json(QueryResult::new(result_sets))).into_response()),
+        format => export_response(
+            result_sets,
+            db_type,
+            format,
+            payload.result_set,
+            payload.csv.unwrap_or_default(),
+            headers,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthMethod;
+    use crate::db::connection::DatabaseConnection;
+    use axum::http::StatusCode;
+
+    fn admin() -> Principal {
+        Principal { subject: "alice".into(), method: AuthMethod::Jwt, roles: vec!["admin".into()] }
+    }
+
+    fn cached_query(connection_id: &str, query: &str) -> SqlQuery {
+        SqlQuery {
+            query: query.into(),
+            connection_id: connection_id.into(),
+            cache: Some(CacheOptions { ttl_seconds: 60 }),
+            format: None,
+            result_set: 0,
+            csv: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_write_is_executed() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(false)).await;
+        let cache = QueryCache::default();
+        for query in ["INSERT INTO t VALUES (1)", "SELECT * FROM t"] {
+            cache
+                .set(&QueryCache::key(&id, query, &[]), &[ResultSet::default()], Duration::from_secs(60))
+                .await
+                .unwrap();
+        }
+        let run = |query: &str| {
+            execute_sql(
+                admin(),
+                State(manager.clone()),
+                State(cache.clone()),
+                State(AccessControl::new()),
+                HeaderMap::new(),
+                Json(cached_query(&id, query)),
+            )
+        };
+
+        // 캐시에 결과가 있어도 쓰기는 실행되므로 접속 오류가 난다
+        let error = run("INSERT INTO t VALUES (1)").await.unwrap_err();
+        assert_eq!(error.status_code, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = run("SELECT * FROM t").await.unwrap();
+        assert_eq!(response.headers()[CACHE_HEADER], "HIT");
+    }
+
+    #[tokio::test]
+    async fn test_write_refused_on_read_only_connection() {
+        let manager = ConnectionManager::new();
+        let id = manager.insert_connection(DatabaseConnection::unreachable(true)).await;
+        let payload = SqlQuery { cache: None, ..cached_query(&id, "INSERT INTO t VALUES (1)") };
+        let error = execute_sql(
+            admin(),
+            State(manager),
+            State(QueryCache::default()),
+            State(AccessControl::new()),
+            HeaderMap::new(),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status_code, StatusCode::FORBIDDEN);
+    }
+}
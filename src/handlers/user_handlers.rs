@@ -1,22 +1,32 @@
+use crate::auth::password::validate_new_passwd;
+use crate::auth::{AccessControl, Permission, Principal};
 use crate::error::AppError;
-use crate::models::user_models::{CreateUser, User};
+use crate::models::user_models::{CreateUser, UpdateUser, User};
 use crate::utils::passwd::passwd_hash;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::{extract::Path, Extension, Json};
+use axum::{extract::{Path, State}, Extension, Json};
 use sqlx::{query_as, PgPool};
 
 pub async fn create_user(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Json(payload): Json<CreateUser>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
     if payload.name.is_empty() || payload.email.is_empty() {
         return Err(AppError::validation_error(
             "이름과 이메일은 필수 항목입니다.".into(),
         ));
     }
-    // 비밀번호 암호화
-    let passwd = passwd_hash(&payload.passwd);
+    validate_new_passwd(&payload.passwd).map_err(AppError::validation_error)?;
+    // 비밀번호 암호화. Argon2 는 CPU 를 오래 쓰므로 블로킹 스레드에서 돌린다
+    let plain = payload.passwd.clone();
+    let passwd = tokio::task::spawn_blocking(move || passwd_hash(&plain))
+        .await
+        .map_err(|e| AppError::database_error(e.to_string()))?
+        .map_err(|e| AppError::database_error(e.to_string()))?;
 
     // 트랜젝션
     // let mut tx = pool.begin().await?;
@@ -32,7 +42,12 @@ pub async fn create_user(
     Ok((StatusCode::CREATED, Json(rec)))
 }
 
-pub async fn get_users(Extension(pool): Extension<PgPool>) -> Result<impl IntoResponse, AppError> {
+pub async fn get_users(
+    principal: Principal,
+    State(access): State<AccessControl>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
     let recs: Vec<_> = query_as::<_, User>("SELECT id, name, email, passwd FROM users")
         .fetch_all(&pool)
         .await?;
@@ -40,9 +55,12 @@ pub async fn get_users(Extension(pool): Extension<PgPool>) -> Result<impl IntoRe
 }
 
 pub async fn get_user(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
     let rec = sqlx::query_as::<_, User>("SELECT id, name, email, passwd FROM users WHERE id = $1")
         .bind(id)
         .fetch_one(&pool)
@@ -52,21 +70,23 @@ pub async fn get_user(
 }
 
 pub async fn update_user(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
-    Json(payload): Json<User>,
+    Json(payload): Json<UpdateUser>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
     if payload.name.is_empty() || payload.email.is_empty() {
         return Err(AppError::validation_error(
             "제목과 저자는 필수 항목입니다.".into(),
         ));
     }
     let rec = sqlx::query_as::<_, User>(
-        "UPDATE users SET name = $1, email = $2, passwd = $3 WHERE id = $4 RETURNING id, name, email, passwd",
+        "UPDATE users SET name = $1, email = $2 WHERE id = $3 RETURNING id, name, email, passwd",
     )
     .bind(&payload.name)
     .bind(&payload.email)
-    .bind(&payload.passwd)
     .bind(id)
     .fetch_one(&pool)
     .await?;
@@ -75,9 +95,12 @@ pub async fn update_user(
 }
 
 pub async fn delete_user(
+    principal: Principal,
+    State(access): State<AccessControl>,
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
 ) -> Result<impl IntoResponse, AppError> {
+    access.authorize(&principal, Permission::ManageUsers, None).await?;
     let result = sqlx::query("DELETE FROM users WHERE id = $1")
         .bind(id)
         .execute(&pool)
@@ -89,3 +112,22 @@ pub async fn delete_user(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthMethod;
+
+    #[tokio::test]
+    async fn test_create_user_rejects_short_passwd() {
+        let admin = Principal { subject: "root".into(), method: AuthMethod::Jwt, roles: vec!["admin".into()] };
+        // 비밀번호 검사에서 끝나므로 데이터베이스에 연결하지 않는다
+        let pool = PgPool::connect_lazy("postgres://127.0.0.1:1/users").unwrap();
+        let payload = CreateUser { name: "kim".into(), email: "kim@example.com".into(), passwd: "short".into() };
+        let error = create_user(admin, State(AccessControl::new()), Extension(pool), Json(payload))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+    }
+}
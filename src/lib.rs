@@ -1,6 +1,11 @@
+pub mod auth;
+pub mod cache;
 pub mod db;
 pub mod error;
+pub mod formats;
 pub mod handlers;
+pub mod mail;
 pub mod models;
 pub mod routes;
+pub mod state;
 pub mod utils; 
\ No newline at end of file
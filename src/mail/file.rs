@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+use crate::mail::{MailMessage, MailSender};
+
+// 메일 한 통을 {dir}/{시각}-{uuid}.eml 로 저장한다
+#[derive(Debug, Clone)]
+pub struct FileMailSender {
+    dir: PathBuf,
+}
+
+impl FileMailSender {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+// 헤더 값에 줄바꿈이 들어가 다른 헤더를 끼워 넣지 못하게 한다
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+#[async_trait]
+impl MailSender for FileMailSender {
+    async fn send(&self, message: &MailMessage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let now = Utc::now();
+        let path = self.dir.join(format!("{}-{}.eml", now.format("%Y%m%dT%H%M%S%.3f"), Uuid::new_v4()));
+        let content = format!(
+            "Date: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            now.to_rfc2822(),
+            header_value(&message.to),
+            header_value(&message.subject),
+            message.body
+        );
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_writes_message_file() {
+        let dir = std::env::temp_dir().join(format!("mail-{}", Uuid::new_v4()));
+        let sender = FileMailSender::new(&dir);
+        let message = MailMessage { to: "a@example.com".into(), subject: "Hi\r\nBcc: x".into(), body: "hello".into() };
+        sender.send(&message).await.unwrap();
+
+        let mut entries = tokio::fs::read_dir(&dir).await.unwrap();
+        let path = entries.next_entry().await.unwrap().unwrap().path();
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(content.contains("\r\nSubject: Hi  Bcc: x\r\n"));
+        assert!(content.ends_with("\r\n\r\nhello\r\n"));
+
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
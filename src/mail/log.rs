@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+use tracing::info;
+use crate::mail::{MailMessage, MailSender};
+
+// 로컬 개발용. 본문을 그대로 로그에 남기므로 운영에서는 쓰지 않는다
+#[derive(Debug, Clone, Default)]
+pub struct LogMailSender;
+
+#[async_trait]
+impl MailSender for LogMailSender {
+    async fn send(&self, message: &MailMessage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        info!("Mail to {}: {}\n{}", message.to, message.subject, message.body);
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+pub mod file;
+pub mod log;
+
+use std::sync::Arc;
+use async_trait::async_trait;
+
+pub use file::FileMailSender;
+pub use log::LogMailSender;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// 메일 발송 방식. SMTP 등 실제 발송기는 이 트레잇을 구현해 Mailer::new 로 넘긴다
+#[async_trait]
+pub trait MailSender: Send + Sync + std::fmt::Debug + 'static {
+    async fn send(&self, message: &MailMessage) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Mailer {
+    sender: Arc<dyn MailSender>,
+}
+
+impl Default for Mailer {
+    fn default() -> Self {
+        Self::new(Arc::new(LogMailSender))
+    }
+}
+
+impl Mailer {
+    pub fn new(sender: Arc<dyn MailSender>) -> Self {
+        Self { sender }
+    }
+
+    // MAIL_SENDER=file 이면 MAIL_DIR (기본 ./mail) 에 파일로, 그 밖에는 로그로 남긴다
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        match std::env::var("MAIL_SENDER").as_deref() {
+            Ok("file") => {
+                let dir = std::env::var("MAIL_DIR").unwrap_or_else(|_| "./mail".into());
+                Ok(Self::new(Arc::new(FileMailSender::new(dir))))
+            }
+            Ok("log") | Ok("") | Err(_) => Ok(Self::default()),
+            Ok(other) => Err(format!("Unsupported MAIL_SENDER: {}", other).into()),
+        }
+    }
+
+    pub async fn send(&self, message: &MailMessage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.sender.send(message).await
+    }
+}
@@ -3,20 +3,25 @@ use axum::{
     response::Result,
     // response::{Response, Result},
     // routing::{delete, get, post, put},
-    extract::State,
     // Json, Router,
 };
+use axum::Extension;
 use dotenv::dotenv;
+use sqlx::postgres::PgPoolOptions;
+use std::net::SocketAddr;
 // use std::{env, io};
 
-mod db;
-mod error;
-mod handlers;
-mod models;
-mod routes;
-mod utils; // 모듈 임포트
+use axum_ex::routes; // 모듈 임포트
 
-use db::connection_manager::ConnectionManager;
+use axum_ex::auth::{require_auth, AccessControl, Authenticator};
+
+use axum_ex::cache::QueryCache;
+use axum_ex::db::connection_manager::ConnectionManager;
+use axum_ex::db::copy_manager::CopyManager;
+use axum_ex::db::job_manager::{JobManager, JobOptions};
+use axum_ex::db::snapshot::SnapshotStore;
+use axum_ex::mail::Mailer;
+use axum_ex::state::AppState;
 //use handlers::*;
 
 use tower_http::trace::{self, TraceLayer};
@@ -44,10 +49,29 @@ async fn main() -> Result<(), sqlx::Error> {
         .with_ansi(false)
         .init();
 
-    let connection_manager = ConnectionManager::new();
+    // 사용자, 로그인 기록을 저장하는 데이터베이스. 첫 요청 때 연결한다
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_lazy(&database_url)?;
+
+    let state = AppState {
+        connection_manager: ConnectionManager::new(),
+        job_manager: JobManager::new(JobOptions::from_env()),
+        query_cache: QueryCache::from_env().expect("Invalid CACHE_REDIS_URL"),
+        copy_manager: CopyManager::new(JobOptions::from_env()),
+        snapshot_store: SnapshotStore::from_env(),
+        authenticator: Authenticator::from_env().expect("Invalid authentication settings"),
+        mailer: Mailer::from_env().expect("Invalid MAIL_SENDER"),
+        access_control: AccessControl::from_env().await,
+    };
 
+    // 로그인, 토큰 갱신, 비밀번호 재설정을 뺀 모든 API 는 인증을 거친다
     let app = routes::create_routes()
-        .with_state(connection_manager)
+        .layer(axum::middleware::from_fn_with_state(state.authenticator.clone(), require_auth))
+        .merge(routes::create_public_routes())
+        .with_state(state)
+        .layer(Extension(pool))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(trace::DefaultMakeSpan::new().level(Level::INFO))
@@ -60,7 +84,10 @@ async fn main() -> Result<(), sqlx::Error> {
     let listener = tokio::net::TcpListener::bind("localhost:3000")
         .await
         .unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // 로그인 기록에 클라이언트 주소를 남기기 위해 ConnectInfo 를 붙인다
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 
     Ok(())
 }
@@ -15,3 +15,10 @@ pub struct CreateUser {
     pub email: String,
     pub passwd: String,
 }
+
+// 비밀번호는 /password/change, /password/reset 으로만 바꾼다
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateUser {
+    pub name: String,
+    pub email: String,
+}
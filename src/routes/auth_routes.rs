@@ -0,0 +1,11 @@
+use crate::handlers::auth_handlers::{confirm_password_reset, login, refresh_token, request_password_reset};
+use crate::state::AppState;
+use axum::{routing::post, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/login", post(login))
+        .route("/token/refresh", post(refresh_token))
+        .route("/password/reset", post(request_password_reset))
+        .route("/password/reset/confirm", post(confirm_password_reset))
+}
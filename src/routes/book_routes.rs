@@ -1,5 +1,5 @@
 use crate::handlers::book_handles::{create_book, delete_book, get_book, get_books, update_book};
-use crate::db::connection_manager::ConnectionManager;
+use crate::state::AppState;
 use axum::{
     routing::{
         get, 
@@ -10,7 +10,7 @@ use axum::{
     Router,
 }; // 적절한 핸들러 임포트
 
-pub fn create_routes() -> Router<ConnectionManager> {
+pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/books", get(get_books).post(create_book))
         .route(
@@ -0,0 +1,12 @@
+use crate::handlers::cache_handlers::{invalidate_all, invalidate_connection};
+use crate::state::AppState;
+use axum::{
+    routing::delete,
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/cache", delete(invalidate_all))
+        .route("/cache/connections/{id}", delete(invalidate_connection))
+}
\ No newline at end of file
@@ -1,11 +1,16 @@
-use crate::handlers::connection_handlers::create_connection;
-use crate::db::connection_manager::ConnectionManager;
+use crate::handlers::connection_handlers::{create_connection, delete_connection, delete_policy, get_policy, set_policy};
+use crate::state::AppState;
 use axum::{
-    routing::post,
+    routing::{get, post},
     Router,
 };
 
-pub fn create_routes() -> Router<ConnectionManager> {
+pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/connect", post(create_connection))
-} 
\ No newline at end of file
+        .route("/disconnect", post(delete_connection))
+        .route(
+            "/connections/{id}/policy",
+            get(get_policy).put(set_policy).delete(delete_policy),
+        )
+}
@@ -0,0 +1,12 @@
+use crate::handlers::copy_handlers::{cancel_copy, get_copy, submit_copy};
+use crate::state::AppState;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/copies", post(submit_copy))
+        .route("/copies/{id}", get(get_copy).delete(cancel_copy))
+}
@@ -0,0 +1,7 @@
+use crate::handlers::ddl_handlers::table_ddl;
+use crate::state::AppState;
+use axum::{routing::get, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/connections/{id}/tables/{table}/ddl", get(table_ddl))
+}
@@ -0,0 +1,7 @@
+use crate::handlers::dump_handlers::dump_sql;
+use crate::state::AppState;
+use axum::{routing::post, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/dump", post(dump_sql))
+}
@@ -0,0 +1,11 @@
+use crate::handlers::explain_handlers::explain_sql;
+use crate::state::AppState;
+use axum::{
+    routing::post,
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/explain", post(explain_sql))
+}
\ No newline at end of file
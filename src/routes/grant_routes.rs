@@ -0,0 +1,17 @@
+use crate::handlers::grant_handlers::{
+    add_team_member, create_grant, delete_grant, list_connection_grants, list_grants, list_teams, remove_team_member,
+};
+use crate::state::AppState;
+use axum::{
+    routing::{delete, get, put},
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/grants", get(list_grants).post(create_grant))
+        .route("/grants/{grant_id}", delete(delete_grant))
+        .route("/connections/{id}/grants", get(list_connection_grants))
+        .route("/teams", get(list_teams))
+        .route("/teams/{team}/members/{subject}", put(add_team_member).delete(remove_team_member))
+}
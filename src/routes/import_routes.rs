@@ -0,0 +1,21 @@
+use crate::handlers::import_handlers::import_data;
+use crate::state::AppState;
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::post,
+    Router,
+};
+
+// 업로드 크기 제한 (IMPORT_MAX_BYTES, 기본 1GiB)
+fn import_body_limit() -> usize {
+    std::env::var("IMPORT_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1024 * 1024 * 1024)
+}
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/connections/{id}/import", post(import_data))
+        .layer(DefaultBodyLimit::max(import_body_limit()))
+}
@@ -0,0 +1,14 @@
+use crate::handlers::job_handlers::{cancel_job, export_job_results, get_job, get_job_results, submit_job};
+use crate::state::AppState;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/jobs", post(submit_job))
+        .route("/jobs/{id}", get(get_job).delete(cancel_job))
+        .route("/jobs/{id}/results", get(get_job_results))
+        .route("/jobs/{id}/export", get(export_job_results))
+}
\ No newline at end of file
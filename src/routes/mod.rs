@@ -1,20 +1,54 @@
+mod auth_routes;
 mod book_routes;
+mod cache_routes;
 mod connection_routes;
+mod copy_routes;
+mod ddl_routes;
+mod dump_routes;
+mod explain_routes;
+mod grant_routes;
+mod import_routes;
+mod password_routes;
+mod job_routes;
+mod procedure_routes;
+mod profile_routes;
+mod schema_routes;
+mod schema_diff_routes;
+mod snapshot_routes;
 mod sql_routes;
 mod user_routes; // 예시로 다른 라우트 모듈을 추가할 수 있음.
 
 use axum::Router;
-use crate::db::connection_manager::ConnectionManager;
+use crate::state::AppState;
 
-pub fn create_routes() -> Router<ConnectionManager> {
+pub fn create_routes() -> Router<AppState> {
     Router::new()
         .merge(book_routes::create_routes())
         .merge(user_routes::create_routes())
         .merge(sql_routes::create_routes())
         .merge(connection_routes::create_routes())
+        .merge(explain_routes::create_routes())
+        .merge(procedure_routes::create_routes())
+        .merge(job_routes::create_routes())
+        .merge(cache_routes::create_routes())
+        .merge(import_routes::create_routes())
+        .merge(copy_routes::create_routes())
+        .merge(dump_routes::create_routes())
+        .merge(schema_routes::create_routes())
+        .merge(ddl_routes::create_routes())
+        .merge(schema_diff_routes::create_routes())
+        .merge(snapshot_routes::create_routes())
+        .merge(profile_routes::create_routes())
+        .merge(password_routes::create_routes())
+        .merge(grant_routes::create_routes())
     // let book_routes = book_routes::create_routes();
     // let user_routes = user_routes::create_routes(); // 추가한 경우
 
     // // 다른 라우트를 조합할 수 있음
     // book_routes.merge(user_routes)
 }
+
+// 인증 미들웨어 바깥에 두는 라우트 (로그인, 토큰 갱신, 비밀번호 재설정)
+pub fn create_public_routes() -> Router<AppState> {
+    Router::new().merge(auth_routes::create_routes())
+}
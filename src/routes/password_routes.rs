@@ -0,0 +1,7 @@
+use crate::handlers::auth_handlers::change_password;
+use crate::state::AppState;
+use axum::{routing::post, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/password/change", post(change_password))
+}
@@ -0,0 +1,11 @@
+use crate::handlers::procedure_handlers::call_procedure;
+use crate::state::AppState;
+use axum::{
+    routing::post,
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/call", post(call_procedure))
+}
\ No newline at end of file
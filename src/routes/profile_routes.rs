@@ -0,0 +1,7 @@
+use crate::handlers::profile_handlers::profile_columns;
+use crate::state::AppState;
+use axum::{routing::post, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/profile", post(profile_columns))
+}
@@ -0,0 +1,7 @@
+use crate::handlers::schema_diff_handlers::compare_schemas;
+use crate::state::AppState;
+use axum::{routing::post, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/schema-diff", post(compare_schemas))
+}
@@ -0,0 +1,18 @@
+use crate::handlers::schema_handlers::{
+    er_diagram, list_columns, list_constraints, list_foreign_keys, list_indexes, list_schemas, list_tables,
+    list_views,
+};
+use crate::state::AppState;
+use axum::{routing::get, Router};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/connections/{id}/schemas", get(list_schemas))
+        .route("/connections/{id}/tables", get(list_tables))
+        .route("/connections/{id}/views", get(list_views))
+        .route("/connections/{id}/tables/{table}/columns", get(list_columns))
+        .route("/connections/{id}/tables/{table}/constraints", get(list_constraints))
+        .route("/connections/{id}/tables/{table}/indexes", get(list_indexes))
+        .route("/connections/{id}/tables/{table}/foreign-keys", get(list_foreign_keys))
+        .route("/connections/{id}/er-diagram", get(er_diagram))
+}
@@ -0,0 +1,16 @@
+use crate::handlers::snapshot_handlers::{
+    create_snapshot, delete_snapshot, detect_drift, diff_snapshot, get_snapshot, list_snapshots,
+};
+use crate::state::AppState;
+use axum::{
+    routing::get,
+    Router,
+};
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/connections/{id}/snapshots", get(list_snapshots).post(create_snapshot))
+        .route("/connections/{id}/snapshots/{snapshot_id}", get(get_snapshot).delete(delete_snapshot))
+        .route("/connections/{id}/snapshots/{snapshot_id}/diff", get(diff_snapshot))
+        .route("/connections/{id}/drift", get(detect_drift))
+}
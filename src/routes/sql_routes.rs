@@ -1,11 +1,11 @@
 use crate::handlers::sql_handlers::execute_sql;
-use crate::db::connection_manager::ConnectionManager;
+use crate::state::AppState;
 use axum::{
     routing::post,
     Router,
 };
 
-pub fn create_routes() -> Router<ConnectionManager> {
+pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/sql", post(execute_sql))
 } 
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::handlers::user_handlers::{create_user, delete_user, get_user, get_users, update_user};
-use crate::db::connection_manager::ConnectionManager;
+use crate::state::AppState;
 use axum::{
     routing::{
         get, 
@@ -10,7 +10,7 @@ use axum::{
     Router,
 }; // 적절한 핸들러 임포트
 
-pub fn create_routes() -> Router<ConnectionManager> {
+pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/users", get(get_users).post(create_user))
         .route(
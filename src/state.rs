@@ -0,0 +1,21 @@
+use axum::extract::FromRef;
+use crate::auth::{AccessControl, Authenticator};
+use crate::cache::QueryCache;
+use crate::db::connection_manager::ConnectionManager;
+use crate::db::copy_manager::CopyManager;
+use crate::db::job_manager::JobManager;
+use crate::db::snapshot::SnapshotStore;
+use crate::mail::Mailer;
+
+// 핸들러는 필요한 부분만 State<ConnectionManager> 처럼 꺼내 쓴다
+#[derive(Debug, Clone, Default, FromRef)]
+pub struct AppState {
+    pub connection_manager: ConnectionManager,
+    pub job_manager: JobManager,
+    pub query_cache: QueryCache,
+    pub copy_manager: CopyManager,
+    pub snapshot_store: SnapshotStore,
+    pub authenticator: Authenticator,
+    pub mailer: Mailer,
+    pub access_control: AccessControl,
+}
\ No newline at end of file
@@ -1,4 +1,6 @@
-use uuid::Uuid;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params};
 use sha2::{Sha512, Digest};
 use base64ct::{
     Base64,
@@ -7,25 +9,77 @@ use base64ct::{
 
 pub struct Passwd {
     pub wd: String,
+    // 예전 SHA-512 해시의 솔트. PHC 문자열은 솔트를 안에 담으므로 새 해시는 빈 문자열이다
     pub salt: String
 }
 
-impl Passwd {
-    fn new () -> Self {
-        Passwd {
-            wd: "".into(),
-            salt: "".into(),
-        }
-    }
+// Argon2id PHC 문자열($argon2id$v=19$m=...,t=...,p=...$솔트$해시)을 만든다
+pub fn passwd_hash(psswd: &str) -> Result<Passwd, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(psswd.as_bytes(), &salt)?;
+    Ok(Passwd {
+        wd: hash.to_string(),
+        salt: String::new(),
+    })
 }
 
-pub fn passwd_hash (psswd:&String) -> Passwd {
-    let mut passwd = Passwd::new();
-    passwd.salt = Uuid::new_v4().to_string();
+// 예전 형식: Base64(SHA-512(비밀번호 + UUID 솔트))
+fn legacy_verify(psswd: &str, hash: &str, salt: &str) -> bool {
     let mut hasher = Sha512::new();
     hasher.update(psswd);
-    hasher.update(&passwd.salt);
-    let hash = hasher.finalize();
-    passwd.wd = Base64::encode_string(&hash);
-    return passwd;
+    hasher.update(salt);
+    let computed = Base64::encode_string(&hasher.finalize());
+    computed.len() == hash.len()
+        && computed
+            .bytes()
+            .zip(hash.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// PHC 문자열이면 그 안의 매개변수로, 아니면 예전 SHA-512 형식으로 확인한다
+pub fn passwd_verify(psswd: &str, hash: &str, salt: &str) -> bool {
+    if hash.starts_with('$') {
+        return PasswordHash::new(hash)
+            .map(|parsed| Argon2::default().verify_password(psswd.as_bytes(), &parsed).is_ok())
+            .unwrap_or(false);
+    }
+    legacy_verify(psswd, hash, salt)
+}
+
+// 예전 형식이거나 현재 기본 매개변수와 다르면 로그인에 성공했을 때 다시 해시한다
+pub fn passwd_needs_rehash(hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return true;
+    };
+    let current = Params::default();
+    parsed.algorithm != Algorithm::Argon2id.ident()
+        || Params::try_from(&parsed)
+            .map(|params| {
+                (params.m_cost(), params.t_cost(), params.p_cost())
+                    != (current.m_cost(), current.t_cost(), current.p_cost())
+            })
+            .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_and_rehash() {
+        let passwd = passwd_hash("correct horse").unwrap();
+        assert!(passwd.wd.starts_with("$argon2id$"));
+        assert!(passwd_verify("correct horse", &passwd.wd, &passwd.salt));
+        assert!(!passwd_verify("wrong horse", &passwd.wd, &passwd.salt));
+        assert!(!passwd_needs_rehash(&passwd.wd));
+
+        let mut hasher = Sha512::new();
+        hasher.update("correct horse");
+        hasher.update("legacy-salt");
+        let legacy = Base64::encode_string(&hasher.finalize());
+        assert!(passwd_verify("correct horse", &legacy, "legacy-salt"));
+        assert!(!passwd_verify("correct horse", &legacy, "other-salt"));
+        assert!(passwd_needs_rehash(&legacy));
+    }
 }
@@ -0,0 +1,145 @@
+use std::net::SocketAddr;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Extension, Json};
+use axum_ex::auth::login::{find_user, register_failure, LoginPolicy, LoginRequest};
+use axum_ex::auth::rbac::CreateGrantRequest;
+use axum_ex::auth::{AccessControl, ApiKeyStore, Authenticator, JwtVerifier, Role, Subject, TokenIssuer};
+use axum_ex::handlers::auth_handlers::login;
+use axum_ex::utils::passwd::passwd_hash;
+use sqlx::PgPool;
+
+const SECRET: &[u8] = b"login-integration-test-secret";
+
+// 마이그레이션을 적용한 사용자 데이터베이스 (서버와 같은 DATABASE_URL).
+// DATABASE_URL 이 없으면 테스트를 건너뛴다
+async fn pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL is not set; skipping login integration test");
+        return None;
+    };
+    Some(PgPool::connect(&url).await.expect("Failed to connect to the user database"))
+}
+
+async fn create_user(pool: &PgPool, passwd: &str) -> (i32, String) {
+    let email = format!("login-{}@example.com", uuid::Uuid::new_v4());
+    let passwd = passwd_hash(passwd).unwrap();
+    let (id,): (i32,) = sqlx::query_as("INSERT INTO users (name, email, passwd, salt_key) VALUES ('test', $1, $2, $3) RETURNING id")
+        .bind(&email)
+        .bind(&passwd.wd)
+        .bind(&passwd.salt)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    (id, email)
+}
+
+async fn delete_user(pool: &PgPool, email: &str) {
+    sqlx::query("DELETE FROM login_log WHERE email = $1").bind(email).execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE email = $1").bind(email).execute(pool).await.unwrap();
+}
+
+// 마지막 시도의 (user_id, success, reason)
+async fn last_attempt(pool: &PgPool, email: &str) -> (Option<i32>, bool, Option<String>) {
+    sqlx::query_as("SELECT user_id, success, reason FROM login_log WHERE email = $1 ORDER BY id DESC LIMIT 1")
+        .bind(email)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+fn authenticator(policy: LoginPolicy) -> Authenticator {
+    Authenticator::new(ApiKeyStore::default(), JwtVerifier::default().with_hs256_secret(SECRET), false)
+        .with_issuer(Some(TokenIssuer::hs256(SECRET)))
+        .with_login_policy(policy)
+}
+
+async fn try_login(
+    pool: &PgPool,
+    authenticator: &Authenticator,
+    access: &AccessControl,
+    email: &str,
+    passwd: &str,
+) -> Result<Vec<String>, StatusCode> {
+    let request = LoginRequest { email: email.to_string(), passwd: passwd.to_string() };
+    let result = login(
+        State(authenticator.clone()),
+        State(access.clone()),
+        Extension(pool.clone()),
+        ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))),
+        HeaderMap::new(),
+        Json(request),
+    )
+    .await;
+    match result {
+        Ok(Json(tokens)) => Ok(authenticator.jwt().verify(&tokens.access_token).unwrap().roles),
+        Err(e) => Err(e.status_code),
+    }
+}
+
+#[tokio::test]
+async fn test_lockout_threshold_and_expiry() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let (id, email) = create_user(&pool, "correct horse").await;
+    let policy = LoginPolicy { max_failures: 3, lockout_secs: 60.0 };
+
+    // 한도 직전까지는 잠그지 않는다
+    assert!(!register_failure(&pool, id, &policy).await.unwrap());
+    assert!(!register_failure(&pool, id, &policy).await.unwrap());
+    assert!(!find_user(&pool, &email).await.unwrap().unwrap().locked);
+    assert!(register_failure(&pool, id, &policy).await.unwrap());
+    assert!(find_user(&pool, &email).await.unwrap().unwrap().locked);
+
+    // 잠금 시간이 지나면 풀리고 다음 실패는 1 부터 다시 센다
+    sqlx::query("UPDATE users SET locked_until = NOW() - INTERVAL '1 second' WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(!find_user(&pool, &email).await.unwrap().unwrap().locked);
+    assert!(!register_failure(&pool, id, &policy).await.unwrap());
+    let (failed,): (i32,) = sqlx::query_as("SELECT failed_logins FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(failed, 1);
+
+    delete_user(&pool, &email).await;
+}
+
+#[tokio::test]
+async fn test_login_records_attempts_and_locks() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let (id, email) = create_user(&pool, "correct horse").await;
+    let authenticator = authenticator(LoginPolicy { max_failures: 2, lockout_secs: 60.0 });
+    let access = AccessControl::new();
+    access
+        .grant(CreateGrantRequest { subject: Subject::User(email.clone()), role: Role::Analyst, connection_id: None }, "test")
+        .await;
+
+    // 성공하면 전역 권한이 토큰의 roles 에 들어간다
+    assert_eq!(try_login(&pool, &authenticator, &access, &email, "correct horse").await, Ok(vec!["analyst".into()]));
+    assert_eq!(last_attempt(&pool, &email).await, (Some(id), true, None));
+
+    assert_eq!(try_login(&pool, &authenticator, &access, &email, "wrong").await, Err(StatusCode::UNAUTHORIZED));
+    assert_eq!(last_attempt(&pool, &email).await, (Some(id), false, Some("bad_password".into())));
+    assert_eq!(try_login(&pool, &authenticator, &access, &email, "wrong").await, Err(StatusCode::UNAUTHORIZED));
+    assert_eq!(last_attempt(&pool, &email).await, (Some(id), false, Some("locked_now".into())));
+
+    // 잠긴 동안에는 맞는 비밀번호도 거부한다
+    assert_eq!(try_login(&pool, &authenticator, &access, &email, "correct horse").await, Err(StatusCode::LOCKED));
+    assert_eq!(last_attempt(&pool, &email).await, (Some(id), false, Some("locked".into())));
+
+    // 없는 사용자도 같은 401 을 받고 user_id 없이 기록된다
+    let unknown = format!("unknown-{}", email);
+    assert_eq!(try_login(&pool, &authenticator, &access, &unknown, "correct horse").await, Err(StatusCode::UNAUTHORIZED));
+    assert_eq!(last_attempt(&pool, &unknown).await, (None, false, Some("unknown_user".into())));
+
+    delete_user(&pool, &unknown).await;
+    delete_user(&pool, &email).await;
+}
@@ -3,7 +3,6 @@ use axum_ex::db::{
     connection_manager::ConnectionManager,
     types::{ConnectionInfo, DatabaseType, PoolOptions},
 };
-use std::sync::Arc;
 
 #[tokio::test]
 async fn test_mysql_connection() {
@@ -15,6 +14,8 @@ async fn test_mysql_connection() {
         username: None,
         password: None,
         pool_options: PoolOptions::default(),
+        read_only: false,
+        policy: None,
     };
 
     // Test connection creation
@@ -1,5 +1,5 @@
 use axum_ex::db::{
-    connection::{ConnectionConfig, DatabaseConnection, Connection},
+    connection::Connection,
     connection_manager::ConnectionManager,
     types::{ConnectionInfo, DatabaseType, PoolOptions},
 };
@@ -21,6 +21,8 @@ fn get_oracle_connection_info() -> ConnectionInfo {
         username: Some(username),
         password: Some(password),
         pool_options: PoolOptions::default(),
+        read_only: false,
+        policy: None,
     }
 }
 
@@ -96,7 +98,7 @@ async fn test_oracle_query_execution_integration() {
 #[tokio::test]
 async fn test_oracle_connection_pool_integration() {
     let manager = ConnectionManager::new();
-    let connection_info = get_oracle_connection_info();
+    let mut connection_info = get_oracle_connection_info();
 
     let pool_options = PoolOptions {
         max_connections: 10,
@@ -105,6 +107,7 @@ async fn test_oracle_connection_pool_integration() {
         idle_timeout_seconds: 600,
         max_lifetime_seconds: 3600,
     };
+    connection_info.pool_options = pool_options;
 
     let result = manager.add_connection(connection_info).await;
     if let Err(e) = &result {
@@ -14,6 +14,8 @@ async fn test_postgres_connection() {
         username: None,
         password: None,
         pool_options: PoolOptions::default(),
+        read_only: false,
+        policy: None,
     };
 
     // Test connection creation